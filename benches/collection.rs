@@ -0,0 +1,38 @@
+//! `cargo bench --bench collection`: import collection on a large generated module.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+/// A 50k-line module: imports at the top and between functions, like generated code.
+fn synthetic_module(lines: usize) -> String {
+    let mut source = String::with_capacity(lines * 24);
+    for i in 0..200 {
+        source.push_str(&format!("from bench.pkg{} import name{}\n", i % 20, i));
+    }
+    let mut written = 200;
+    let mut i = 0;
+    while written < lines {
+        if i % 50 == 0 {
+            source.push_str(&format!("import bench.generated.mod{}\n", i));
+            written += 1;
+        }
+        source.push_str(&format!("def f{}(x):\n    return x + {}\n\n", i, i));
+        written += 3;
+        i += 1;
+    }
+    source
+}
+
+fn collect_imports(c: &mut Criterion) {
+    let source = synthetic_module(50_000);
+    let mut group = c.benchmark_group("get_file_imports");
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.sample_size(20);
+    group.bench_function("50k_lines", |b| {
+        b.iter(|| importee::bench::collect_imports(black_box(&source)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, collect_imports);
+criterion_main!(benches);
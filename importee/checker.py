@@ -2,8 +2,9 @@ from __future__ import annotations
 
 import json
 import pathlib
+import sys
 from dataclasses import dataclass
-from typing import Any, Dict, List
+from typing import Any, Callable, Dict, List, Optional
 
 from .config import ImporteeConfig
 
@@ -14,6 +15,12 @@ class Issue:
     path: pathlib.Path
     line: int
     message: str
+    severity: str = "error"
+    column: int = 0
+    author: Optional[str] = None
+    commit: Optional[str] = None
+    # {"range": {"start_line", "start_column", "end_line", "end_column"}, "replacement"}
+    fix: Optional[Dict[str, Any]] = None
 
     def __str__(self) -> str:  # pragma: no cover - trivial
         return f"{self.path}:{self.line}: {self.message}"
@@ -30,6 +37,34 @@ def _coerce_str_list(val: Any) -> List[str]:
     return []
 
 
+def _coerce_bool(val: Any) -> bool:
+    if isinstance(val, str):
+        # ini values arrive as strings
+        return val.strip().lower() in ("1", "true", "yes", "on")
+    return bool(val)
+
+
+# Boolean [tool.importee] options forwarded as-is to the Rust run config
+_RUN_FLAGS = (
+    "namespace_packages",
+    "src_layout",
+    "include_stubs",
+    "warn_cache_write_failures",
+    "blame",
+    "report_unresolved",
+    "report_syntax_errors",
+    "profile",
+    "follow_symlinks",
+    "strict_config",
+    "resolve_reexports",
+    "respect_gitignore",
+    "fail_fast",
+    "detect_dynamic_imports",
+    "one_per_file",
+    "ndjson_stream",
+)
+
+
 def _build_run_config(
     options: Dict[str, Any], verbose: bool, quiet: bool, no_cache: bool
 ) -> Dict[str, Any]:
@@ -41,14 +76,47 @@ def _build_run_config(
     cfg_no_cache = bool(options.get("no_cache")) if isinstance(options, dict) else False
     if no_cache or cfg_no_cache:
         run_cfg["no_cache"] = True
+    if isinstance(options, dict):
+        for key in _RUN_FLAGS:
+            if key in options:
+                run_cfg[key] = _coerce_bool(options[key])
+        if options.get("cache_dir"):
+            run_cfg["cache_dir"] = str(options["cache_dir"])
+        if options.get("relative_to"):
+            run_cfg["relative_to"] = str(options["relative_to"])
+        if options.get("jobs"):
+            run_cfg["jobs"] = int(options["jobs"])
+        if options.get("debug_report"):
+            run_cfg["debug_report"] = str(options["debug_report"])
+        budgets = options.get("dir_budgets")
+        if isinstance(budgets, dict):
+            run_cfg["dir_budgets"] = [[str(k), int(v)] for k, v in budgets.items()]
+        extensions = options.get("extensions")
+        if isinstance(extensions, list):
+            run_cfg["extensions"] = [str(x) for x in extensions]
+        disabled_rules = options.get("disabled_rules")
+        if isinstance(disabled_rules, list):
+            run_cfg["disabled_rules"] = [str(x) for x in disabled_rules]
     return run_cfg
 
 
+def _linear_rule(item: Dict[str, Any]) -> Dict[str, Any]:
+    rule: Dict[str, Any] = {"order": _coerce_str_list(item.get("order"))}
+    # Remaining keys (source_module, severity, ...) are passed through unchanged
+    for key, value in item.items():
+        if key != "order" and value is not None:
+            rule[key] = value
+    return rule
+
+
 def run_check(
     config: ImporteeConfig,
     verbose: bool = False,
     quiet: bool = False,
     no_cache: bool = False,
+    changed_since: Optional[str] = None,
+    progress: Optional[Callable[[int, int], None]] = None,
+    disabled_rules: Optional[List[str]] = None,
 ) -> List[Issue]:
     # Defer heavy lifting to Rust extension
     try:
@@ -70,43 +138,90 @@ def run_check(
     if isinstance(linear_opt, list):
         for item in linear_opt:
             if isinstance(item, dict):
-                order = _coerce_str_list(item.get("order"))
-                src = item.get("source_module")
-                linear_rules.append(
-                    {"order": order, "source_module": src}
-                    if src is not None
-                    else {"order": order}
-                )
+                linear_rules.append(_linear_rule(item))
     elif isinstance(linear_opt, dict):
-        order = _coerce_str_list(linear_opt.get("order"))
-        src = linear_opt.get("source_module")
-        linear_rules.append(
-            {"order": order, "source_module": src}
-            if src is not None
-            else {"order": order}
-        )
+        linear_rules.append(_linear_rule(linear_opt))
+
+    rules_cfg: Dict[str, Any] = {"linear": linear_rules}
+    # Other rule tables are passed through; a single table becomes a one-item list
+    if isinstance(rules, dict):
+        for key, value in rules.items():
+            if key == "linear":
+                continue
+            if isinstance(value, dict):
+                rules_cfg[key] = [value]
+            elif isinstance(value, list):
+                rules_cfg[key] = [item for item in value if isinstance(item, dict)]
 
     project_cfg = {
         "source_modules": source_modules,
-        "rules": {"linear": linear_rules},
+        "rules": rules_cfg,
         "exclude": exclude,
         # Note: project_root is dynamically determined by Rust code from file paths
     }
+    if "test_fixtures" in config.options:
+        fixtures = config.options.get("test_fixtures")
+        if not isinstance(fixtures, list):
+            fixtures = []
+        project_cfg["test_fixtures"] = [str(x) for x in fixtures]
+    include = config.options.get("include")
+    if isinstance(include, list):
+        project_cfg["include"] = [str(x) for x in include]
+    extra_roots = config.options.get("extra_source_roots")
+    if isinstance(extra_roots, list):
+        project_cfg["extra_source_roots"] = [str(x) for x in extra_roots]
+    ignore = config.options.get("ignore")
+    if isinstance(ignore, list):
+        project_cfg["ignore"] = [item for item in ignore if isinstance(item, dict)]
 
     run_cfg = _build_run_config(config.options, verbose, quiet, no_cache)
-
-    result_json = _rust.check_imports(json.dumps(project_cfg), json.dumps(run_cfg))
+    if changed_since:
+        run_cfg["changed_since"] = changed_since
+    if disabled_rules:
+        # Added to the rules disabled in the config
+        configured = run_cfg.get("disabled_rules", [])
+        run_cfg["disabled_rules"] = configured + list(disabled_rules)
+
+    if progress is not None:
+        # Called as progress(files_done, files_total) after each checked file
+        result_json = _rust.check_imports_with_progress(
+            json.dumps(project_cfg), json.dumps(run_cfg), progress
+        )
+    else:
+        result_json = _rust.check_imports(json.dumps(project_cfg), json.dumps(run_cfg))
     # The Rust currently prints diagnostics and returns an empty issues list
     try:
         payload = json.loads(result_json)
     except Exception:
         return []
 
+    if not quiet:
+        for diagnostic in payload.get("diagnostics", []):
+            print(diagnostic, file=sys.stderr)
+        timings = payload.get("timings")
+        if isinstance(timings, dict):
+            phases = ", ".join(f"{k[:-3]} {v:.1f} ms" for k, v in timings.items())
+            print(f"[profile] {phases}", file=sys.stderr)
+
     issues: List[Issue] = []
     for item in payload.get("issues", []):
         path = pathlib.Path(item.get("path", "."))
         rule_name = item.get("rule_name", "")
         line = int(item.get("line", 0))
         msg = str(item.get("message", ""))
-        issues.append(Issue(rule_name, path, line, msg))
+        severity = str(item.get("severity", "error"))
+        column = int(item.get("column", 0))
+        issues.append(
+            Issue(
+                rule_name,
+                path,
+                line,
+                msg,
+                severity,
+                column,
+                author=item.get("author"),
+                commit=item.get("commit"),
+                fix=item.get("fix"),
+            )
+        )
     return issues
@@ -13,6 +13,8 @@ def check_return_status(
     quiet: bool,
     verbose: bool,
     no_cache: bool,
+    changed_since: str | None = None,
+    disabled_rules: tuple[str, ...] = (),
 ) -> bool:
     """Programmatic API: run check and return True if no issues, False otherwise.
 
@@ -22,7 +24,14 @@ def check_return_status(
 
     start = time.perf_counter()
     cfg = discover_config(target_path, config)  # type: ignore[arg-type]
-    issues = run_check(cfg, verbose=verbose, quiet=quiet, no_cache=no_cache)
+    issues = run_check(
+        cfg,
+        verbose=verbose,
+        quiet=quiet,
+        no_cache=no_cache,
+        changed_since=changed_since,
+        disabled_rules=list(disabled_rules),
+    )
     elapsed = time.perf_counter() - start
     had_issues = bool(issues)
     if had_issues:
@@ -87,6 +96,18 @@ def cli() -> None:
     is_flag=True,
     help="Disable import cache",
 )
+@click.option(
+    "--changed-since",
+    metavar="REF",
+    help="Only check files changed relative to this git ref, or untracked",
+)
+@click.option(
+    "--disable",
+    "disabled_rules",
+    metavar="RULE",
+    multiple=True,
+    help="Do not run this rule (e.g. Linear); can be repeated",
+)
 @click.option(
     "--no-exit",
     is_flag=True,
@@ -98,12 +119,16 @@ def check_cmd(
     quiet: bool,
     verbose: bool,
     no_cache: bool,
+    changed_since: str | None,
+    disabled_rules: tuple[str, ...],
     no_exit: bool,
 ) -> None:
     """Scan a directory for invalid imports."""
     if verbose and quiet:
         raise click.UsageError("--quiet and --verbose are mutually exclusive")
-    ok = check_return_status(target_path, config, quiet, verbose, no_cache)
+    ok = check_return_status(
+        target_path, config, quiet, verbose, no_cache, changed_since, disabled_rules
+    )
     if not ok and not no_exit:
         sys.exit(1)
 
@@ -0,0 +1,133 @@
+//! Attribute issues to the author and commit of the offending import line.
+
+use std::collections::{BTreeMap, HashMap};
+use std::process::Command;
+
+use crate::results::Issue;
+
+/// Author and commit of a committed line.
+struct LineOrigin {
+    commit: String,
+    author: String,
+}
+
+/// Fill `author`/`commit` on issues, running `git blame` once per file. Lines that are not
+/// committed yet, files outside a git repository and file-level issues (line 0) stay unset.
+pub fn annotate(issues: &mut [Issue]) {
+    let mut by_path: BTreeMap<String, Vec<&mut Issue>> = BTreeMap::new();
+    for issue in issues.iter_mut().filter(|issue| issue.line > 0) {
+        by_path.entry(issue.path.clone()).or_default().push(issue);
+    }
+    for (path, issues) in by_path {
+        let origins = match blame_file(&path) {
+            Some(origins) => origins,
+            None => continue,
+        };
+        for issue in issues {
+            if let Some(origin) = origins.get(&issue.line) {
+                issue.author = Some(origin.author.clone());
+                issue.commit = Some(origin.commit.clone());
+            }
+        }
+    }
+}
+
+/// Origins of every committed line of a file, keyed by (1-based) line number.
+fn blame_file(path: &str) -> Option<HashMap<u32, LineOrigin>> {
+    let output = Command::new("git")
+        .args(["blame", "--line-porcelain", "--", path])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_line_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `git blame --line-porcelain`: every line gets a `<sha> <orig> <final> [<count>]`
+/// header followed by its full set of `key value` headers and a tab-prefixed content line.
+fn parse_line_porcelain(output: &str) -> HashMap<u32, LineOrigin> {
+    let mut origins = HashMap::new();
+    let mut current: Option<(u32, String)> = None;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            current = None;
+            continue;
+        }
+        match current.as_mut() {
+            None => {
+                let mut parts = line.split(' ');
+                let sha = parts.next().unwrap_or_default();
+                let final_line = parts.nth(1).and_then(|n| n.parse::<u32>().ok());
+                if let Some(final_line) = final_line {
+                    current = Some((final_line, sha.to_string()));
+                }
+            }
+            Some((final_line, sha)) => {
+                // Uncommitted lines are blamed on the all-zero commit
+                if let Some(author) = line.strip_prefix("author ") {
+                    if sha.bytes().any(|b| b != b'0') {
+                        origins.insert(
+                            *final_line,
+                            LineOrigin {
+                                commit: sha.clone(),
+                                author: author.to_string(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+    origins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::annotate;
+    use crate::results::{Issue, Severity};
+    use crate::test_support::TempProject;
+
+    fn issue(line: u32) -> Issue {
+        Issue {
+            rule_name: String::from("Linear"),
+            severity: Severity::Error,
+            path: String::from("app/a.py"),
+            line,
+            column: 0,
+            message: String::from("bad import"),
+            author: None,
+            commit: None,
+            fix: None,
+        }
+    }
+
+    #[test]
+    fn committed_lines_get_author_and_commit() {
+        let project = TempProject::new();
+        project.write("app/a.py", "import app.b\n");
+        project.git(&["init", "-q"]);
+        project.git(&["add", "."]);
+        project.git(&["commit", "-q", "-m", "init"]);
+        project.write("app/a.py", "import app.b\nimport app.c\n");
+
+        let mut issues = vec![issue(1), issue(2), issue(0)];
+        annotate(&mut issues);
+        assert_eq!(issues[0].author.as_deref(), Some("Test Author"));
+        assert_eq!(issues[0].commit.as_ref().map(|c| c.len()), Some(40));
+        // Uncommitted and file-level issues are left alone
+        assert_eq!(issues[1].author, None);
+        assert_eq!(issues[2].author, None);
+    }
+
+    #[test]
+    fn outside_git_nothing_is_attached() {
+        let project = TempProject::new();
+        project.write("app/a.py", "import app.b\n");
+        let mut issues = vec![issue(1)];
+        annotate(&mut issues);
+        assert_eq!(issues[0].commit, None);
+    }
+}
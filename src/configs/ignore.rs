@@ -0,0 +1,98 @@
+//! Project-level `ignore` entries: suppressions kept in the configuration instead of inline
+//! `# importee: ignore` comments.
+
+use std::path::Path;
+
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+
+use crate::module_path::ModulePath;
+
+/// One `[[tool.importee.ignore]]` table as users write it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IgnoreEntry {
+    /// Glob pattern of the files whose issues are dropped, e.g. `myapp/legacy/*.py`
+    pub path: String,
+    /// Only drop issues of this rule (as reported, e.g. "Linear")
+    #[serde(default)]
+    pub rule: Option<String>,
+    /// Only drop issues about imports of this module or its submodules
+    #[serde(default)]
+    pub target: Option<ModulePath>,
+}
+
+#[derive(Debug)]
+struct CompiledIgnore {
+    path: GlobMatcher,
+    rule: Option<String>,
+    target: Option<ModulePath>,
+}
+
+/// `ignore` entries compiled once per run.
+#[derive(Debug, Default)]
+pub struct IgnoreList {
+    entries: Vec<CompiledIgnore>,
+}
+
+impl IgnoreList {
+    /// Entries with an invalid `path` pattern are skipped (and reported in verbose mode).
+    pub fn new(entries: &[IgnoreEntry], verbose: bool) -> Self {
+        let entries = entries
+            .iter()
+            .filter_map(|entry| match Glob::new(&entry.path) {
+                Ok(glob) => Some(CompiledIgnore {
+                    path: glob.compile_matcher(),
+                    rule: entry.rule.clone(),
+                    target: entry.target.clone(),
+                }),
+                Err(e) => {
+                    if verbose {
+                        eprintln!("[core] invalid ignore pattern '{}': {}", entry.path, e);
+                    }
+                    None
+                }
+            })
+            .collect();
+        IgnoreList { entries }
+    }
+
+    /// Whether an issue of `rule` in `file` is dropped. `target` is the imported module the
+    /// issue is about; issues about the whole file only match entries without a `target`.
+    pub fn suppresses(&self, file: &Path, rule: &str, target: Option<&ModulePath>) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.path.is_match(file)
+                && entry.rule.as_deref().is_none_or(|name| name == rule)
+                && match (&entry.target, target) {
+                    (None, _) => true,
+                    (Some(ignored), Some(target)) => target.starts_with(ignored),
+                    (Some(_), None) => false,
+                }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IgnoreEntry, IgnoreList};
+    use crate::module_path::ModulePath;
+    use std::path::Path;
+
+    #[test]
+    fn entries_match_by_path_rule_and_target() {
+        let entries: Vec<IgnoreEntry> = serde_json::from_value(serde_json::json!([
+            {"path": "app/legacy/*.py"},
+            {"path": "app/api.py", "rule": "Linear", "target": "app.db"},
+        ]))
+        .unwrap();
+        let ignores = IgnoreList::new(&entries, false);
+        let db = ModulePath::from_dotted("app.db.session");
+        let models = ModulePath::from_dotted("app.models");
+
+        assert!(ignores.suppresses(Path::new("app/legacy/old.py"), "Forbidden", None));
+        assert!(ignores.suppresses(Path::new("app/api.py"), "Linear", Some(&db)));
+        assert!(!ignores.suppresses(Path::new("app/api.py"), "Layered", Some(&db)));
+        assert!(!ignores.suppresses(Path::new("app/api.py"), "Linear", Some(&models)));
+        assert!(!ignores.suppresses(Path::new("app/api.py"), "Linear", None));
+        assert!(!ignores.suppresses(Path::new("app/other.py"), "Linear", Some(&db)));
+    }
+}
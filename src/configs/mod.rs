@@ -1,5 +1,12 @@
+mod ignore;
+mod overrides;
 mod project;
+mod pyproject;
 mod run;
 
-pub use self::project::ProjectConfig;
+pub use self::ignore::{IgnoreEntry, IgnoreList};
+pub use self::overrides::DirOverrides;
+pub use self::project::{
+    BoundaryDirection, ImportStyle, ProjectConfig, ProjectRulesConfig, RuleOptions,
+};
 pub use self::run::RunConfig;
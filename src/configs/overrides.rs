@@ -0,0 +1,138 @@
+//! Per-directory `.importee.toml` files letting a subtree relax the project configuration.
+//! The nearest file above a checked file applies, and takes precedence over the project
+//! config: it can exclude more files and disable rules by name.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+/// Name of the override file looked up in each directory.
+pub const OVERRIDE_FILE: &str = ".importee.toml";
+
+/// `.importee.toml` as users write it.
+#[derive(Deserialize, Default)]
+struct OverrideFile {
+    /// Glob patterns, relative to the file's directory, of files not to check
+    #[serde(default)]
+    exclude: Vec<String>,
+    /// Names of rules (as reported in issues, e.g. "Linear") not to run
+    #[serde(default)]
+    disable: Vec<String>,
+}
+
+/// The override governing a directory.
+#[derive(Debug)]
+pub struct DirOverride {
+    /// Directory holding the override file
+    dir: PathBuf,
+    exclude: Option<GlobSet>,
+    disable: Vec<String>,
+}
+
+impl DirOverride {
+    /// Whether `file` matches one of the override's `exclude` patterns.
+    pub fn excludes(&self, file: &Path) -> bool {
+        let rel = file.strip_prefix(&self.dir).unwrap_or(file);
+        self.exclude.as_ref().is_some_and(|set| set.is_match(rel))
+    }
+
+    /// Whether the rule named `rule` is disabled.
+    pub fn disables(&self, rule: &str) -> bool {
+        self.disable.iter().any(|name| name == rule)
+    }
+}
+
+/// Nearest override per directory, shared by every thread of a run so each file is read once.
+#[derive(Debug, Default)]
+pub struct DirOverrides {
+    nearest: DashMap<PathBuf, Option<Arc<DirOverride>>>,
+}
+
+impl DirOverrides {
+    /// The override file in `dir` or its closest ancestor, if any. Paths are cwd-relative,
+    /// so the lookup stops at the cwd for relative `dir`s.
+    pub fn nearest(&self, dir: &Path, verbose: bool) -> Option<Arc<DirOverride>> {
+        if let Some(found) = self.nearest.get(dir) {
+            return found.clone();
+        }
+        let found = match read_override(dir, verbose) {
+            Some(found) => Some(Arc::new(found)),
+            None => dir
+                .parent()
+                .and_then(|parent| self.nearest(parent, verbose)),
+        };
+        self.nearest.insert(dir.to_path_buf(), found.clone());
+        found
+    }
+}
+
+/// Parse `dir/.importee.toml`; unreadable or invalid files are ignored.
+fn read_override(dir: &Path, verbose: bool) -> Option<DirOverride> {
+    let path = dir.join(OVERRIDE_FILE);
+    let text = fs::read_to_string(&path).ok()?;
+    let file: OverrideFile = match toml::from_str(&text) {
+        Ok(file) => file,
+        Err(e) => {
+            if verbose {
+                eprintln!("[core] ignoring invalid {}: {}", path.to_string_lossy(), e);
+            }
+            return None;
+        }
+    };
+    let mut exclude = GlobSetBuilder::new();
+    for pattern in file.exclude.iter() {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                exclude.add(glob);
+            }
+            Err(e) => {
+                if verbose {
+                    eprintln!(
+                        "[core] invalid exclude pattern '{}' in {}: {}",
+                        pattern,
+                        path.to_string_lossy(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+    Some(DirOverride {
+        dir: dir.to_path_buf(),
+        exclude: exclude.build().ok().filter(|set| !set.is_empty()),
+        disable: file.disable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DirOverrides;
+    use crate::test_support::TempProject;
+    use std::path::Path;
+
+    #[test]
+    fn nearest_override_applies_to_nested_directories() {
+        let project = TempProject::new();
+        project.write(
+            "app/legacy/.importee.toml",
+            "exclude = [\"gen_*.py\"]\ndisable = [\"Linear\"]\n",
+        );
+        let overrides = DirOverrides::default();
+
+        let found = overrides
+            .nearest(Path::new("app/legacy/deep"), false)
+            .unwrap();
+        assert!(found.disables("Linear"));
+        assert!(!found.disables("Forbidden"));
+        assert!(found.excludes(Path::new("app/legacy/gen_models.py")));
+        assert!(!found.excludes(Path::new("app/legacy/models.py")));
+        // Cached for the directory and the ones walked through
+        assert!(overrides.nearest.contains_key(Path::new("app/legacy")));
+
+        assert!(overrides.nearest(Path::new("app/core"), false).is_none());
+    }
+}
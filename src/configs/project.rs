@@ -1,13 +1,44 @@
-#[derive(Deserialize, Debug, Clone, Default)]
+/// Options common to every rule definition, flattened into it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RuleOptions {
+    /// Severity of reported issues (default: error)
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    /// Message of reported issues, with `{rule}`, `{target}`, `{from}`, `{line}` and `{reason}`
+    /// placeholders (default: `imported "{target}" : {reason}`)
+    #[serde(default)]
+    pub message_template: Option<String>,
+    /// Glob patterns of the project-relative files this rule checks (default: all files);
+    /// ignored by rules over the whole dependency graph
+    #[serde(default)]
+    pub applies_to: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct LinearRuleDef {
+    #[serde(default)]
     pub order: Vec<String>,
+    /// Independent ordering chains; order only applies between members of the same group
+    #[serde(default)]
+    pub groups: Vec<Vec<String>>,
+    /// Only allow imports from strictly earlier submodules, not from the same one (default: false)
+    #[serde(default)]
+    pub strict: Option<bool>,
+    /// Skip imports made by package `__init__.py` files, which often import every submodule
+    /// to re-export them (default: false)
+    #[serde(default)]
+    pub ignore_init: Option<bool>,
     #[serde(default)]
     pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
 }
+use crate::configs::IgnoreEntry;
 use crate::module_path::ModulePath;
-use serde::Deserialize;
+use crate::results::Severity;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ProjectConfig {
     /// List of source modules
     pub source_modules: Vec<ModulePath>,
@@ -17,11 +48,394 @@ pub struct ProjectConfig {
     /// List of glob patterns to exclude from checking
     #[serde(default)]
     pub exclude: Vec<String>,
+    /// Glob patterns of the only files to check (default: all); matched against files only,
+    /// directories are still walked
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Glob patterns of test fixture files (default: `**/conftest.py`), only checked by
+    /// dependency rules such as `forbidden`; set to `[]` to check them like any module
+    #[serde(default = "default_test_fixtures")]
+    pub test_fixtures: Vec<String>,
+    /// Other directories holding the source modules' packages, e.g. an editable install's
+    /// checkout; imports found there are local too
+    #[serde(default)]
+    pub extra_source_roots: Vec<String>,
+    /// Issues to drop, by file glob and optionally rule name and imported module
+    #[serde(default)]
+    pub ignore: Vec<IgnoreEntry>,
+}
+
+pub(super) fn default_test_fixtures() -> Vec<String> {
+    vec![String::from("**/conftest.py")]
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LayeredRuleDef {
+    /// Layers from lowest to highest; each layer is a set of submodule names
+    pub layers: Vec<Vec<String>>,
+    /// Module the layers are submodules of (default: the first source module)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IndependenceRuleDef {
+    /// Groups of submodule names; modules of different groups may not import each other
+    pub groups: Vec<Vec<String>>,
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct VersionIsolationRuleDef {
+    /// Package whose direct children are the versions, e.g. "app.api"
+    pub parent: ModulePath,
+    /// Regex a child name must match to count as a version (default `^v\d+$`)
+    #[serde(default)]
+    pub version_pattern: Option<String>,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AllowedExternalsRuleDef {
+    /// Top-level external packages that may be imported
+    #[serde(default)]
+    pub allowed: Vec<String>,
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ForbiddenRuleDef {
+    /// Modules (and their submodules) that may not be imported, local or external
+    pub modules: Vec<ModulePath>,
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InterfaceImplementationRuleDef {
+    /// Only check packages under this module (default: everywhere)
+    #[serde(default)]
+    pub parent: ModulePath,
+    /// Name of the interface package (default: "api")
+    #[serde(default = "default_interface")]
+    pub interface: String,
+    /// Name of the paired implementation package (default: "impl")
+    #[serde(default = "default_implementation")]
+    pub implementation: String,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+fn default_interface() -> String {
+    String::from("api")
+}
+
+fn default_implementation() -> String {
+    String::from("impl")
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransitiveLeafRuleDef {
+    /// Leaf modules; they may depend on each other but on nothing else in the project
+    pub modules: Vec<ModulePath>,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NoCyclesRuleDef {
+    #[serde(flatten)]
+    pub options: RuleOptions,
 }
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReverseAllowlistRuleDef {
+    /// Module only the allowed importers (and its own submodules) may import
+    pub protected: ModulePath,
+    /// Prefixes of the modules allowed to import `protected`
+    #[serde(default)]
+    pub allowed_importers: Vec<ModulePath>,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FanRuleDef {
+    /// Maximum number of distinct local modules a module may import
+    #[serde(default)]
+    pub max_fan_out: Option<usize>,
+    /// Maximum number of distinct modules that may import a module
+    #[serde(default)]
+    pub max_fan_in: Option<usize>,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReservedNamesRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    /// Additional names to treat as reserved on top of the bundled builtins/keywords
+    #[serde(default)]
+    pub extra: Vec<String>,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct VisibilityRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RespectDunderAllRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NoWildcardRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PublicApiRuleDef {
+    /// Packages whose submodules may only be imported through their `__init__.py` from outside
+    pub packages: Vec<ModulePath>,
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CouplingRuleDef {
+    /// Files under this module are checked (default: every file)
+    #[serde(default)]
+    pub from_prefix: ModulePath,
+    /// Package whose distinct imported modules are counted
+    pub to_prefix: ModulePath,
+    /// Most distinct modules of `to_prefix` a file may import
+    pub max: usize,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NoDuplicateImportsRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NoPrivateImportsRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MaxImportDepthRuleDef {
+    /// Deepest allowed import target, in segments below `source_module`
+    pub max_depth: usize,
+    /// Package depth is measured from (default: every segment of the target counts)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+/// Import style enforced by the `no_relative_imports` rule.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStyle {
+    #[default]
+    Absolute,
+    Relative,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NoRelativeImportsRuleDef {
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    /// Style to enforce (default: absolute, i.e. forbid relative imports)
+    #[serde(default)]
+    pub prefer: ImportStyle,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+/// Which imports crossing a `boundary` package are forbidden.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BoundaryDirection {
+    /// Modules outside the package may not import it
+    #[default]
+    Inbound,
+    /// Modules inside the package may not import the rest of the project
+    Outbound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BoundaryRuleDef {
+    /// Packages (module prefixes) imports may not cross into, e.g. `tests`
+    pub forbidden_sources: Vec<ModulePath>,
+    /// Direction of the forbidden imports (default: inbound)
+    #[serde(default)]
+    pub direction: BoundaryDirection,
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NoFirstPartyAliasRuleDef {
+    /// Aliases shorter than this many characters are flagged (default: 3)
+    #[serde(default = "default_min_alias_length")]
+    pub min_length: usize,
+    /// Only check imports made from this module (default: everywhere)
+    #[serde(default)]
+    pub source_module: ModulePath,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+fn default_min_alias_length() -> usize {
+    3
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TestScopeRuleDef {
+    /// Package holding the tests (default: "tests")
+    #[serde(default = "default_tests_module")]
+    pub tests_module: ModulePath,
+    /// Package the tested modules live in, e.g. `app` (default: the top level)
+    #[serde(default)]
+    pub subject_module: ModulePath,
+    /// File name prefix of test files, stripped to name the subject (default: "test_")
+    #[serde(default = "default_test_prefix")]
+    pub test_prefix: String,
+    /// Modules (or `*`/`**` patterns) every test may import, e.g. test utilities
+    #[serde(default)]
+    pub common: Vec<ModulePath>,
+    #[serde(flatten)]
+    pub options: RuleOptions,
+}
+
+fn default_tests_module() -> ModulePath {
+    ModulePath::from_dotted("tests")
+}
+
+fn default_test_prefix() -> String {
+    String::from("test_")
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ProjectRulesConfig {
     /// Multiple linear rules supported
     #[serde(default)]
     pub linear: Vec<LinearRuleDef>,
+    /// Layered architecture rules
+    #[serde(default)]
+    pub layered: Vec<LayeredRuleDef>,
+    /// Keep groups of sibling submodules from importing each other
+    #[serde(default)]
+    pub independence: Vec<IndependenceRuleDef>,
+    /// Only allow listed third-party packages
+    #[serde(default)]
+    pub allowed_externals: Vec<AllowedExternalsRuleDef>,
+    /// Forbid importing the listed modules
+    #[serde(default)]
+    pub forbidden: Vec<ForbiddenRuleDef>,
+    /// Require implementation packages to import their paired interface
+    #[serde(default)]
+    pub interface_implementation: Vec<InterfaceImplementationRuleDef>,
+    /// Forbid imports between versioned sibling packages
+    #[serde(default)]
+    pub version_isolation: Vec<VersionIsolationRuleDef>,
+    /// Keep shared utility modules transitive dependency leaves
+    #[serde(default)]
+    pub transitive_leaf: Vec<TransitiveLeafRuleDef>,
+    /// Cap the fan-in/fan-out of modules
+    #[serde(default)]
+    pub fan: Vec<FanRuleDef>,
+    /// Only let allowlisted modules import a protected module
+    #[serde(default)]
+    pub reverse_allowlist: Vec<ReverseAllowlistRuleDef>,
+    /// Forbid importing local modules named like builtins or soft keywords
+    #[serde(default)]
+    pub reserved_names: Vec<ReservedNamesRuleDef>,
+    /// Enforce `# importee: visibility=...` declarations
+    #[serde(default)]
+    pub visibility: Vec<VisibilityRuleDef>,
+    /// Forbid importing names a module leaves out of its `__all__`
+    #[serde(default)]
+    pub respect_dunder_all: Vec<RespectDunderAllRuleDef>,
+    /// Forbid `from x import *`
+    #[serde(default)]
+    pub no_wildcard: Vec<NoWildcardRuleDef>,
+    /// Enforce absolute (or relative) local imports
+    #[serde(default)]
+    pub no_relative_imports: Vec<NoRelativeImportsRuleDef>,
+    /// Limit how deep imports reach into packages
+    #[serde(default)]
+    pub max_import_depth: Vec<MaxImportDepthRuleDef>,
+    /// Forbid importing other packages' `_private` modules
+    #[serde(default)]
+    pub no_private_imports: Vec<NoPrivateImportsRuleDef>,
+    /// Only allow importing listed packages through their `__init__.py`
+    #[serde(default)]
+    pub public_api: Vec<PublicApiRuleDef>,
+    /// Keep imports from crossing into (or out of) packages such as `tests`
+    #[serde(default)]
+    pub boundary: Vec<BoundaryRuleDef>,
+    /// Keep each test file to the module it tests
+    #[serde(default)]
+    pub test_scope: Vec<TestScopeRuleDef>,
+    /// Forbid short `as` aliases of first-party imports
+    #[serde(default)]
+    pub no_first_party_alias: Vec<NoFirstPartyAliasRuleDef>,
+    /// Forbid importing the same module twice in a file
+    #[serde(default)]
+    pub no_duplicate_imports: Vec<NoDuplicateImportsRuleDef>,
+    /// Limit how many modules of a package a file may import
+    #[serde(default)]
+    pub coupling: Vec<CouplingRuleDef>,
+    /// Forbid import cycles between modules
+    #[serde(default)]
+    pub no_cycles: Vec<NoCyclesRuleDef>,
 }
@@ -0,0 +1,198 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::ignore::IgnoreEntry;
+use super::project::{default_test_fixtures, LinearRuleDef, ProjectConfig, ProjectRulesConfig};
+use crate::module_path::ModulePath;
+
+/// Why a `pyproject.toml` could not be turned into a `ProjectConfig`.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    /// The file has no `[tool.importee]` table
+    MissingTable,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "cannot read pyproject.toml: {}", e),
+            ConfigError::Toml(e) => write!(f, "invalid pyproject.toml: {}", e),
+            ConfigError::MissingTable => write!(f, "no [tool.importee] table in pyproject.toml"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Toml(e)
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct Pyproject {
+    #[serde(default)]
+    tool: Tool,
+}
+
+#[derive(Deserialize, Default)]
+struct Tool {
+    importee: Option<ImporteeTable>,
+}
+
+/// `[tool.importee]` as users write it, before normalization.
+#[derive(Deserialize, Default)]
+struct ImporteeTable {
+    #[serde(default)]
+    source_modules: Vec<ModulePath>,
+    /// Single source module, as read by the Python package
+    source_module: Option<ModulePath>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    extra_source_roots: Vec<String>,
+    #[serde(default)]
+    ignore: Vec<IgnoreEntry>,
+    #[serde(default)]
+    rules: RulesTable,
+}
+
+#[derive(Deserialize, Default)]
+struct RulesTable {
+    linear: Option<OneOrMany<LinearRuleDef>>,
+}
+
+/// A single table (`[tool.importee.rules.linear]`) or an array of tables
+/// (`[[tool.importee.rules.linear]]`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(item) => vec![item],
+            OneOrMany::Many(items) => items,
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Read `[tool.importee]` from a `pyproject.toml`, without going through the Python
+    /// package. Only `source_modules` (or `source_module`), `exclude`, `include`,
+    /// `extra_source_roots`, `ignore` and `rules.linear` are mapped; other options keep their
+    /// defaults.
+    #[allow(dead_code)] // for a standalone binary; the extension gets its config as JSON
+    pub fn from_pyproject(path: &Path) -> Result<ProjectConfig, ConfigError> {
+        let text = fs::read_to_string(path)?;
+        let pyproject: Pyproject = toml::from_str(&text)?;
+        let table = pyproject.tool.importee.ok_or(ConfigError::MissingTable)?;
+
+        let mut source_modules = table.source_modules;
+        source_modules.extend(table.source_module.filter(|module| !module.is_empty()));
+        Ok(ProjectConfig {
+            source_modules,
+            rules: ProjectRulesConfig {
+                linear: table
+                    .rules
+                    .linear
+                    .map(OneOrMany::into_vec)
+                    .unwrap_or_default(),
+                ..ProjectRulesConfig::default()
+            },
+            exclude: table.exclude,
+            include: table.include,
+            test_fixtures: default_test_fixtures(),
+            extra_source_roots: table.extra_source_roots,
+            ignore: table.ignore,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigError;
+    use crate::configs::ProjectConfig;
+    use crate::module_path::ModulePath;
+    use crate::results::Severity;
+    use std::fs;
+
+    const PYPROJECT: &str = r#"
+[project]
+name = "myapp"
+
+[tool.importee]
+source_module = "myapp"
+exclude = ["**/migrations/**"]
+
+[[tool.importee.rules.linear]]
+order = ["models", "services", "api"]
+
+[[tool.importee.rules.linear]]
+source_module = "myapp.api"
+order = ["v1", "v2"]
+severity = "warning"
+"#;
+
+    #[test]
+    fn pyproject_with_two_linear_rules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("pyproject.toml");
+        fs::write(&path, PYPROJECT).unwrap();
+
+        let config = ProjectConfig::from_pyproject(&path).unwrap();
+        assert_eq!(config.source_modules, [ModulePath::from_dotted("myapp")]);
+        assert_eq!(config.exclude, ["**/migrations/**"]);
+        let linear = &config.rules.linear;
+        assert_eq!(linear.len(), 2);
+        assert_eq!(linear[0].order, ["models", "services", "api"]);
+        assert!(linear[0].source_module.is_empty());
+        assert_eq!(linear[0].options.severity, None);
+        assert_eq!(linear[1].order, ["v1", "v2"]);
+        assert_eq!(
+            linear[1].source_module,
+            ModulePath::from_dotted("myapp.api")
+        );
+        assert_eq!(linear[1].options.severity, Some(Severity::Warning));
+        // Options not read from the file keep their defaults
+        assert_eq!(config.test_fixtures, ["**/conftest.py"]);
+    }
+
+    #[test]
+    fn single_linear_table_and_missing_section() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("pyproject.toml");
+        fs::write(
+            &path,
+            "[tool.importee]\nsource_modules = [\"a\", \"b\"]\n\n[tool.importee.rules.linear]\norder = [\"x\", \"y\"]\n",
+        )
+        .unwrap();
+        let config = ProjectConfig::from_pyproject(&path).unwrap();
+        assert_eq!(config.source_modules.len(), 2);
+        assert_eq!(config.rules.linear.len(), 1);
+
+        fs::write(&path, "[project]\nname = \"other\"\n").unwrap();
+        assert!(matches!(
+            ProjectConfig::from_pyproject(&path),
+            Err(ConfigError::MissingTable)
+        ));
+    }
+}
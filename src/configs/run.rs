@@ -4,4 +4,95 @@ use serde::Deserialize;
 pub struct RunConfig {
     pub verbose: Option<bool>,
     pub no_cache: Option<bool>,
+    /// Also check `.pyi` stubs of modules that have no `.py` file
+    pub include_stubs: Option<bool>,
+    /// Extensions (without the dot) of files checked as modules (default: `["py"]`), e.g.
+    /// `pyx` or `py.jinja`. Compiled and binary extensions are always skipped
+    pub extensions: Option<Vec<String>>,
+    /// Treat directories without `__init__.py` as (PEP 420) namespace packages
+    pub namespace_packages: Option<bool>,
+    /// Treat names re-exported by a package's `__init__.py` (`from .impl import Thing`) as
+    /// local modules, so `pkg.Thing` isn't reported as external. Parses each `__init__` once
+    pub resolve_reexports: Option<bool>,
+    /// Also collect `importlib.import_module("pkg.sub")` and `__import__("pkg.sub")` calls
+    /// with a string literal module, anywhere in a file
+    pub detect_dynamic_imports: Option<bool>,
+    /// Resolve top-level packages under `src/` (default: only when `<package>/` is missing)
+    pub src_layout: Option<bool>,
+    /// Store the import cache here instead of `<project_root>/.importee_cache`
+    pub cache_dir: Option<String>,
+    /// Report (once per run) when the import cache could not be written
+    pub warn_cache_write_failures: Option<bool>,
+    /// Only check files changed relative to this git ref, or untracked (project rules still
+    /// see every file)
+    pub changed_since: Option<String>,
+    /// Report imports under a source's root module whose file does not exist (`Unresolved`)
+    pub report_unresolved: Option<bool>,
+    /// Report files that fail to parse as `SyntaxError` issues instead of skipping them
+    pub report_syntax_errors: Option<bool>,
+    /// Attach the git blame author/commit of the import line to each issue
+    pub blame: Option<bool>,
+    /// `[module_prefix, max_issues]` pairs; exceeding one adds a `budget-exceeded` diagnostic
+    #[serde(default)]
+    pub dir_budgets: Vec<(String, usize)>,
+    /// Write a JSON Lines log of every file, import, resolution and rule outcome to this path
+    pub debug_report: Option<String>,
+    /// Report issue paths relative to this directory (e.g. the repository root), so output
+    /// doesn't depend on where the sources were configured from; other paths are kept
+    pub relative_to: Option<String>,
+    /// Skip files and directories ignored by the repository's `.gitignore` files
+    pub respect_gitignore: Option<bool>,
+    /// Walk into symlinked directories (default: skip them); each directory is walked once
+    pub follow_symlinks: Option<bool>,
+    /// Report misconfigured rules (missing source or ordered modules, empty orders) as
+    /// `Config` issues instead of only warning in verbose mode
+    pub strict_config: Option<bool>,
+    /// Names of rules not to run at all (e.g. `["Linear"]`), matched case-insensitively
+    pub disabled_rules: Option<Vec<String>>,
+    /// Keep only the worst issue of each file: highest severity, then lowest line
+    pub one_per_file: Option<bool>,
+    /// Stop checking files once one has an issue, e.g. for a pass/fail pre-commit hook. The
+    /// issues found so far (including those of files already being checked) are returned
+    pub fail_fast: Option<bool>,
+    /// Write each issue to stdout as a JSON line as soon as its file is checked, instead of
+    /// returning it, e.g. to pipe a very large scan. Issues are then unsorted and not blamed
+    pub ndjson_stream: Option<bool>,
+    /// Number of threads checking files (default: one per core, shared with other runs of
+    /// the process), e.g. to avoid oversubscribing shared CI runners
+    pub jobs: Option<usize>,
+    /// Report time spent walking, parsing, checking rules and in the cache (`timings`)
+    pub profile: Option<bool>,
+}
+
+/// Never scanned, even when listed in `extensions`: compiled and native modules.
+const BINARY_EXTENSIONS: &[&str] = &["pyc", "pyo", "pyd", "so", "dll", "dylib"];
+
+impl RunConfig {
+    /// Whether `disabled_rules` lists the rule named `name`.
+    pub fn disables_rule(&self, name: &str) -> bool {
+        self.disabled_rules
+            .iter()
+            .flatten()
+            .any(|disabled| disabled.eq_ignore_ascii_case(name))
+    }
+
+    /// Extensions of module files in lookup order: the configured `extensions` (default `py`)
+    /// without binary ones, then `pyi` with `include_stubs`. A file only counts when no file of
+    /// the same module with an earlier extension exists.
+    pub fn module_extensions(&self) -> Vec<String> {
+        let mut extensions: Vec<String> = match &self.extensions {
+            Some(configured) => configured
+                .iter()
+                .map(|extension| extension.trim_start_matches('.').to_string())
+                .filter(|extension| {
+                    !extension.is_empty() && !BINARY_EXTENSIONS.contains(&extension.as_str())
+                })
+                .collect(),
+            None => vec![String::from("py")],
+        };
+        if self.include_stubs.unwrap_or(false) && !extensions.iter().any(|e| e == "pyi") {
+            extensions.push(String::from("pyi"));
+        }
+        extensions
+    }
 }
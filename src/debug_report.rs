@@ -0,0 +1,135 @@
+//! Optional JSON Lines log of a whole run (`debug_report` run option): every file visited,
+//! the imports found and how they resolved, and every rule outcome. Meant for diagnosing
+//! unexpected results, where interleaved verbose output from rayon threads is hard to follow.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::{Mutex, OnceLock};
+
+use serde_json::Value;
+
+use crate::run_context::RunContext;
+
+/// Lazily opened, buffered report file shared by every thread of a run, or the reason it
+/// could not be created.
+#[derive(Debug, Default)]
+pub struct DebugSink {
+    writer: OnceLock<Result<Mutex<BufWriter<File>>, String>>,
+}
+
+/// Append one entry to the report, if one was requested. `entry` is only built when it is.
+pub fn record<F>(run_config: &RunContext, entry: F)
+where
+    F: FnOnce() -> Value,
+{
+    let path = match run_config.debug_report.as_deref() {
+        Some(path) => path,
+        None => return,
+    };
+    let writer = run_config
+        .debug_sink
+        .writer
+        .get_or_init(|| match File::create(path) {
+            Ok(file) => Ok(Mutex::new(BufWriter::new(file))),
+            Err(e) => Err(format!(
+                "[debug] cannot create debug report {}: {}",
+                path, e
+            )),
+        });
+    if let Ok(writer) = writer {
+        let line = entry().to_string();
+        let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(writer, "{}", line);
+    }
+}
+
+/// Flush buffered entries; called once the run is over. Returns a diagnostic for the
+/// result when the report could not be created.
+pub fn flush(run_config: &RunContext) -> Option<String> {
+    match run_config.debug_sink.writer.get()? {
+        Ok(writer) => {
+            let _ = writer.lock().unwrap_or_else(|e| e.into_inner()).flush();
+            None
+        }
+        Err(diagnostic) => Some(diagnostic.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::test_support::TempProject;
+    use crate::walker::run_check_imports;
+
+    #[test]
+    fn report_has_entries_for_each_processed_file() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/low.py", "import os\nimport app.high\n");
+        project.write("app/high.py", "");
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": ["low", "high"]}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            debug_report: Some(String::from("report.jsonl")),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(config, run).issues;
+        assert_eq!(issues.len(), 1);
+
+        let report = std::fs::read_to_string("report.jsonl").unwrap();
+        let entries: Vec<serde_json::Value> = report
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let mut files: Vec<&str> = entries
+            .iter()
+            .filter(|e| e["event"] == "file")
+            .map(|e| e["module"].as_str().unwrap())
+            .collect();
+        files.sort_unstable();
+        assert_eq!(files, ["app.__init__", "app.high", "app.low"]);
+
+        let imports = entries
+            .iter()
+            .find(|e| e["event"] == "imports" && e["module"] == "app.low")
+            .unwrap();
+        assert_eq!(imports["imports"][0]["target"], "os");
+        assert_eq!(imports["imports"][0]["local"], false);
+
+        let outcome = entries
+            .iter()
+            .find(|e| e["event"] == "rule" && e["module"] == "app.low")
+            .unwrap();
+        assert_eq!(outcome["rule"], "Linear");
+        assert_eq!(outcome["pass"], false);
+    }
+
+    #[test]
+    fn unwritable_report_is_a_diagnostic() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/low.py", "import app.high\n");
+        project.write("app/high.py", "");
+        // A path under a regular file can never be created, even as root
+        project.write("blocker", "");
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": ["low", "high"]}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            debug_report: Some(String::from("blocker/report.jsonl")),
+            ..RunConfig::default()
+        };
+        let diagnostics = run_check_imports(config, run).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics[0].starts_with("[debug] cannot create debug report blocker/report.jsonl")
+        );
+    }
+}
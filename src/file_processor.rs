@@ -1,31 +1,126 @@
-use crate::configs::RunConfig;
-use crate::imports::classification::ImportResolver;
-use crate::imports::collection::get_file_imports;
+use crate::configs::{IgnoreEntry, ProjectConfig, ProjectRulesConfig, RunConfig};
+use crate::imports::classification::{ImportResolver, ModuleKind};
+use crate::imports::collection::{get_file_imports, SyntaxError};
+use crate::imports::encoding::read_source;
+use crate::imports::import_line::{ImportKind, ImportLine};
 use crate::module_path::ModulePath;
-use crate::results::Issue;
-use crate::rules::ImportRule;
+use crate::profile::Phase;
+use crate::results::{Issue, Severity};
+use crate::rules::{render_message, ImportRule, RuleOutcome};
+use crate::run_context::RunContext;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::cmp::Reverse;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 
 #[derive(Serialize, Deserialize)]
 struct CacheEntry {
     #[serde(default = "cache_version_current")]
     version: u8,
     hash: String,
-    // (target_dotted, line_no)
-    imports: Vec<(String, u32)>,
+    /// `config_hash` of the run that wrote the entry
+    config_hash: String,
+    imports: Vec<CachedImport>,
+    /// Issues of the file, present once rules ran over it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    issues: Option<CachedIssues>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedIssues {
+    /// `rules_key` of the rules that produced the issues
+    rules: String,
+    issues: Vec<Issue>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedImport {
+    target: String,
+    line: u32,
+    column: i32,
+    external: bool,
+    #[serde(default)]
+    ignored_rules: Vec<String>,
+    kind: ImportKind,
+    wildcard: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alias: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    names: Vec<String>,
 }
 
 fn cache_version_current() -> u8 {
-    2
+    15
+}
+
+/// Configuration a cache entry depends on: options changing how imports are collected and
+/// resolved, and the rules and `ignore` entries (for results derived from them).
+#[derive(Serialize)]
+struct ConfigKey<'a> {
+    source_modules: &'a [ModulePath],
+    extra_source_roots: &'a [String],
+    rules: &'a ProjectRulesConfig,
+    ignore: &'a [IgnoreEntry],
+    namespace_packages: Option<bool>,
+    src_layout: Option<bool>,
+    module_extensions: Vec<String>,
+    report_unresolved: Option<bool>,
+    report_syntax_errors: Option<bool>,
+    resolve_reexports: Option<bool>,
+    detect_dynamic_imports: Option<bool>,
+}
+
+/// Hash of the `ConfigKey` of a run, serialized canonically. Entries written under another
+/// configuration are not reused.
+pub fn config_hash(project_config: &ProjectConfig, run_config: &RunConfig) -> String {
+    let key = ConfigKey {
+        source_modules: &project_config.source_modules,
+        extra_source_roots: &project_config.extra_source_roots,
+        rules: &project_config.rules,
+        ignore: &project_config.ignore,
+        namespace_packages: run_config.namespace_packages,
+        src_layout: run_config.src_layout,
+        module_extensions: run_config.module_extensions(),
+        report_unresolved: run_config.report_unresolved,
+        report_syntax_errors: run_config.report_syntax_errors,
+        resolve_reexports: run_config.resolve_reexports,
+        detect_dynamic_imports: run_config.detect_dynamic_imports,
+    };
+    // Plain data with string keys, which always serializes
+    let bytes = serde_json::to_vec(&key).unwrap_or_default();
+    compute_hash(&bytes)
+}
+
+/// Checks made by the core rather than by a rule, which directory overrides can disable too.
+const CORE_CHECKS: [&str; 2] = ["SyntaxError", "Unresolved"];
+
+/// Key of the rules applied to one file: directory overrides can disable some of the
+/// configured rules and core checks, so cached issues are only reused for the same set.
+fn rules_key(rules: &[&dyn ImportRule], disabled: impl Fn(&str) -> bool) -> String {
+    let mut described: Vec<String> = rules
+        .iter()
+        .map(|rule| format!("{}:{}", rule.name(), rule.describe()))
+        .collect();
+    described.extend(
+        CORE_CHECKS
+            .iter()
+            .filter(|check| disabled(check))
+            .map(|check| format!("!{}", check)),
+    );
+    compute_hash_from_string(&described.join("|"))
 }
 
 /// Compute hash from file content string (avoids re-reading the file)
 fn compute_hash_from_string(content: &str) -> String {
+    compute_hash(content.as_bytes())
+}
+
+fn compute_hash(bytes: &[u8]) -> String {
     let mut hasher = blake3::Hasher::new();
-    hasher.update(content.as_bytes());
+    hasher.update(bytes);
     hasher.finalize().to_hex().to_string()
 }
 
@@ -45,78 +140,129 @@ fn find_project_root(start: &Path) -> PathBuf {
     }
 }
 
-fn cache_file_path(resolver: &ImportResolver, module_path: &ModulePath) -> PathBuf {
-    let project_root = find_project_root(resolver.root_dir());
-    let cache_root = project_root.join(".importee_cache");
-    let rel_file = module_path.file_path();
-    let mut cache_path = cache_root.join(rel_file);
-    cache_path.set_extension("imports.json");
-    cache_path
+fn cache_root(resolver: &ImportResolver, run_config: &RunConfig) -> PathBuf {
+    match run_config.cache_dir.as_deref() {
+        Some(dir) => PathBuf::from(dir),
+        None => find_project_root(resolver.root_dir()).join(".importee_cache"),
+    }
+}
+
+/// Path of a module's entry relative to the cache root, always with `/` separators so a
+/// cache has the same layout on every platform (e.g. `a/b/c.imports.json`).
+fn cache_key(module_path: &ModulePath) -> String {
+    format!("{}.imports.json", module_path.segments().join("/"))
+}
+
+fn cache_file_path(
+    resolver: &ImportResolver,
+    run_config: &RunContext,
+    module_path: &ModulePath,
+) -> PathBuf {
+    cache_root(resolver, run_config).join(cache_key(module_path))
 }
 
-fn try_load_cache(
+fn load_cache_entry(
     resolver: &ImportResolver,
+    run_config: &RunContext,
     module_path: &ModulePath,
     hash: &str,
-) -> Option<Vec<crate::imports::import_line::ImportLine>> {
-    let path = cache_file_path(resolver, module_path);
+) -> Option<CacheEntry> {
+    let path = cache_file_path(resolver, run_config, module_path);
     let data = fs::read_to_string(path).ok()?;
     let entry: CacheEntry = serde_json::from_str(&data).ok()?;
-    // Invalidate old cache formats (without line numbers)
-    if entry.version < 2 {
+    // Invalidate other cache formats (e.g. without line/column numbers, externals,
+    // suppressions, config hash, import kind, wildcard flag, issues, their fixes, aliases or
+    // imported names), and entries collected with older rules (multi-level relative imports
+    // resolved one level up, ignores on the closing line of an import missed)
+    if entry.version != cache_version_current() {
         return None;
     }
-    if entry.hash != hash {
+    if entry.hash != hash || entry.config_hash != run_config.config_hash {
         return None;
     }
-    let mut out = Vec::with_capacity(entry.imports.len());
-    for (target_dotted, line) in entry.imports.into_iter() {
-        out.push(crate::imports::import_line::ImportLine {
+    Some(entry)
+}
+
+fn cached_imports(module_path: &ModulePath, cached: Vec<CachedImport>) -> Vec<ImportLine> {
+    cached
+        .into_iter()
+        .map(|cached| ImportLine {
             from_module: module_path.clone(),
-            target_module: ModulePath::from_dotted(&target_dotted),
-            import_line: line,
-        });
-    }
-    Some(out)
+            target_module: ModulePath::from_dotted(&cached.target),
+            import_line: cached.line,
+            column: cached.column,
+            external: cached.external,
+            ignored_rules: cached.ignored_rules,
+            kind: cached.kind,
+            wildcard: cached.wildcard,
+            alias: cached.alias,
+            names: cached.names,
+        })
+        .collect()
 }
 
-fn ensure_cache_dir(cache_root: &Path) {
+fn ensure_cache_dir(cache_root: &Path) -> io::Result<()> {
     // Create cache directory if it doesn't exist
-    let _ = fs::create_dir_all(cache_root);
+    fs::create_dir_all(cache_root)?;
 
     // Ensure .gitignore exists with * to ignore all cache files
     let gitignore_path = cache_root.join(".gitignore");
     if !gitignore_path.exists() {
-        let _ = fs::write(gitignore_path, "*\n");
+        fs::write(gitignore_path, "*\n")?;
     }
+    Ok(())
 }
 
 fn save_cache(
     resolver: &ImportResolver,
+    run_config: &RunContext,
     module_path: &ModulePath,
     hash: &str,
-    imports: &[crate::imports::import_line::ImportLine],
-) {
-    let path = cache_file_path(resolver, module_path);
+    imports: &[ImportLine],
+    issues: Option<CachedIssues>,
+) -> io::Result<()> {
+    // Ensure cache root directory has .gitignore
+    ensure_cache_dir(&cache_root(resolver, run_config))?;
+
+    let path = cache_file_path(resolver, run_config, module_path);
     if let Some(parent) = path.parent() {
-        let _ = fs::create_dir_all(parent);
+        fs::create_dir_all(parent)?;
     }
-
-    // Ensure cache root directory has .gitignore
-    let project_root = find_project_root(resolver.root_dir());
-    let cache_root = project_root.join(".importee_cache");
-    ensure_cache_dir(&cache_root);
-    let flat: Vec<(String, u32)> = imports
+    let flat: Vec<CachedImport> = imports
         .iter()
-        .map(|imp| (imp.target_module.to_dotted(), imp.import_line))
+        .map(|imp| CachedImport {
+            target: imp.target_module.to_dotted(),
+            line: imp.import_line,
+            column: imp.column,
+            external: imp.external,
+            ignored_rules: imp.ignored_rules.clone(),
+            kind: imp.kind,
+            wildcard: imp.wildcard,
+            alias: imp.alias.clone(),
+            names: imp.names.clone(),
+        })
         .collect();
     let entry = CacheEntry {
         version: cache_version_current(),
         hash: hash.to_string(),
+        config_hash: run_config.config_hash.clone(),
         imports: flat,
+        issues,
     };
-    if let Ok(json) = serde_json::to_string(&entry) {
-        let _ = fs::write(path, json);
+    let json = serde_json::to_string(&entry).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+/// Diagnostic to report once per run when cache writes failed and the user asked to be told.
+pub fn cache_write_diagnostic(run_config: &RunContext) -> Option<String> {
+    if run_config.warn_cache_write_failures.unwrap_or(false)
+        && run_config.cache_write_failed.load(Ordering::Relaxed)
+    {
+        Some(String::from(
+            "[cache] warning: could not write the import cache; files are re-parsed on every run",
+        ))
+    } else {
+        None
     }
 }
 
@@ -124,57 +270,173 @@ fn save_cache(
 /// Returns a Vec<Issue> instead of mutating a CheckResult
 pub fn process_file_with_rules(
     module_path: &ModulePath,
-    run_config: &RunConfig,
+    run_config: &RunContext,
+    resolver: &ImportResolver,
+    rules: &[&dyn ImportRule],
+) -> Vec<Issue> {
+    process_file_with_imports(module_path, run_config, resolver, rules).0
+}
+
+/// Like `process_file_with_rules`, also returning the file's imports for project rules;
+/// none for files skipped or failing to parse.
+pub fn process_file_with_imports(
+    module_path: &ModulePath,
+    run_config: &RunContext,
+    resolver: &ImportResolver,
+    rules: &[&dyn ImportRule],
+) -> (Vec<Issue>, Vec<ImportLine>) {
+    let (issues, imports) = process_module(module_path, None, run_config, resolver, rules);
+    if !issues.is_empty() {
+        run_config.issue_found.store(true, Ordering::Relaxed);
+    }
+    (worst_issue_only(issues, run_config), imports)
+}
+
+/// Like `process_file_with_rules`, checking `source` (e.g. an unsaved editor buffer) in
+/// place of the module's file. The file is not read, and the cache is left alone.
+pub fn process_source_with_rules(
+    module_path: &ModulePath,
+    source: &str,
+    run_config: &RunContext,
     resolver: &ImportResolver,
-    rules: &[&Box<dyn ImportRule>],
+    rules: &[&dyn ImportRule],
 ) -> Vec<Issue> {
+    let (issues, _) = process_module(module_path, Some(source), run_config, resolver, rules);
+    worst_issue_only(issues, run_config)
+}
+
+/// With `one_per_file`, the highest-severity issue of a file (the first on ties); all of them
+/// otherwise. Cached issues are kept whole, so the option can be toggled freely.
+fn worst_issue_only(issues: Vec<Issue>, run_config: &RunContext) -> Vec<Issue> {
+    if !run_config.one_per_file.unwrap_or(false) {
+        return issues;
+    }
+    issues
+        .into_iter()
+        .min_by_key(|issue| (Reverse(issue.severity), issue.line, issue.column))
+        .into_iter()
+        .collect()
+}
+
+fn process_module(
+    module_path: &ModulePath,
+    unsaved: Option<&str>,
+    run_config: &RunContext,
+    resolver: &ImportResolver,
+    rules: &[&dyn ImportRule],
+) -> (Vec<Issue>, Vec<ImportLine>) {
     // Only handle files here; directory walking is managed by walker
-    if module_path.to_dir_pathbuf().is_dir() {
-        return Vec::new();
+    if resolver
+        .base_dir()
+        .join(module_path.to_dir_pathbuf())
+        .is_dir()
+    {
+        return (Vec::new(), Vec::new());
+    }
+    let source_file = module_path.source_file(resolver.base_dir(), &run_config.module_extensions());
+    let verbose = run_config.verbose.unwrap_or(false);
+
+    // The nearest `.importee.toml` can exclude the file or disable rules for it
+    let dir_override = run_config.overrides.nearest(
+        source_file.parent().unwrap_or_else(|| Path::new("")),
+        verbose,
+    );
+    if dir_override
+        .as_ref()
+        .is_some_and(|found| found.excludes(&source_file))
+    {
+        if verbose {
+            println!(
+                "[core] excluded {} (directory override)",
+                source_file.to_string_lossy()
+            );
+        }
+        return (Vec::new(), Vec::new());
     }
+    let disabled = |rule: &str| {
+        dir_override
+            .as_ref()
+            .is_some_and(|found| found.disables(rule))
+    };
+    let rules: Vec<&dyn ImportRule> = rules
+        .iter()
+        .copied()
+        .filter(|rule| !disabled(rule.name()) && rule.applies_to_file(&source_file))
+        .collect();
+    let rules = rules.as_slice();
 
     // Always print file header in verbose; quiet suppresses output
-    if run_config.verbose.unwrap_or(false) {
-        println!("=== {} ===", module_path.file_path().to_string_lossy());
+    if verbose {
+        println!("=== {} ===", source_file.to_string_lossy());
     }
     let _ = io::stdout().flush();
+    crate::debug_report::record(run_config, || {
+        json!({
+            "event": "file",
+            "module": module_path.to_dotted(),
+            "path": source_file.to_string_lossy(),
+            "rules": rules.iter().map(|rule| rule.name()).collect::<Vec<_>>(),
+        })
+    });
 
-    // Read file once and compute hash from content (avoid double read)
-    let file_path = module_path.file_path();
-    let file_content = match fs::read_to_string(&file_path) {
-        Ok(content) => content,
-        Err(_) => return Vec::new(), // Can't read file, skip it
-    };
-    let file_hash = compute_hash_from_string(&file_content);
-
-    let disable_cache = run_config.no_cache.unwrap_or(false);
-    let mut imports = if disable_cache {
-        Vec::new()
-    } else {
-        if let Some(cached) = try_load_cache(resolver, module_path, &file_hash) {
-            cached
-        } else {
-            Vec::new()
+    let loaded = match unsaved {
+        Some(source) => {
+            get_file_imports(module_path, resolver, run_config, Some(source)).map(|imports| {
+                Some(LoadedFile {
+                    imports,
+                    hash: None,
+                    from_cache: false,
+                    cached_issues: None,
+                })
+            })
         }
+        None => load_file(module_path, run_config, resolver),
     };
-
-    if imports.is_empty() {
-        // Pass the file content we already read to avoid re-reading
-        for imp in
-            get_file_imports(module_path, resolver, run_config, Some(&file_content)).into_iter()
+    let loaded = match loaded {
+        Ok(loaded) => loaded,
+        Err(e)
+            if run_config.report_syntax_errors.unwrap_or(false)
+                && !disabled("SyntaxError")
+                && !ignored_by_config(run_config, &source_file, "SyntaxError", None) =>
         {
-            imports.push(imp);
+            // Rules cannot say anything about a file that does not parse
+            let issue = Issue {
+                rule_name: String::from("SyntaxError"),
+                severity: Severity::Error,
+                path: source_file.to_string_lossy().to_string(),
+                line: e.line,
+                column: e.column,
+                message: e.message,
+                author: None,
+                commit: None,
+                fix: None,
+            };
+            return (vec![issue], Vec::new());
         }
-        if !disable_cache {
-            save_cache(resolver, module_path, &file_hash, &imports);
+        Err(_) => None,
+    };
+    // Unchanged file under the same configuration and rules: reuse the previous issues
+    let key = rules_key(rules, disabled);
+    let (imports, file_hash, from_cache) = match loaded {
+        Some(loaded) => {
+            if let Some(cached) = loaded.cached_issues.filter(|cached| cached.rules == key) {
+                if verbose {
+                    println!("[core] reusing cached issues");
+                }
+                return (cached.issues, loaded.imports);
+            }
+            (loaded.imports, loaded.hash, loaded.from_cache)
         }
-    }
-
-    for imp in imports.iter() {
-        let (is_local, reason) = resolver.classify_module(&imp.target_module);
-        if is_local {
-            // keep
-        } else if run_config.verbose.unwrap_or(false) {
+        None => (Vec::new(), None, false),
+    };
+    let report_unresolved = run_config.report_unresolved.unwrap_or(false);
+    let mut unresolved = Vec::new();
+    for imp in imports.iter().filter(|imp| imp.external) {
+        if !run_config.verbose.unwrap_or(false) && !report_unresolved {
+            continue;
+        }
+        let (kind, reason) = resolver.classify_module(&imp.target_module);
+        if run_config.verbose.unwrap_or(false) {
             println!(
                 "[external] {} -> {} ({})",
                 imp.from_module.to_dotted(),
@@ -182,45 +444,597 @@ pub fn process_file_with_rules(
                 reason
             );
         }
+        if report_unresolved
+            && kind == ModuleKind::Unresolved
+            && !imp.is_ignored("Unresolved")
+            && !disabled("Unresolved")
+            && !ignored_by_config(
+                run_config,
+                &source_file,
+                "Unresolved",
+                Some(&imp.target_module),
+            )
+        {
+            unresolved.push(Issue {
+                rule_name: String::from("Unresolved"),
+                severity: Severity::Error,
+                path: source_file.to_string_lossy().to_string(),
+                line: imp.import_line,
+                column: imp.column,
+                message: reason,
+                author: None,
+                commit: None,
+                fix: None,
+            });
+        }
+    }
+
+    unresolved.extend(evaluate_imports(
+        module_path,
+        &source_file,
+        &imports,
+        run_config,
+        rules,
+    ));
+    if let Some(file_hash) = file_hash {
+        if rules.iter().all(|rule| rule.cacheable()) {
+            let issues = CachedIssues {
+                rules: key,
+                issues: unresolved.clone(),
+            };
+            store_cache(
+                resolver,
+                run_config,
+                module_path,
+                &file_hash,
+                &imports,
+                Some(issues),
+            );
+        } else if !from_cache {
+            store_cache(
+                resolver,
+                run_config,
+                module_path,
+                &file_hash,
+                &imports,
+                None,
+            );
+        }
     }
+    (unresolved, imports)
+}
 
-    let mut issues = Vec::new();
+/// A file's imports, with what the cache knew about it.
+struct LoadedFile {
+    imports: Vec<ImportLine>,
+    /// Content hash, for writing the cache entry back; `None` for unsaved sources
+    hash: Option<String>,
+    /// Whether the imports came from a valid cache entry
+    from_cache: bool,
+    cached_issues: Option<CachedIssues>,
+}
+
+/// Collect the imports of a file, going through the on-disk cache unless disabled.
+/// Unreadable files yield no imports; files that fail to parse are an error.
+pub fn load_file_imports(
+    module_path: &ModulePath,
+    run_config: &RunContext,
+    resolver: &ImportResolver,
+) -> Result<Vec<ImportLine>, SyntaxError> {
+    let Some(loaded) = load_file(module_path, run_config, resolver)? else {
+        return Ok(Vec::new());
+    };
+    if let (Some(hash), false) = (&loaded.hash, loaded.from_cache) {
+        store_cache(
+            resolver,
+            run_config,
+            module_path,
+            hash,
+            &loaded.imports,
+            None,
+        );
+    }
+    Ok(loaded.imports)
+}
+
+/// Read and parse a file, or take its imports (and issues) from the cache. Nothing is
+/// written back; `None` when the file cannot be read.
+fn load_file(
+    module_path: &ModulePath,
+    run_config: &RunContext,
+    resolver: &ImportResolver,
+) -> Result<Option<LoadedFile>, SyntaxError> {
+    // Read file once and hash its raw bytes, whatever their encoding (avoid double read)
+    let file_path = module_path.source_file(resolver.base_dir(), &run_config.module_extensions());
+    let (file_bytes, file_content) = match read_source(&file_path) {
+        Ok(source) => source,
+        Err(_) => return Ok(None), // Can't read file, skip it
+    };
+    let file_hash = compute_hash(&file_bytes);
+
+    let entry = if run_config.no_cache.unwrap_or(false) {
+        None
+    } else {
+        crate::profile::time(run_config, Phase::CacheLoad, || {
+            load_cache_entry(resolver, run_config, module_path, &file_hash)
+        })
+    };
+    let from_cache = entry.is_some();
+    let (imports, cached_issues) = match entry {
+        Some(entry) => (cached_imports(module_path, entry.imports), entry.issues),
+        // Pass the file content we already read to avoid re-reading
+        None => (
+            get_file_imports(module_path, resolver, run_config, Some(&file_content))?,
+            None,
+        ),
+    };
+    crate::debug_report::record(run_config, || {
+        let found: Vec<serde_json::Value> = imports
+            .iter()
+            .map(|imp| {
+                let (kind, reason) = resolver.classify_module(&imp.target_module);
+                json!({
+                    "target": imp.target_module.to_dotted(),
+                    "line": imp.import_line,
+                    "local": kind == ModuleKind::Local,
+                    "reason": reason,
+                    "ignored_rules": imp.ignored_rules,
+                })
+            })
+            .collect();
+        json!({
+            "event": "imports",
+            "module": module_path.to_dotted(),
+            "from_cache": from_cache,
+            "imports": found,
+        })
+    });
+    Ok(Some(LoadedFile {
+        imports,
+        hash: Some(file_hash),
+        from_cache,
+        cached_issues,
+    }))
+}
+
+/// Write a cache entry, remembering failures for `cache_write_diagnostic`.
+fn store_cache(
+    resolver: &ImportResolver,
+    run_config: &RunContext,
+    module_path: &ModulePath,
+    hash: &str,
+    imports: &[ImportLine],
+    issues: Option<CachedIssues>,
+) {
+    if run_config.no_cache.unwrap_or(false) {
+        return;
+    }
+    let saved = crate::profile::time(run_config, Phase::CacheSave, || {
+        save_cache(resolver, run_config, module_path, hash, imports, issues)
+    });
+    if saved.is_err() {
+        run_config.cache_write_failed.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Whether a project `ignore` entry drops an issue of `rule` in `source_file`.
+fn ignored_by_config(
+    run_config: &RunContext,
+    source_file: &Path,
+    rule: &str,
+    target: Option<&ModulePath>,
+) -> bool {
+    let ignored = run_config.ignores.suppresses(source_file, rule, target);
+    if ignored && run_config.verbose.unwrap_or(false) {
+        println!("[{}] suppressed by ignore entry", rule);
+    }
+    ignored
+}
 
-    for imp in imports.iter() {
+/// Run rules over the already collected imports of one file, `source_file` holding the module.
+pub fn evaluate_imports(
+    module_path: &ModulePath,
+    source_file: &Path,
+    imports: &[ImportLine],
+    run_config: &RunContext,
+    rules: &[&dyn ImportRule],
+) -> Vec<Issue> {
+    crate::profile::time(run_config, Phase::Rules, || {
+        check_rules(module_path, source_file, imports, run_config, rules)
+    })
+}
+
+/// The issue for a failing `outcome` of `rule` on one import, unless suppressed by a comment
+/// or an `ignore` entry. Its `fix` is left to the caller.
+fn line_issue(
+    module_path: &ModulePath,
+    source_file: &Path,
+    run_config: &RunContext,
+    rule: &dyn ImportRule,
+    imp: &ImportLine,
+    outcome: RuleOutcome,
+) -> Option<Issue> {
+    let suppressed = !outcome.pass && imp.is_ignored(rule.name());
+    crate::debug_report::record(run_config, || {
+        json!({
+            "event": "rule",
+            "module": module_path.to_dotted(),
+            "rule": rule.name(),
+            "target": imp.target_module.to_dotted(),
+            "line": imp.import_line,
+            "pass": outcome.pass,
+            "reason": outcome.reason,
+            "ignored": suppressed,
+        })
+    });
+    if run_config.verbose.unwrap_or(false) && !outcome.pass {
+        println!(
+            "[{}] imported \"{}\" : {}",
+            rule.name(),
+            imp.target_module.to_dotted(),
+            outcome.reason
+        );
+    }
+    if suppressed {
         if run_config.verbose.unwrap_or(false) {
+            println!("[{}] suppressed by ignore comment", rule.name());
+        }
+        return None;
+    }
+    if outcome.pass
+        || ignored_by_config(
+            run_config,
+            source_file,
+            rule.name(),
+            Some(&imp.target_module),
+        )
+    {
+        return None;
+    }
+    let target = imp.target_module.to_dotted();
+    let message = match rule.message_template() {
+        Some(template) => render_message(
+            template,
+            &[
+                ("rule", rule.name()),
+                ("target", &target),
+                ("from", &module_path.to_dotted()),
+                ("line", &imp.import_line.to_string()),
+                ("reason", &outcome.reason),
+            ],
+        ),
+        None => format!("imported \"{}\" : {}", target, outcome.reason),
+    };
+    Some(Issue {
+        rule_name: rule.name().to_string(),
+        severity: rule.severity(),
+        path: source_file.to_string_lossy().to_string(),
+        line: imp.import_line,
+        column: imp.column,
+        message,
+        author: None,
+        commit: None,
+        fix: None,
+    })
+}
+
+fn check_rules(
+    module_path: &ModulePath,
+    source_file: &Path,
+    imports: &[ImportLine],
+    run_config: &RunContext,
+    rules: &[&dyn ImportRule],
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    if run_config.verbose.unwrap_or(false) {
+        for imp in imports.iter() {
             println!("{}", imp);
         }
-        for rule in rules.iter() {
-            let outcome = rule.check_line(&module_path.file_path(), imp);
-            if run_config.verbose.unwrap_or(false) && !outcome.pass {
-                println!(
-                    "[{}] imported \"{}\" : {}",
-                    rule.name(),
-                    imp.target_module.to_dotted(),
-                    outcome.reason
-                );
+    }
+    for rule in rules.iter() {
+        // Unresolved relative imports have no target to check, and external imports only
+        // reach the rules that asked for them
+        let checked: Vec<ImportLine> = imports
+            .iter()
+            .filter(|imp| !imp.target_module.is_empty())
+            .filter(|imp| !imp.external || rule.wants_external())
+            .cloned()
+            .collect();
+        let outcomes = rule.check_file(source_file, &checked);
+        for (imp, outcome) in checked.iter().zip(outcomes) {
+            let issue = line_issue(module_path, source_file, run_config, *rule, imp, outcome);
+            issues.extend(issue.map(|issue| Issue {
+                fix: rule.fix(imp, imports),
+                ..issue
+            }));
+        }
+    }
+    for rule in rules.iter() {
+        if let Some(outcome) = rule.finalize(module_path, imports) {
+            crate::debug_report::record(run_config, || {
+                json!({
+                    "event": "rule",
+                    "module": module_path.to_dotted(),
+                    "rule": rule.name(),
+                    "pass": outcome.pass,
+                    "reason": outcome.reason,
+                })
+            });
+            if outcome.pass || ignored_by_config(run_config, source_file, rule.name(), None) {
+                continue;
             }
-            if !outcome.pass {
-                let message = format!(
-                    "imported \"{}\" : {}",
-                    imp.target_module.to_dotted(),
-                    outcome.reason
-                );
-                issues.push(Issue {
-                    rule_name: rule.name().to_string(),
-                    path: module_path.file_path().to_string_lossy().to_string(),
-                    line: imp.import_line,
-                    message,
-                });
+            if run_config.verbose.unwrap_or(false) {
+                println!("[{}] {}", rule.name(), outcome.reason);
             }
+            let message = match rule.message_template() {
+                Some(template) => render_message(
+                    template,
+                    &[
+                        ("rule", rule.name()),
+                        ("target", ""),
+                        ("from", &module_path.to_dotted()),
+                        ("line", "0"),
+                        ("reason", &outcome.reason),
+                    ],
+                ),
+                None => outcome.reason,
+            };
+            issues.push(Issue {
+                rule_name: rule.name().to_string(),
+                severity: rule.severity(),
+                path: source_file.to_string_lossy().to_string(),
+                line: 0,
+                column: 0,
+                message,
+                author: None,
+                commit: None,
+                fix: None,
+            });
         }
     }
     if imports.is_empty() && run_config.verbose.unwrap_or(false) {
         println!(
             "[core] no imports found in {}",
-            module_path.file_path().to_string_lossy()
+            source_file.to_string_lossy()
         );
     }
 
     issues
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cache_file_path, cache_key, compute_hash_from_string, load_cache_entry, load_file_imports,
+        process_file_with_rules,
+    };
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::imports::classification::ImportResolver;
+    use crate::module_path::ModulePath;
+    use crate::results::Severity;
+    use crate::rules::duplicates::NoDuplicateImportsRule;
+    use crate::rules::layered::LayeredRule;
+    use crate::rules::ImportRule;
+    use crate::run_context::RunContext;
+    use crate::test_support::{run_of, TempProject};
+    use std::fs;
+
+    fn project(order: &[&str]) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": order}]},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn cache_entries_are_invalidated_by_config_changes() {
+        let tmp = TempProject::new();
+        tmp.write("app/__init__.py", "");
+        tmp.write("app/b.py", "");
+        tmp.write("app/a.py", "import app.b\n");
+        let module = ModulePath::from_dotted("app.a");
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false);
+        let run_for = |project: &ProjectConfig| {
+            let run = RunConfig {
+                cache_dir: Some(String::from("cache")),
+                ..RunConfig::default()
+            };
+            RunContext::new(project, run)
+        };
+
+        let first = run_for(&project(&["a", "b"]));
+        assert_eq!(
+            load_file_imports(&module, &first, &resolver).unwrap().len(),
+            1
+        );
+        let hash = compute_hash_from_string("import app.b\n");
+        assert!(load_cache_entry(&resolver, &first, &module, &hash).is_some());
+
+        let reordered = run_for(&project(&["b", "a"]));
+        assert_ne!(reordered.config_hash, first.config_hash);
+        assert!(load_cache_entry(&resolver, &reordered, &module, &hash).is_none());
+    }
+
+    #[test]
+    fn cache_entries_of_other_versions_are_rejected() {
+        let tmp = TempProject::new();
+        tmp.write("app/__init__.py", "");
+        tmp.write("app/a.py", "import os\n");
+        let module = ModulePath::from_dotted("app.a");
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false);
+        let run = run_of(RunConfig {
+            cache_dir: Some(String::from("cache")),
+            ..RunConfig::default()
+        });
+        load_file_imports(&module, &run, &resolver).unwrap();
+        let hash = compute_hash_from_string("import os\n");
+        assert!(load_cache_entry(&resolver, &run, &module, &hash).is_some());
+
+        let cache_path = cache_file_path(&resolver, &run, &module);
+        let mut entry: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&cache_path).unwrap()).unwrap();
+        entry["version"] = serde_json::json!(13);
+        fs::write(&cache_path, entry.to_string()).unwrap();
+        assert!(load_cache_entry(&resolver, &run, &module, &hash).is_none());
+    }
+
+    #[test]
+    fn cache_keys_use_forward_slashes_on_every_platform() {
+        let module = ModulePath::from_dotted("a.b.c");
+        assert_eq!(cache_key(&module), "a/b/c.imports.json");
+        let resolver = ImportResolver::new("a", Some(ModulePath::from_dotted("a")), false);
+        let run = run_of(RunConfig {
+            cache_dir: Some(String::from("cache")),
+            ..RunConfig::default()
+        });
+        let path = cache_file_path(&resolver, &run, &module);
+        assert!(path.ends_with("a/b/c.imports.json"));
+        #[cfg(windows)]
+        assert!(path
+            .to_string_lossy()
+            .ends_with("cache\\a/b/c.imports.json"));
+    }
+
+    #[test]
+    fn issues_are_reused_while_file_and_rules_are_unchanged() {
+        let tmp = TempProject::new();
+        tmp.write("app/__init__.py", "");
+        tmp.write("app/b.py", "");
+        tmp.write("app/a.py", "import app.b\n");
+        let module = ModulePath::from_dotted("app.a");
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false);
+        let run = run_of(RunConfig {
+            cache_dir: Some(String::from("cache")),
+            ..RunConfig::default()
+        });
+        let layered = |layers: &[&str]| {
+            let layers = layers.iter().map(|layer| vec![layer.to_string()]).collect();
+            LayeredRule::new(ModulePath::from_dotted("app"), layers)
+        };
+        let strict = layered(&["a", "b"]);
+        let rules: [&dyn ImportRule; 1] = [&strict];
+
+        let first = process_file_with_rules(&module, &run, &resolver, &rules);
+        assert_eq!(first.len(), 1);
+
+        // A hit returns the stored issues without running the rules again
+        let cache_path = cache_file_path(&resolver, &run, &module);
+        let entry = fs::read_to_string(&cache_path).unwrap();
+        fs::write(&cache_path, entry.replace("layer violation", "from cache")).unwrap();
+        let second = process_file_with_rules(&module, &run, &resolver, &rules);
+        assert!(second[0].message.contains("from cache"));
+
+        // Other rules for the file, or other content, recompute them
+        let relaxed = layered(&["b", "a"]);
+        let relaxed_rules: [&dyn ImportRule; 1] = [&relaxed];
+        assert!(process_file_with_rules(&module, &run, &resolver, &relaxed_rules).is_empty());
+        tmp.write("app/a.py", "\nimport app.b\n");
+        let changed = process_file_with_rules(&module, &run, &resolver, &rules);
+        assert_eq!(changed[0].line, 2);
+        assert!(changed[0].message.contains("layer violation"));
+    }
+
+    #[test]
+    fn cached_issues_follow_core_checks_disabled_by_overrides() {
+        let tmp = TempProject::new();
+        tmp.write("app/__init__.py", "");
+        tmp.write("app/a.py", "import app.missing\n");
+        let module = ModulePath::from_dotted("app.a");
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false);
+        let run = || {
+            run_of(RunConfig {
+                cache_dir: Some(String::from("cache")),
+                report_unresolved: Some(true),
+                ..RunConfig::default()
+            })
+        };
+
+        let first = process_file_with_rules(&module, &run(), &resolver, &[]);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].rule_name, "Unresolved");
+
+        tmp.write("app/.importee.toml", "disable = [\"Unresolved\"]\n");
+        assert!(process_file_with_rules(&module, &run(), &resolver, &[]).is_empty());
+    }
+
+    #[test]
+    fn duplicate_imports_are_reported_on_later_lines() {
+        let tmp = TempProject::new();
+        tmp.write("app/__init__.py", "");
+        tmp.write("app/b.py", "");
+        tmp.write(
+            "app/a.py",
+            "import os\nimport app.b\nimport os  # importee: ignore\nfrom app import b\nimport app.b\n",
+        );
+        let module = ModulePath::from_dotted("app.a");
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false);
+        let run = run_of(RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        });
+        let rule = NoDuplicateImportsRule::new(ModulePath::default());
+        let rules: [&dyn ImportRule; 1] = [&rule];
+
+        let issues = process_file_with_rules(&module, &run, &resolver, &rules);
+        let lines: Vec<u32> = issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, [4, 5]);
+        assert_eq!(
+            issues[0].message,
+            "imported \"app.b\" : duplicate import of 'app.b' (first at line 2)"
+        );
+        // Only the plain repeated `import app.b` can simply be removed
+        assert!(issues[0].fix.is_none());
+        let fix = serde_json::to_value(&issues[1].fix).unwrap();
+        assert_eq!(
+            fix,
+            serde_json::json!({
+                "range": {"start_line": 5, "start_column": 0, "end_line": 6, "end_column": 0},
+                "replacement": "",
+            })
+        );
+    }
+
+    #[test]
+    fn one_per_file_keeps_the_worst_issue() {
+        let tmp = TempProject::new();
+        tmp.write("app/__init__.py", "");
+        tmp.write("app/b.py", "");
+        tmp.write("app/c.py", "");
+        tmp.write("app/d.py", "");
+        tmp.write(
+            "app/a.py",
+            "import app.c\nimport app.d\nimport app.b\nimport app.b\n",
+        );
+        let project: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {
+                "forbidden": [{"modules": ["app.c", "app.d"], "severity": "warning"}],
+                "no_duplicate_imports": [{}],
+            },
+        }))
+        .unwrap();
+        let module = ModulePath::from_dotted("app.a");
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false);
+        let run = |one_per_file| {
+            let run = RunConfig {
+                no_cache: Some(true),
+                one_per_file,
+                ..RunConfig::default()
+            };
+            RunContext::new(&project, run)
+        };
+        let built = crate::rules::build_rules(&project, &run(None));
+        let rules: Vec<&dyn ImportRule> = built.iter().map(|rule| rule.as_ref()).collect();
+
+        let all = process_file_with_rules(&module, &run(None), &resolver, &rules);
+        assert_eq!(all.len(), 3);
+        let worst = process_file_with_rules(&module, &run(Some(true)), &resolver, &rules);
+        let worst: Vec<(Severity, u32)> = worst
+            .iter()
+            .map(|issue| (issue.severity, issue.line))
+            .collect();
+        assert_eq!(worst, [(Severity::Error, 4)]);
+    }
+}
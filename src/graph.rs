@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::imports::import_line::ImportLine;
+
+/// A dependency between two modules, weighted by how many import statements create it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub count: usize,
+    /// Line of the first import statement creating it, in the file of `from`
+    pub line: u32,
+}
+
+/// The first-party dependency graph built from collected imports.
+#[derive(Serialize, Debug, Default)]
+pub struct DependencyGraph {
+    /// Every module appearing on either side of an edge, deduplicated and sorted
+    pub nodes: Vec<String>,
+    /// Edges sorted by (from, to)
+    pub edges: Vec<Edge>,
+}
+
+impl DependencyGraph {
+    /// External imports are left out: the graph only covers first-party modules.
+    pub fn from_imports(imports: &[ImportLine]) -> Self {
+        let mut counts: BTreeMap<(String, String), (usize, u32)> = BTreeMap::new();
+        let mut nodes: BTreeSet<String> = BTreeSet::new();
+        for imp in imports.iter().filter(|imp| !imp.external) {
+            let from = imp.from_module.to_dotted();
+            let to = imp.target_module.to_dotted();
+            nodes.insert(from.clone());
+            nodes.insert(to.clone());
+            let (count, line) = counts.entry((from, to)).or_insert((0, imp.import_line));
+            *count += 1;
+            *line = (*line).min(imp.import_line);
+        }
+        DependencyGraph {
+            nodes: nodes.into_iter().collect(),
+            edges: counts
+                .into_iter()
+                .map(|((from, to), (count, line))| Edge {
+                    from,
+                    to,
+                    count,
+                    line,
+                })
+                .collect(),
+        }
+    }
+
+    /// Adjacency list mapping every node to the modules it imports, both sorted. Modules
+    /// that are only imported map to an empty list.
+    pub fn adjacency(&self) -> BTreeMap<String, Vec<String>> {
+        let mut adjacency: BTreeMap<String, Vec<String>> = self
+            .nodes
+            .iter()
+            .map(|node| (node.clone(), Vec::new()))
+            .collect();
+        for edge in self.edges.iter() {
+            adjacency
+                .entry(edge.from.clone())
+                .or_default()
+                .push(edge.to.clone());
+        }
+        adjacency
+    }
+
+    /// Breadth-first reachability from `starts`, only expanding nodes accepted by `traverse`
+    /// (the starts are always expanded). Maps every reached node other than the starts to a
+    /// shortest path leading to it, both ends included.
+    pub fn reachable_paths<F>(&self, starts: &[&str], traverse: F) -> BTreeMap<String, Vec<String>>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut successors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for edge in self.edges.iter() {
+            successors
+                .entry(edge.from.as_str())
+                .or_default()
+                .push(edge.to.as_str());
+        }
+
+        let mut parent: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut seen: BTreeSet<&str> = starts.iter().copied().collect();
+        let mut queue: VecDeque<&str> = starts.iter().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            for next in successors.get(node).into_iter().flatten() {
+                if seen.insert(next) {
+                    parent.insert(next, node);
+                    if traverse(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        parent
+            .keys()
+            .map(|node| {
+                let mut path = vec![node.to_string()];
+                let mut cur = *node;
+                while let Some(prev) = parent.get(cur) {
+                    path.push(prev.to_string());
+                    cur = prev;
+                }
+                path.reverse();
+                (node.to_string(), path)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DependencyGraph, Edge};
+    use crate::imports::import_line::ImportLine;
+    use crate::test_support::import_of;
+
+    fn line(from: &str, target: &str, import_line: u32) -> ImportLine {
+        ImportLine {
+            import_line,
+            ..import_of(from, target)
+        }
+    }
+
+    #[test]
+    fn repeated_imports_are_counted() {
+        let graph = DependencyGraph::from_imports(&[
+            line("app.b", "app.core", 1),
+            line("app.a", "app.core", 1),
+            line("app.a", "app.util", 2),
+            line("app.a", "app.core", 5),
+        ]);
+        assert_eq!(graph.nodes, ["app.a", "app.b", "app.core", "app.util"]);
+        let edge = |from: &str, to: &str, count, line| Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            count,
+            line,
+        };
+        assert_eq!(
+            graph.edges,
+            [
+                edge("app.a", "app.core", 2, 1),
+                edge("app.a", "app.util", 1, 2),
+                edge("app.b", "app.core", 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn adjacency_lists_each_dependency_once() {
+        let mut external = line("app.a", "requests", 3);
+        external.external = true;
+        let graph = DependencyGraph::from_imports(&[
+            line("app.b", "app.core", 1),
+            line("app.a", "app.util", 2),
+            line("app.a", "app.core", 1),
+            line("app.a", "app.core", 5),
+            external,
+        ]);
+        let adjacency = serde_json::to_value(graph.adjacency()).unwrap();
+        assert_eq!(
+            adjacency,
+            serde_json::json!({
+                "app.a": ["app.core", "app.util"],
+                "app.b": ["app.core"],
+                "app.core": [],
+                "app.util": [],
+            })
+        );
+    }
+
+    #[test]
+    fn reachable_paths_are_shortest_and_respect_traverse() {
+        let graph = DependencyGraph::from_imports(&[
+            line("a", "b", 1),
+            line("b", "c", 1),
+            line("a", "c", 2),
+            line("c", "d", 1),
+            line("x", "a", 1),
+        ]);
+        let paths = graph.reachable_paths(&["a"], |node| node != "c");
+        assert_eq!(paths.keys().collect::<Vec<_>>(), ["b", "c"]);
+        assert_eq!(paths["c"], ["a", "c"]);
+
+        let paths = graph.reachable_paths(&["a"], |_| true);
+        assert_eq!(paths["d"], ["a", "c", "d"]);
+    }
+}
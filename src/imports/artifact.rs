@@ -0,0 +1,97 @@
+//! Versioned artifact holding the files walked and the imports collected from them, so rules
+//! can be evaluated later (e.g. in another CI step) without re-parsing any source file.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+/// Bump whenever the serialized shape of `Artifact` (e.g. of `ImportLine`) changes.
+pub const ARTIFACT_VERSION: u32 = 10;
+
+/// A file the walk covered, recorded whether or not it has imports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectedFile {
+    pub module: ModulePath,
+    /// Path issues about the file are reported at
+    pub path: String,
+}
+
+/// Contents of an artifact. Rules that look at a whole module (e.g. `finalize`) need
+/// `files` to see the modules without imports too.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Artifact {
+    pub files: Vec<CollectedFile>,
+    pub imports: Vec<ImportLine>,
+}
+
+#[derive(Serialize)]
+struct ArtifactRef<'a> {
+    version: u32,
+    files: &'a [CollectedFile],
+    imports: &'a [ImportLine],
+}
+
+/// Just the version, read first so an incompatible artifact (e.g. with an unknown
+/// `ImportKind`) is reported as such rather than as a format error.
+#[derive(Deserialize)]
+struct ArtifactHeader {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub enum ArtifactError {
+    Io(std::io::Error),
+    Format(serde_json::Error),
+    /// The artifact was written by an incompatible version
+    Version(u32),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactError::Io(e) => write!(f, "artifact io error: {}", e),
+            ArtifactError::Format(e) => write!(f, "artifact format error: {}", e),
+            ArtifactError::Version(v) => write!(
+                f,
+                "unsupported artifact version {} (expected {})",
+                v, ARTIFACT_VERSION
+            ),
+        }
+    }
+}
+
+/// Write an artifact to `path`, with files sorted by path and imports by importing module
+/// then line for stable diffs.
+pub fn write_artifact(path: &Path, artifact: &Artifact) -> Result<(), ArtifactError> {
+    let mut files = artifact.files.clone();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut sorted = artifact.imports.clone();
+    sorted.sort_by(|a, b| {
+        a.from_module
+            .to_dotted()
+            .cmp(&b.from_module.to_dotted())
+            .then(a.import_line.cmp(&b.import_line))
+    });
+    let json = serde_json::to_string(&ArtifactRef {
+        version: ARTIFACT_VERSION,
+        files: &files,
+        imports: &sorted,
+    })
+    .map_err(ArtifactError::Format)?;
+    fs::write(path, json).map_err(ArtifactError::Io)
+}
+
+/// Load an artifact written by `write_artifact`.
+pub fn read_artifact(path: &Path) -> Result<Artifact, ArtifactError> {
+    let data = fs::read_to_string(path).map_err(ArtifactError::Io)?;
+    let header: ArtifactHeader = serde_json::from_str(&data).map_err(ArtifactError::Format)?;
+    if header.version != ARTIFACT_VERSION {
+        return Err(ArtifactError::Version(header.version));
+    }
+    serde_json::from_str(&data).map_err(ArtifactError::Format)
+}
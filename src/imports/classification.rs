@@ -1,38 +1,276 @@
+use std::collections::HashSet;
+use std::fs;
 use std::path::{Path, PathBuf};
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use crate::imports::encoding::decode_source;
 use crate::module_path::ModulePath;
 use dashmap::DashMap;
+use rustpython_ast::{Mod, Stmt};
+use rustpython_parser::{parse, Mode};
+
+/// How `classify_module` classified a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    /// Found under the resolver's roots
+    Local,
+    /// Outside the root module: third-party or standard library
+    External,
+    /// Carries the root module prefix but points at no file, or climbs past the top-level
+    /// package: likely typos or broken refactors rather than third-party packages
+    Unresolved,
+}
+
+/// Filesystem lookups shared by every resolver of a run. Keys are absolute paths, so
+/// resolvers rooted at different source modules reuse each other's results.
+#[derive(Clone)]
+pub struct ResolverCache {
+    /// Absolute cwd when the cache was created; module paths are relative to it
+    base: PathBuf,
+    /// None: missing, Some(true): directory, Some(false): file
+    kinds: Arc<DashMap<PathBuf, Option<bool>>>,
+    /// Names each parsed `__init__` file re-exports from submodules (see `resolve_reexports`)
+    reexports: Arc<DashMap<PathBuf, Arc<HashSet<String>>>>,
+    #[cfg(test)]
+    hits: Arc<AtomicUsize>,
+}
+
+impl Default for ResolverCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResolverCache {
+    pub fn new() -> Self {
+        Self {
+            base: std::env::current_dir().unwrap_or_default(),
+            kinds: Arc::new(DashMap::new()),
+            reexports: Arc::new(DashMap::new()),
+            #[cfg(test)]
+            hits: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Directory holding the files of `module`'s top-level package: `src` for the src
+    /// layout, the cwd (an empty path) otherwise. `Some(true)` forces the src layout (when
+    /// `src/<package>` exists), `Some(false)` disables it, and `None` uses it only when the
+    /// flat `<package>` directory is missing.
+    pub fn layout_base(&self, module: &ModulePath, src_layout: Option<bool>) -> PathBuf {
+        let Some(package) = module.segments().first() else {
+            return PathBuf::new();
+        };
+        let src = Path::new("src").join(package);
+        let use_src = match src_layout {
+            Some(enabled) => enabled && self.is_dir(&src),
+            None => !self.is_dir(Path::new(package)) && self.is_dir(&src),
+        };
+        if use_src {
+            PathBuf::from("src")
+        } else {
+            PathBuf::new()
+        }
+    }
+
+    fn kind(&self, path: &Path) -> Option<bool> {
+        let key = self.base.join(path);
+        if let Some(found) = self.kinds.get(&key) {
+            #[cfg(test)]
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return *found;
+        }
+        let kind = fs::metadata(&key).ok().map(|meta| meta.is_dir());
+        self.kinds.insert(key, kind);
+        kind
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.kind(path).is_some()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.kind(path) == Some(true)
+    }
+
+    /// Names bound in the `__init__` file `init` by `from .sub import Name` imports (or
+    /// absolute imports of the package's own submodules), parsed once per file.
+    fn reexports(&self, init: &Path, package: &str) -> Arc<HashSet<String>> {
+        let key = self.base.join(init);
+        if let Some(found) = self.reexports.get(&key) {
+            return found.clone();
+        }
+        let names = Arc::new(parse_reexports(&key, package));
+        self.reexports.insert(key, names.clone());
+        names
+    }
+}
+
+/// See `ResolverCache::reexports`. Unreadable or unparsable files re-export nothing.
+fn parse_reexports(init: &Path, package: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let Ok(bytes) = fs::read(init) else {
+        return names;
+    };
+    let source = decode_source(&bytes);
+    let Ok(Mod::Module(module)) = parse(&source, Mode::Module, &init.to_string_lossy()) else {
+        return names;
+    };
+    let own_prefix = format!("{}.", package);
+    for stmt in module.body.iter() {
+        let Stmt::ImportFrom(inner) = stmt else {
+            continue;
+        };
+        let relative = inner.level.is_some_and(|level| level.to_u32() > 0);
+        let from_submodule = inner
+            .module
+            .as_ref()
+            .is_some_and(|module| module.as_str().starts_with(&own_prefix));
+        if !relative && !from_submodule {
+            continue;
+        }
+        for alias in inner.names.iter() {
+            let name = alias.asname.as_ref().unwrap_or(&alias.name);
+            if name.as_str() != "*" {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    names
+}
 
 #[derive(Clone)]
 pub struct ImportResolver {
     cache: Arc<DashMap<String, bool>>,
+    /// Filesystem lookups, possibly shared with other resolvers (see `with_shared_cache`)
+    fs: ResolverCache,
     root_dir: PathBuf,
-    root_module: Option<String>,
+    /// Directory module file paths are relative to (see `base_dir`)
+    base_dir: PathBuf,
+    /// Module `root_dir` stands for, e.g. `company.product.core` for a nested source
+    root_module: Option<ModulePath>,
+    /// Cached dotted name of `root_module`
+    root_module_name: Option<String>,
     /// Cached prefix string for performance (root_module + ".")
     root_module_prefix: Option<String>,
+    /// Whether directories without `__init__.py` count as packages
+    namespace_packages: bool,
+    /// Extensions of module files, in lookup order (`py`, and `pyi` for stubs)
+    extensions: Vec<String>,
+    /// Other directories backing the root module (e.g. an editable install), searched after
+    /// `root_dir`
+    extra_roots: Vec<PathBuf>,
+    /// Whether names re-exported by a package's `__init__` count as local modules
+    resolve_reexports: bool,
 }
 
 impl Default for ImportResolver {
     fn default() -> Self {
         Self {
             cache: Arc::new(DashMap::new()),
+            fs: ResolverCache::new(),
             root_dir: PathBuf::new(),
+            base_dir: PathBuf::new(),
             root_module: None,
+            root_module_name: None,
             root_module_prefix: None,
+            namespace_packages: false,
+            extensions: vec![String::from("py")],
+            extra_roots: Vec::new(),
+            resolve_reexports: false,
         }
     }
 }
 
+/// Absolute form of a resolver root, so cache paths don't depend on the cwd. Roots that
+/// can't be canonicalized (e.g. missing directories, or `""` for the cwd) are kept as given.
+fn canonical_root(root_dir: PathBuf) -> PathBuf {
+    fs::canonicalize(&root_dir).unwrap_or(root_dir)
+}
+
 impl ImportResolver {
-    pub fn new(root_dir: impl Into<PathBuf>, root_module: Option<String>, _verbose: bool) -> Self {
-        let root_module_prefix = root_module.as_ref().map(|m| format!("{}.", m));
+    /// Resolver with its own filesystem cache.
+    pub fn new(
+        root_dir: impl Into<PathBuf>,
+        root_module: Option<ModulePath>,
+        verbose: bool,
+    ) -> Self {
+        Self::with_shared_cache(root_dir, root_module, verbose, &ResolverCache::new())
+    }
+
+    /// Like `new`, but filesystem lookups go through `cache`, shared with other resolvers.
+    pub fn with_shared_cache(
+        root_dir: impl Into<PathBuf>,
+        root_module: Option<ModulePath>,
+        _verbose: bool,
+        cache: &ResolverCache,
+    ) -> Self {
+        let root_module = root_module.filter(|module| !module.is_empty());
+        let root_module_name = root_module.as_ref().map(|m| m.to_dotted());
+        let root_module_prefix = root_module_name.as_ref().map(|m| format!("{}.", m));
         Self {
             cache: Arc::new(DashMap::new()),
-            root_dir: root_dir.into(),
+            fs: cache.clone(),
+            root_dir: canonical_root(root_dir.into()),
+            base_dir: PathBuf::new(),
             root_module,
+            root_module_name,
             root_module_prefix,
+            namespace_packages: false,
+            extensions: vec![String::from("py")],
+            extra_roots: Vec::new(),
+            resolve_reexports: false,
+        }
+    }
+
+    /// Accept files with these extensions (e.g. `pyi` stubs, `__init__.pyi` packages) as
+    /// local modules instead of only `.py` files.
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Also look for modules under these directories, each standing for the root module like
+    /// `root_dir` does. Classification and cache keys stay keyed by dotted name.
+    pub fn with_extra_roots(mut self, extra_roots: Vec<PathBuf>) -> Self {
+        self.extra_roots = extra_roots;
+        self
+    }
+
+    /// Treat `pkg.Thing` as local when `pkg/__init__.py` re-exports `Thing` from one of its
+    /// submodules (`from .impl import Thing`), even though no `Thing` module exists.
+    pub fn with_resolve_reexports(mut self, enabled: bool) -> Self {
+        self.resolve_reexports = enabled;
+        self
+    }
+
+    /// Enable or disable PEP 420 namespace package handling.
+    pub fn with_namespace_packages(mut self, enabled: bool) -> Self {
+        self.namespace_packages = enabled;
+        self
+    }
+
+    /// Resolve against `src/<root_module>` for projects using the src layout, as
+    /// `ResolverCache::layout_base` decides for the root module's top-level package. Paths
+    /// are cwd-relative, and only resolvers rooted at their source package (or at a missing
+    /// one) are affected.
+    pub fn with_src_layout(mut self, src_layout: Option<bool>) -> Self {
+        let root_module = match &self.root_module {
+            Some(root_module) => root_module,
+            None => return self,
+        };
+        let flat = root_module.to_dir_pathbuf();
+        let at_flat = self.root_dir == flat || self.root_dir == canonical_root(flat.clone());
+        if !at_flat && !self.root_dir.as_os_str().is_empty() {
+            return self;
         }
+        let base = self.fs.layout_base(root_module, src_layout);
+        if !base.as_os_str().is_empty() {
+            self.root_dir = canonical_root(base.join(flat));
+            self.base_dir = base;
+        }
+        self
     }
 
     /// Project root directory for resolution (used for caching paths and lookups)
@@ -40,38 +278,117 @@ impl ImportResolver {
         &self.root_dir
     }
 
-    /// Returns true if the dotted module path exists under the configured root directory,
-    /// without requiring it to be prefixed by the root module name.
-    pub fn module_exists_under_root(&self, dotted: &str) -> bool {
-        if dotted.is_empty() {
-            return false;
-        }
-        // Accept both root-prefixed and project-relative dotted names
+    /// Directory module file paths are relative to: `src` under the src layout (see
+    /// `with_src_layout`), the cwd (an empty path) otherwise.
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Strip the root module prefix from a dotted name, if present.
+    /// Returns "" for the root module itself and the input unchanged when it isn't prefixed.
+    fn strip_root_prefix<'a>(&self, dotted: &'a str) -> &'a str {
         // Use cached prefix to avoid string allocation
-        let dotted_rel = if let Some(root_mod) = &self.root_module {
+        if let Some(root_mod) = &self.root_module_name {
             if dotted == root_mod {
-                ""
-            } else if let Some(prefix) = &self.root_module_prefix {
+                return "";
+            }
+            if let Some(prefix) = &self.root_module_prefix {
                 if let Some(stripped) = dotted.strip_prefix(prefix.as_str()) {
-                    stripped
-                } else {
-                    dotted
+                    return stripped;
                 }
-            } else {
-                dotted
             }
+        }
+        dotted
+    }
+
+    /// Directory under the root that backs the given dotted module.
+    fn module_dir(&self, dotted: &str) -> PathBuf {
+        let rel = self.strip_root_prefix(dotted);
+        if rel.is_empty() {
+            self.root_dir.clone()
         } else {
-            dotted
-        };
-        if dotted_rel.is_empty() {
-            return self.root_dir.join("__init__.py").exists();
+            self.root_dir.join(rel.replace('.', "/"))
         }
-        let rel = dotted_rel.replace('.', "/");
-        let file = self.root_dir.join(format!("{}.py", rel));
-        if file.exists() {
-            return true;
+    }
+
+    /// Whether a directory is a package: it contains `__init__.py`, or namespace packages
+    /// are enabled and it simply exists.
+    fn is_package_dir(&self, dir: &Path) -> bool {
+        self.module_file_exists(&dir.join("__init__"))
+            || (self.namespace_packages && self.fs.is_dir(dir))
+    }
+
+    /// Whether `<stem>.<extension>` exists for one of the module extensions.
+    fn module_file_exists(&self, stem: &Path) -> bool {
+        self.extensions.iter().any(|extension| {
+            let mut path = stem.as_os_str().to_owned();
+            path.push(".");
+            path.push(extension);
+            self.fs.exists(Path::new(&path))
+        })
+    }
+
+    /// Whether `rel` (a `/`-separated path, empty for the root module itself) is a module or
+    /// package under `root_dir` or, failing that, under one of the extra roots.
+    fn exists_under_roots(&self, rel: &str) -> bool {
+        std::iter::once(&self.root_dir)
+            .chain(self.extra_roots.iter())
+            .any(|root| {
+                if rel.is_empty() {
+                    return self.is_package_dir(root);
+                }
+                let path = root.join(rel);
+                self.module_file_exists(&path) || self.is_package_dir(&path)
+            })
+    }
+
+    /// Resolve a relative import (leading dots) against the current module.
+    /// Levels are counted from the module's own package. Climbing past the top-level package
+    /// (the outermost ancestor in an unbroken chain of packages) is an ImportError in Python,
+    /// so such imports are left unresolved (empty path). Without any package anchor this
+    /// falls back to `ModulePath::from_import`.
+    fn resolve_relative(&self, current_module: &ModulePath, import: &str) -> ModulePath {
+        let segments = current_module.segments();
+        if segments.len() < 2 {
+            return ModulePath::from_import(current_module, import);
+        }
+        let package_len = segments.len() - 1;
+        let is_package =
+            |len: usize| self.is_package_dir(&self.module_dir(&segments[..len].join(".")));
+        if !is_package(package_len) {
+            return ModulePath::from_import(current_module, import);
         }
-        self.root_dir.join(&rel).join("__init__.py").exists()
+        let mut top_len = package_len;
+        while top_len > 1 && is_package(top_len - 1) {
+            top_len -= 1;
+        }
+
+        let dot_prefix = import.chars().take_while(|&c| c == '.').count();
+        let remainder = &import[dot_prefix..];
+        let climb = dot_prefix.saturating_sub(1);
+        if climb > package_len - top_len {
+            return ModulePath::default();
+        }
+        let base_len = package_len - climb;
+        let mut resolved = segments[..base_len].to_vec();
+        resolved.extend(
+            ModulePath::from_dotted(remainder)
+                .segments()
+                .iter()
+                .cloned(),
+        );
+        ModulePath::new(resolved)
+    }
+
+    /// Returns true if the dotted module path exists under the configured root directory,
+    /// without requiring it to be prefixed by the root module name.
+    pub fn module_exists_under_root(&self, dotted: &str) -> bool {
+        if dotted.is_empty() {
+            return false;
+        }
+        // Accept both root-prefixed and project-relative dotted names
+        let dotted_rel = self.strip_root_prefix(dotted);
+        self.exists_under_roots(&dotted_rel.replace('.', "/"))
     }
 
     /// Resolve an import string potentially missing the project root module prefix by
@@ -81,12 +398,12 @@ impl ImportResolver {
     ///   of the current module path (e.g., root, then root.sub, ...).
     pub fn resolve_import(&self, current_module: &ModulePath, import: &str) -> ModulePath {
         if import.starts_with('.') {
-            return ModulePath::from_import(current_module, import);
+            return self.resolve_relative(current_module, import);
         }
 
         // If the import already starts with the root module, do not prefix further
         // Use cached prefix to avoid string allocation
-        if let Some(root_mod) = &self.root_module {
+        if let Some(root_mod) = &self.root_module_name {
             if import == root_mod {
                 return ModulePath::from_dotted(import);
             }
@@ -140,38 +457,79 @@ impl ImportResolver {
             // Also consider modules that exist under root without explicit root prefix
             is_local = self.module_exists_under_root(dotted);
         }
+        if !is_local && self.resolve_reexports {
+            is_local = self.is_reexported(dotted);
+        }
         // Insert into cache (lock-free)
         self.cache.insert(dotted.to_string(), is_local);
         is_local
     }
 
+    /// Whether the last segment of `dotted` is a name re-exported by its parent package's
+    /// `__init__` file, for each module extension.
+    fn is_reexported(&self, dotted: &str) -> bool {
+        let Some((parent, name)) = dotted.rsplit_once('.') else {
+            return false;
+        };
+        let rel = self.strip_root_prefix(parent).replace('.', "/");
+        std::iter::once(&self.root_dir)
+            .chain(self.extra_roots.iter())
+            .flat_map(|root| {
+                let package = root.join(&rel);
+                self.extensions
+                    .iter()
+                    .map(move |extension| package.join(format!("__init__.{}", extension)))
+            })
+            .any(|init| self.fs.exists(&init) && self.fs.reexports(&init, parent).contains(name))
+    }
+
     /// Returns true if the module exists under root.
     fn exists_in_root(&self, dotted: &str) -> bool {
-        if let Some(root_mod) = &self.root_module {
+        if let Some(root_mod) = &self.root_module_name {
             if dotted == root_mod {
-                return self.root_dir.join("__init__.py").exists();
+                return self.exists_under_roots("");
             }
             // Use cached prefix to avoid string allocation
             if let Some(prefix) = &self.root_module_prefix {
                 if let Some(stripped) = dotted.strip_prefix(prefix.as_str()) {
-                    let rel = stripped.replace('.', "/");
-                    let file = self.root_dir.join(format!("{}.py", rel));
-                    if file.exists() {
-                        return true;
-                    }
-                    return self.root_dir.join(&rel).join("__init__.py").exists();
+                    return self.exists_under_roots(&stripped.replace('.', "/"));
                 }
             }
             // Not under root module => external
             return false;
         }
         // Fallback: treat dotted path as project-relative
-        let rel = dotted.replace('.', "/");
-        let file = self.root_dir.join(format!("{}.py", rel));
-        if file.exists() {
-            return true;
-        }
-        self.root_dir.join(&rel).join("__init__.py").exists()
+        self.exists_under_roots(&dotted.replace('.', "/"))
+    }
+
+    /// File holding a local module's source: its module file or, for a package, its
+    /// `__init__` file, under `root_dir` or one of the extra roots. None for modules outside
+    /// the root module and for missing ones.
+    pub fn module_source(&self, module: &ModulePath) -> Option<PathBuf> {
+        let dotted = module.to_dotted();
+        let rel = match (&self.root_module_name, &self.root_module_prefix) {
+            (Some(root_mod), _) if dotted == *root_mod => "",
+            (Some(_), Some(prefix)) => dotted.strip_prefix(prefix.as_str())?,
+            _ => dotted.as_str(),
+        };
+        let rel = rel.replace('.', "/");
+        std::iter::once(&self.root_dir)
+            .chain(self.extra_roots.iter())
+            .find_map(|root| {
+                let path = root.join(&rel);
+                [path.clone(), path.join("__init__")]
+                    .into_iter()
+                    .filter(|stem| stem != root)
+                    .flat_map(|stem| {
+                        self.extensions.iter().map(move |extension| {
+                            let mut file = stem.as_os_str().to_owned();
+                            file.push(".");
+                            file.push(extension);
+                            PathBuf::from(file)
+                        })
+                    })
+                    .find(|file| self.fs.kind(file) == Some(false))
+            })
     }
 
     /// Helper for ModulePath input.
@@ -179,15 +537,21 @@ impl ImportResolver {
         self.is_local_dotted(&module.to_dotted())
     }
 
-    /// Classify a module as local or external, with a human-readable reason for external.
-    pub fn classify_module(&self, module: &ModulePath) -> (bool, String) {
+    /// Classify a module, with a human-readable reason when it isn't local.
+    pub fn classify_module(&self, module: &ModulePath) -> (ModuleKind, String) {
+        if module.is_empty() {
+            return (
+                ModuleKind::Unresolved,
+                String::from("relative import beyond the top-level package"),
+            );
+        }
         let dotted = module.to_dotted();
         if self.is_local_dotted(&dotted) {
-            return (true, String::new());
+            return (ModuleKind::Local, String::new());
         }
 
         // Compute why it's considered external
-        if let Some(root_mod) = &self.root_module {
+        if let Some(root_mod) = &self.root_module_name {
             // Use cached prefix to avoid string allocation
             let has_prefix = if let Some(prefix) = &self.root_module_prefix {
                 dotted == *root_mod || dotted.starts_with(prefix.as_str())
@@ -195,7 +559,10 @@ impl ImportResolver {
                 dotted == *root_mod
             };
             if !has_prefix {
-                return (false, format!("not in root module '{}'", root_mod));
+                return (
+                    ModuleKind::External,
+                    format!("not in root module '{}'", root_mod),
+                );
             }
             // Has correct prefix but path missing
             let rel = if dotted == *root_mod {
@@ -210,10 +577,10 @@ impl ImportResolver {
                 self.root_dir.join("__init__.py")
             } else {
                 self.root_dir
-                    .join(&dotted[root_mod.len() + 1..].replace('.', "/"))
+                    .join(dotted[root_mod.len() + 1..].replace('.', "/"))
             };
             return (
-                false,
+                ModuleKind::Unresolved,
                 format!(
                     "path not found under root: {} (or {})",
                     file.with_extension("py").to_string_lossy(),
@@ -226,8 +593,313 @@ impl ImportResolver {
         let rel = dotted.replace('.', "/");
         let file = self.root_dir.join(format!("{}.py", rel));
         (
-            false,
+            ModuleKind::External,
             format!("path not found under cwd: {}", file.to_string_lossy()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ImportResolver, ModuleKind};
+    use crate::module_path::ModulePath;
+    use crate::test_support::TempProject;
+    use std::fs;
+
+    #[test]
+    fn flat_and_src_layouts_resolve_the_same_import() {
+        let current = ModulePath::from_dotted("pkg.main");
+        let expected = ModulePath::from_dotted("pkg.sub.mod");
+
+        let flat = TempProject::new();
+        flat.write("pkg/__init__.py", "");
+        flat.write("pkg/sub/__init__.py", "");
+        flat.write("pkg/sub/mod.py", "");
+        let resolver = ImportResolver::new("pkg", Some(ModulePath::from_dotted("pkg")), false)
+            .with_src_layout(None);
+        assert_eq!(resolver.resolve_import(&current, "pkg.sub.mod"), expected);
+        assert!(resolver.is_local_module(&expected));
+        assert!(resolver.module_exists_under_root("sub.mod"));
+        drop(flat);
+
+        let src = TempProject::new();
+        src.write("src/pkg/__init__.py", "");
+        src.write("src/pkg/sub/__init__.py", "");
+        src.write("src/pkg/sub/mod.py", "");
+        // The walker roots a missing source directory at the cwd
+        let resolver = ImportResolver::new("", Some(ModulePath::from_dotted("pkg")), false)
+            .with_src_layout(None);
+        assert_eq!(resolver.resolve_import(&current, "pkg.sub.mod"), expected);
+        assert!(resolver.is_local_module(&expected));
+        assert!(resolver.module_exists_under_root("sub.mod"));
+
+        let disabled = ImportResolver::new("", Some(ModulePath::from_dotted("pkg")), false)
+            .with_src_layout(Some(false));
+        assert!(!disabled.is_local_module(&expected));
+    }
+
+    #[test]
+    fn relative_and_absolute_roots_classify_the_same() {
+        let project = TempProject::new();
+        project.write("pkg/__init__.py", "");
+        project.write("pkg/sub/__init__.py", "");
+        project.write("pkg/sub/mod.py", "");
+        let root_module = Some(ModulePath::from_dotted("pkg"));
+        let relative = ImportResolver::new("pkg", root_module.clone(), false);
+        let absolute = ImportResolver::new(
+            std::env::current_dir().unwrap().join("pkg"),
+            root_module,
+            false,
+        );
+
+        assert_eq!(relative.root_dir(), absolute.root_dir());
+        assert!(relative.root_dir().is_absolute());
+        for module in ["pkg.sub.mod", "pkg.missing", "os.path"] {
+            let module = ModulePath::from_dotted(module);
+            assert_eq!(
+                relative.is_local_module(&module),
+                absolute.is_local_module(&module)
+            );
+        }
+        assert!(relative.is_local_module(&ModulePath::from_dotted("pkg.sub.mod")));
+    }
+
+    #[test]
+    fn modules_under_extra_roots_are_local() {
+        let project = TempProject::new();
+        project.write("pkg/__init__.py", "");
+        project.write("pkg/main.py", "");
+        // Editable install: the rest of the package lives in another checkout
+        project.write("checkout/src/pkg/__init__.py", "");
+        project.write("checkout/src/pkg/plugins/__init__.py", "");
+        project.write("checkout/src/pkg/plugins/extra.py", "");
+        let resolver = ImportResolver::new("pkg", Some(ModulePath::from_dotted("pkg")), false)
+            .with_extra_roots(vec!["checkout/src/pkg".into()]);
+
+        assert!(resolver.is_local_module(&ModulePath::from_dotted("pkg.main")));
+        assert!(resolver.is_local_module(&ModulePath::from_dotted("pkg.plugins.extra")));
+        assert!(resolver.module_exists_under_root("plugins"));
+        assert!(!resolver.is_local_module(&ModulePath::from_dotted("pkg.missing")));
+        assert!(resolver.cache.contains_key("pkg.plugins.extra"));
+
+        let primary_only = ImportResolver::new("pkg", Some(ModulePath::from_dotted("pkg")), false);
+        assert!(!primary_only.is_local_module(&ModulePath::from_dotted("pkg.plugins.extra")));
+    }
+
+    /// `<tmp>/<top>/pkg/mod.py` where only `pkg` is a regular package unless
+    /// `top_is_regular` also gives `<top>` an `__init__.py`.
+    fn relative_fixture(top: &str, top_is_regular: bool) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join(top);
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        if top_is_regular {
+            fs::write(root.join("__init__.py"), "").unwrap();
+        }
+        fs::write(root.join("pkg/__init__.py"), "").unwrap();
+        fs::write(root.join("pkg/mod.py"), "").unwrap();
+        fs::write(root.join("util.py"), "").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn relative_import_in_regular_package() {
+        let tmp = relative_fixture("reg", true);
+        let resolver = ImportResolver::new(
+            tmp.path().join("reg"),
+            Some(ModulePath::from_dotted("reg")),
+            false,
+        );
+        let current = ModulePath::from_dotted("reg.pkg.mod");
+        assert_eq!(
+            resolver.resolve_import(&current, ".sibling").to_dotted(),
+            "reg.pkg.sibling"
+        );
+        assert_eq!(
+            resolver.resolve_import(&current, "..util").to_dotted(),
+            "reg.util"
+        );
+    }
+
+    #[test]
+    fn relative_import_in_namespace_package() {
+        let tmp = relative_fixture("ns", false);
+        let current = ModulePath::from_dotted("ns.pkg.mod");
+
+        // `ns` has no __init__.py: as a namespace package it anchors the parent level
+        let resolver = ImportResolver::new(
+            tmp.path().join("ns"),
+            Some(ModulePath::from_dotted("ns")),
+            false,
+        )
+        .with_namespace_packages(true);
+        assert_eq!(
+            resolver.resolve_import(&current, "..util").to_dotted(),
+            "ns.util"
+        );
+
+        // Without namespace packages `pkg` is the top-level package and `..` can't escape it
+        let resolver = ImportResolver::new(
+            tmp.path().join("ns"),
+            Some(ModulePath::from_dotted("ns")),
+            false,
+        );
+        let escaping = resolver.resolve_import(&current, "..util");
+        assert!(escaping.is_empty());
+        assert_eq!(
+            resolver.classify_module(&escaping).0,
+            ModuleKind::Unresolved
+        );
+    }
+
+    #[test]
+    fn nested_namespace_package_is_local() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("pkg");
+        fs::create_dir_all(root.join("ns/inner")).unwrap();
+        fs::write(root.join("__init__.py"), "").unwrap();
+        fs::write(root.join("ns/inner/mod.py"), "").unwrap();
+        let inner = ModulePath::from_dotted("pkg.ns.inner");
+
+        let resolver = ImportResolver::new(&root, Some(ModulePath::from_dotted("pkg")), false);
+        assert!(!resolver.is_local_module(&inner));
+        assert!(!resolver.module_exists_under_root("ns.inner"));
+
+        let resolver = ImportResolver::new(&root, Some(ModulePath::from_dotted("pkg")), false)
+            .with_namespace_packages(true);
+        assert!(resolver.is_local_module(&inner));
+        assert!(resolver.is_local_module(&ModulePath::from_dotted("pkg.ns")));
+        assert!(resolver.module_exists_under_root("ns.inner"));
+        // Missing directories are still external
+        assert!(!resolver.is_local_module(&ModulePath::from_dotted("pkg.ns.missing")));
+    }
+
+    #[test]
+    fn shared_cache_is_reused_across_resolvers() {
+        use super::ResolverCache;
+        use std::sync::atomic::Ordering;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("pkg");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("__init__.py"), "").unwrap();
+        fs::write(root.join("a.py"), "").unwrap();
+        let module = ModulePath::from_dotted("pkg.a");
+
+        let cache = ResolverCache::new();
+        let first = ImportResolver::with_shared_cache(
+            &root,
+            Some(ModulePath::from_dotted("pkg")),
+            false,
+            &cache,
+        );
+        assert_eq!(first.classify_module(&module).0, ModuleKind::Local);
+        let lookups = cache.kinds.len();
+        let hits = cache.hits.load(Ordering::Relaxed);
+
+        // A resolver for another source starts with an empty dotted cache but no new lookups
+        let second = ImportResolver::with_shared_cache(
+            &root,
+            Some(ModulePath::from_dotted("pkg")),
+            false,
+            &cache,
+        );
+        assert_eq!(second.classify_module(&module).0, ModuleKind::Local);
+        assert_eq!(cache.kinds.len(), lookups);
+        assert!(cache.hits.load(Ordering::Relaxed) > hits);
+    }
+
+    #[test]
+    fn names_reexported_by_init_are_local_when_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("pkg");
+        fs::create_dir_all(root.join("models")).unwrap();
+        fs::write(
+            root.join("__init__.py"),
+            "from .impl import Thing\nfrom pkg.models import User as Account\nimport os\n",
+        )
+        .unwrap();
+        fs::write(root.join("impl.py"), "class Thing: ...\n").unwrap();
+        fs::write(root.join("models/__init__.py"), "").unwrap();
+        let resolver = |enabled| {
+            ImportResolver::new(&root, Some(ModulePath::from_dotted("pkg")), false)
+                .with_resolve_reexports(enabled)
+        };
+        let local = |resolver: &ImportResolver, dotted: &str| {
+            resolver.classify_module(&ModulePath::from_dotted(dotted)).0 == ModuleKind::Local
+        };
+
+        assert!(!local(&resolver(false), "pkg.Thing"));
+        let resolver = resolver(true);
+        assert!(local(&resolver, "pkg.Thing"));
+        assert!(local(&resolver, "pkg.Account"));
+        assert!(!local(&resolver, "pkg.User"));
+        assert!(!local(&resolver, "pkg.os"));
+        assert_eq!(resolver.fs.reexports.len(), 1);
+    }
+
+    #[test]
+    fn names_reexported_by_an_init_stub_are_local() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("pkg");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("__init__.pyi"), "from .impl import Thing\n").unwrap();
+        fs::write(root.join("impl.pyi"), "class Thing: ...\n").unwrap();
+        let resolver = |extensions: &[&str]| {
+            ImportResolver::new(&root, Some(ModulePath::from_dotted("pkg")), false)
+                .with_extensions(extensions.iter().map(|e| e.to_string()).collect())
+                .with_resolve_reexports(true)
+        };
+        let thing = ModulePath::from_dotted("pkg.Thing");
+
+        assert_ne!(
+            resolver(&["py"]).classify_module(&thing).0,
+            ModuleKind::Local
+        );
+        assert_eq!(
+            resolver(&["py", "pyi"]).classify_module(&thing).0,
+            ModuleKind::Local
+        );
+    }
+
+    #[test]
+    fn multi_segment_root_module_strips_the_whole_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("company/product/core");
+        fs::create_dir_all(root.join("engine/parts")).unwrap();
+        for package in ["", "engine", "engine/parts"] {
+            fs::write(root.join(package).join("__init__.py"), "").unwrap();
+        }
+        fs::write(root.join("engine/parts/gear.py"), "").unwrap();
+        let resolver = ImportResolver::new(
+            &root,
+            Some(ModulePath::from_dotted("company.product.core")),
+            false,
+        );
+        let classify = |dotted: &str| resolver.classify_module(&ModulePath::from_dotted(dotted));
+
+        assert_eq!(classify("company.product.core").0, ModuleKind::Local);
+        assert_eq!(
+            classify("company.product.core.engine.parts.gear").0,
+            ModuleKind::Local
+        );
+        let (kind, reason) = classify("company.product.core.engine.missing");
+        assert_eq!(kind, ModuleKind::Unresolved);
+        assert!(reason.contains("engine/missing.py"));
+        assert_eq!(
+            classify("company.product.other"),
+            (
+                ModuleKind::External,
+                String::from("not in root module 'company.product.core'")
+            )
+        );
+        assert_eq!(
+            resolver
+                .resolve_import(
+                    &ModulePath::from_dotted("company.product.core.engine.parts.gear"),
+                    "company.product.core.engine"
+                )
+                .to_dotted(),
+            "company.product.core.engine"
+        );
+    }
+}
@@ -1,21 +1,32 @@
-use crate::configs::RunConfig;
 use crate::imports::classification::ImportResolver;
-use crate::imports::import_line::ImportLine;
+use crate::imports::encoding::read_source;
+use crate::imports::import_line::{ImportKind, ImportLine, IGNORE_ALL};
 use crate::module_path::ModulePath;
-use rustpython_ast::{Mod, Ranged, Stmt};
+use crate::profile::Phase;
+use crate::run_context::RunContext;
+use rustpython_ast::{Alias, Constant, Expr, ExprCall, Mod, Ranged, Stmt, Visitor};
 use rustpython_parser::{parse, Mode};
-use std::fs;
+use std::cell::RefCell;
+use std::path::Path;
 
-/// Build a line offset table for fast line number lookups.
-/// Returns a vector where offsets[i] is the byte offset of line i+1.
-fn build_line_offsets(source: &str) -> Vec<usize> {
-    let mut offsets = vec![0];
-    for (i, byte) in source.bytes().enumerate() {
-        if byte == b'\n' {
-            offsets.push(i + 1);
-        }
-    }
-    offsets
+thread_local! {
+    /// Line offset table reused by every file a thread parses, so large generated modules
+    /// don't each allocate one
+    static LINE_OFFSETS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fill `offsets` with a line offset table for fast line number lookups:
+/// offsets[i] is the byte offset of line i+1.
+fn build_line_offsets(source: &str, offsets: &mut Vec<usize>) {
+    offsets.clear();
+    offsets.push(0);
+    offsets.extend(
+        source
+            .bytes()
+            .enumerate()
+            .filter(|(_, byte)| *byte == b'\n')
+            .map(|(i, _)| i + 1),
+    );
 }
 
 /// Convert a byte offset to a line number using the pre-built offset table.
@@ -27,37 +38,178 @@ fn offset_to_line(offset: usize, line_offsets: &[usize]) -> u32 {
     }
 }
 
+/// Byte column of an offset within its (1-based) line. Tabs count as a single byte.
+fn offset_to_column(offset: usize, line_no: u32, line_offsets: &[usize]) -> i32 {
+    let line_start = line_offsets
+        .get((line_no as usize).saturating_sub(1))
+        .copied()
+        .unwrap_or(0);
+    offset.saturating_sub(line_start) as i32
+}
+
+/// Rules suppressed by a trailing `# importee: ignore` (every rule) or
+/// `# importee: ignore[RuleA, RuleB]` comment on a source line.
+fn parse_ignore_directive(line: &str) -> Vec<String> {
+    for (idx, _) in line.match_indices('#') {
+        let rest = line[idx + 1..].trim_start();
+        let rest = match rest.strip_prefix("importee:") {
+            Some(rest) => rest.trim_start(),
+            None => continue,
+        };
+        let rest = match rest.strip_prefix("ignore") {
+            Some(rest) => rest,
+            None => continue,
+        };
+        if let Some(list) = rest.strip_prefix('[') {
+            if let Some(end) = list.find(']') {
+                return list[..end]
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+            }
+        } else if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            return vec![IGNORE_ALL.to_string()];
+        }
+    }
+    Vec::new()
+}
+
+/// Value of a module's `# importee: visibility=<value>` directive, which must be a comment on
+/// its own line. The first directive wins.
+pub fn parse_visibility_directive(source: &str) -> Option<&str> {
+    source.lines().find_map(|line| {
+        let rest = line.trim_start().strip_prefix('#')?.trim_start();
+        let rest = rest.strip_prefix("importee:")?.trim_start();
+        let value = rest.strip_prefix("visibility")?.trim_start();
+        Some(value.strip_prefix('=')?.trim())
+    })
+}
+
+/// String literals a module lists in its top-level `__all__` (assigned, annotated or extended
+/// with `+=`), or `None` when it doesn't define `__all__` or fails to parse.
+pub fn parse_dunder_all(source: &str) -> Option<Vec<String>> {
+    let Ok(Mod::Module(module)) = parse(source, Mode::Module, "<__all__>") else {
+        return None;
+    };
+    let is_dunder_all =
+        |target: &Expr| matches!(target, Expr::Name(name) if name.id.as_str() == "__all__");
+    let mut names: Option<Vec<String>> = None;
+    for stmt in module.body.iter() {
+        let (value, extend) = match stmt {
+            Stmt::Assign(inner) if inner.targets.iter().any(is_dunder_all) => {
+                (&*inner.value, false)
+            }
+            Stmt::AnnAssign(inner) if is_dunder_all(&inner.target) => match &inner.value {
+                Some(value) => (&**value, false),
+                None => continue,
+            },
+            Stmt::AugAssign(inner) if is_dunder_all(&inner.target) => (&*inner.value, true),
+            _ => continue,
+        };
+        let elts = match value {
+            Expr::List(list) => &list.elts,
+            Expr::Tuple(tuple) => &tuple.elts,
+            _ => continue,
+        };
+        let literals = elts.iter().filter_map(|elt| match elt {
+            Expr::Constant(constant) => match &constant.value {
+                Constant::Str(name) => Some(name.clone()),
+                _ => None,
+            },
+            _ => None,
+        });
+        match names.as_mut() {
+            Some(names) if extend => names.extend(literals),
+            _ => names = Some(literals.collect()),
+        }
+    }
+    names
+}
+
+/// Text of a (1-based) source line, without its line break.
+fn source_line<'a>(source: &'a str, line_no: u32, line_offsets: &[usize]) -> &'a str {
+    let idx = (line_no as usize).saturating_sub(1);
+    let start = line_offsets.get(idx).copied().unwrap_or(source.len());
+    let end = line_offsets
+        .get(idx + 1)
+        .copied()
+        .unwrap_or(source.len())
+        .max(start);
+    source[start..end].trim_end_matches(['\n', '\r'])
+}
+
+/// A file the parser rejected, located at the offset the parser gave up on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub line: u32,
+    pub column: i32,
+    pub message: String,
+}
+
 /// Parse imports for a module identified by its ModulePath. This preserves the full dotted path
 /// for `from_module` instead of only using the file's stem.
 /// If file_content is provided, it will be used instead of reading the file (performance optimization).
+/// Unreadable files yield no imports; files that fail to parse are an error.
 pub fn get_file_imports(
     module: &ModulePath,
     resolver: &ImportResolver,
-    run_config: &RunConfig,
+    run_config: &RunContext,
     file_content: Option<&str>,
-) -> Vec<ImportLine> {
-    let file_path = module.file_path();
+) -> Result<Vec<ImportLine>, SyntaxError> {
+    let file_path = module.source_file(resolver.base_dir(), &run_config.module_extensions());
     let content: String;
     let file_content_ref = match file_content {
         Some(c) => c,
         None => {
-            content = match fs::read_to_string(&file_path) {
-                Ok(c) => c,
-                Err(_) => return Vec::new(),
+            content = match read_source(&file_path) {
+                Ok((_, c)) => c,
+                Err(_) => return Ok(Vec::new()),
             };
             &content
         }
     };
 
+    // Build line offset table once for O(log n) line number lookups
+    LINE_OFFSETS.with_borrow_mut(|line_offsets| {
+        build_line_offsets(file_content_ref, line_offsets);
+        parse_file_imports(
+            module,
+            resolver,
+            run_config,
+            &file_path,
+            file_content_ref,
+            line_offsets,
+        )
+    })
+}
+
+/// `get_file_imports` once the file is read and its line offsets are built.
+fn parse_file_imports(
+    module: &ModulePath,
+    resolver: &ImportResolver,
+    run_config: &RunContext,
+    file_path: &Path,
+    file_content_ref: &str,
+    line_offsets: &[usize],
+) -> Result<Vec<ImportLine>, SyntaxError> {
     // Parse with rustpython parser
-    let ast = match parse(file_content_ref, Mode::Module, &file_path.to_string_lossy()) {
+    let parsed = crate::profile::time(run_config, Phase::Parse, || {
+        parse(file_content_ref, Mode::Module, &file_path.to_string_lossy())
+    });
+    let ast = match parsed {
         Ok(suite) => suite,
-        Err(_) => return Vec::new(),
+        Err(e) => {
+            let offset = e.offset.to_usize();
+            let line = offset_to_line(offset, line_offsets);
+            return Err(SyntaxError {
+                line,
+                column: offset_to_column(offset, line, line_offsets),
+                message: e.error.to_string(),
+            });
+        }
     };
 
-    // Build line offset table once for O(log n) line number lookups
-    let line_offsets = build_line_offsets(file_content_ref);
-
     let mut results: Vec<ImportLine> = Vec::new();
 
     let body: &[Stmt] = match &ast {
@@ -71,69 +223,195 @@ pub fn get_file_imports(
             module,
             resolver,
             file_content_ref,
-            &line_offsets,
+            line_offsets,
             &mut results,
             run_config,
         );
     }
 
-    results
+    if run_config.detect_dynamic_imports.unwrap_or(false) {
+        let mut calls = DynamicImportCalls::default();
+        if let Mod::Module(m) = ast {
+            for stmt in m.body {
+                calls.visit_stmt(stmt);
+            }
+        }
+        for (spec, offset) in calls.found {
+            let line = offset_to_line(offset, line_offsets);
+            if run_config.verbose.unwrap_or(false) {
+                println!("[collect] from={} dynamic={}", module.to_dotted(), spec);
+            }
+            let resolved = resolver.resolve_import(module, &spec);
+            results.push(ImportLine {
+                from_module: module.clone(),
+                external: !resolver.is_local_module(&resolved),
+                target_module: resolved,
+                import_line: line,
+                column: offset_to_column(offset, line, line_offsets),
+                ignored_rules: parse_ignore_directive(source_line(
+                    file_content_ref,
+                    line,
+                    line_offsets,
+                )),
+                kind: ImportKind::DynamicImport,
+                wildcard: false,
+                alias: None,
+                names: Vec::new(),
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Module names (with the offset of the call) of the `import_module("...")` and
+/// `__import__("...")` calls anywhere in a module, including nested scopes.
+#[derive(Default)]
+struct DynamicImportCalls {
+    found: Vec<(String, usize)>,
+}
+
+impl Visitor for DynamicImportCalls {
+    fn visit_expr_call(&mut self, node: ExprCall) {
+        let importer = match &*node.func {
+            Expr::Name(name) => name.id.as_str(),
+            Expr::Attribute(attribute) => attribute.attr.as_str(),
+            _ => "",
+        };
+        if importer == "import_module" || importer == "__import__" {
+            // Non-literal module names can't be resolved statically
+            if let Some(Expr::Constant(constant)) = node.args.first() {
+                if let Constant::Str(spec) = &constant.value {
+                    self.found
+                        .push((spec.clone(), node.range.start().to_usize()));
+                }
+            }
+        }
+        self.generic_visit_expr_call(node)
+    }
+}
+
+/// The name an import is bound to with `as`, if any.
+fn as_name(alias: &Alias) -> Option<String> {
+    alias.asname.as_ref().map(|name| name.to_string())
 }
 
+/// One base spec per imported name, with the line and column the name is reported at, its
+/// `as` alias and, for a name that isn't a submodule, the name imported from the base.
+type BaseSpec = (String, u32, i32, Option<String>, Option<String>);
+
 fn collect_imports_from_stmt(
     stmt: &Stmt,
     current_module: &ModulePath,
     resolver: &ImportResolver,
+    source: &str,
     line_offsets: &[usize],
     out: &mut Vec<ImportLine>,
-    run_config: &RunConfig,
+    run_config: &RunContext,
 ) {
-    let mut base: Option<String> = None;
+    let mut bases: Vec<BaseSpec> = Vec::new();
     let mut line_no: u32 = 0;
+    let mut kind = ImportKind::Import;
+    // The parser keeps `*` as an alias named "*"
+    let mut wildcard = false;
+    // Names of a multiline (e.g. parenthesized) import are reported on their own line, at their
+    // own column; names on the statement's first line keep the statement's column
+    let locate = |alias: &Alias, line_no: u32, column: i32| {
+        let start = alias.range().start().to_usize();
+        let alias_line = offset_to_line(start, line_offsets);
+        if alias_line == line_no {
+            (line_no, column)
+        } else {
+            (
+                alias_line,
+                offset_to_column(start, alias_line, line_offsets),
+            )
+        }
+    };
 
     match stmt {
         Stmt::Import(inner) => {
             let start = inner.range().start().to_usize();
             line_no = offset_to_line(start, line_offsets);
-            if let Some(alias) = inner.names.first() {
-                base = Some(alias.name.to_string());
+            let column = offset_to_column(start, line_no, line_offsets);
+            for alias in inner.names.iter() {
+                let (line, col) = locate(alias, line_no, column);
+                bases.push((alias.name.to_string(), line, col, as_name(alias), None));
             }
         }
         Stmt::ImportFrom(inner) => {
             let start = inner.range().start().to_usize();
             line_no = offset_to_line(start, line_offsets);
-            // Prefer the module; only use relative dots when module is missing
+            let column = offset_to_column(start, line_no, line_offsets);
+            // The parser always sets a level; it counts the leading dots
+            kind = if inner.level.is_some_and(|level| level.to_u32() > 0) {
+                ImportKind::RelativeImport
+            } else {
+                ImportKind::FromImport
+            };
+            // One leading dot per level, so `from ..pkg import x` climbs two packages
+            let dots = ".".repeat(inner.level.map_or(0, |level| level.to_usize()));
             let module_name = inner
                 .module
                 .as_ref()
-                .map(|m| m.to_string())
+                .map(|m| format!("{}{}", dots, m))
                 .unwrap_or_default();
             if !module_name.is_empty() {
-                // If first alias is a submodule that exists, prefer pkg.alias; else pkg
-                if let Some(first) = inner.names.first() {
-                    let try_sub = format!("{}.{}", module_name, first.name);
+                // For each alias that is a submodule that exists, prefer pkg.alias; else pkg
+                for alias in inner.names.iter() {
+                    let (line, col) = locate(alias, line_no, column);
+                    if alias.name.as_str() == "*" {
+                        wildcard = true;
+                        bases.push((module_name.clone(), line, col, None, None));
+                        continue;
+                    }
+                    let try_sub = format!("{}.{}", module_name, alias.name);
                     let resolved_try = resolver.resolve_import(current_module, &try_sub);
                     if resolver.is_local_module(&resolved_try) {
-                        base = Some(try_sub);
+                        bases.push((try_sub, line, col, as_name(alias), None));
+                    } else {
+                        let name = Some(alias.name.to_string());
+                        bases.push((module_name.clone(), line, col, as_name(alias), name));
+                    }
+                }
+                if bases.is_empty() {
+                    bases.push((module_name, line_no, column, None, None));
+                }
+            } else {
+                for alias in inner.names.iter() {
+                    let (line, col) = locate(alias, line_no, column);
+                    if alias.name.as_str() == "*" {
+                        wildcard = true;
+                        bases.push((dots.clone(), line, col, None, None));
                     } else {
-                        base = Some(module_name);
+                        let base = format!("{}{}", dots, alias.name);
+                        bases.push((base, line, col, as_name(alias), None));
                     }
-                } else {
-                    base = Some(module_name);
                 }
-            } else if let Some(first) = inner.names.first() {
-                let dots = if inner.level.is_some() {
-                    String::from(".")
-                } else {
-                    String::new()
-                };
-                base = Some(format!("{}{}", dots, first.name));
             }
         }
         _ => {}
     }
 
-    if let Some(base_spec) = base {
+    // Comments are dropped by the parser, so look for suppressions in the raw lines: the
+    // statement's first and last lines (e.g. the closing parenthesis) cover every name, a
+    // name's own line covers that name
+    let end_line = offset_to_line(stmt.range().end().to_usize(), line_offsets);
+    let mut statement_ignores = Vec::new();
+    if !bases.is_empty() {
+        for line in [line_no, end_line.max(line_no)] {
+            for rule in parse_ignore_directive(source_line(source, line, line_offsets)) {
+                if !statement_ignores.contains(&rule) {
+                    statement_ignores.push(rule);
+                }
+            }
+        }
+    }
+
+    // Several symbols from the same module collapse onto a single target, unless their lines
+    // suppress different rules
+    let first_new = out.len();
+    for (base_spec, line, col, alias, name) in bases {
         if run_config.verbose.unwrap_or(false) {
             println!(
                 "[collect] from={} base={}",
@@ -142,13 +420,38 @@ fn collect_imports_from_stmt(
             );
         }
         let resolved = resolver.resolve_import(current_module, &base_spec);
-        if resolver.is_local_module(&resolved) {
-            out.push(ImportLine {
-                from_module: current_module.clone(),
-                target_module: resolved,
-                import_line: line_no,
-            });
+        let external = !resolver.is_local_module(&resolved);
+        let mut ignored_rules = statement_ignores.clone();
+        if line != line_no && line != end_line {
+            for rule in parse_ignore_directive(source_line(source, line, line_offsets)) {
+                if !ignored_rules.contains(&rule) {
+                    ignored_rules.push(rule);
+                }
+            }
+        }
+        if let Some(existing) = out[first_new..].iter_mut().find(|imp| {
+            imp.target_module == resolved
+                && imp.ignored_rules.len() == ignored_rules.len()
+                && imp
+                    .ignored_rules
+                    .iter()
+                    .all(|rule| ignored_rules.contains(rule))
+        }) {
+            existing.names.extend(name);
+            continue;
         }
+        out.push(ImportLine {
+            from_module: current_module.clone(),
+            target_module: resolved,
+            import_line: line,
+            column: col,
+            external,
+            ignored_rules,
+            kind,
+            wildcard,
+            alias,
+            names: name.into_iter().collect(),
+        });
     }
 }
 
@@ -156,15 +459,16 @@ fn collect_imports_deep(
     stmt: &Stmt,
     current_module: &ModulePath,
     resolver: &ImportResolver,
-    _source: &str,
+    source: &str,
     line_offsets: &[usize],
     out: &mut Vec<ImportLine>,
-    run_config: &RunConfig,
+    run_config: &RunContext,
 ) {
     collect_imports_from_stmt(
         stmt,
         current_module,
         resolver,
+        source,
         line_offsets,
         out,
         run_config,
@@ -228,3 +532,376 @@ fn collect_imports_deep(
     }
     */
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{get_file_imports, parse_dunder_all, parse_visibility_directive};
+    use crate::configs::RunConfig;
+    use crate::imports::classification::{ImportResolver, ModuleKind};
+    use crate::imports::import_line::ImportKind;
+    use crate::module_path::ModulePath;
+    use crate::test_support::run_of;
+    use std::fs;
+
+    /// Build `<tmp>/pkg` with the given empty submodules and return a resolver rooted there.
+    fn fixture(modules: &[&str]) -> (tempfile::TempDir, ImportResolver) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("pkg");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("__init__.py"), "").unwrap();
+        for module in modules {
+            let path = root.join(format!("{}.py", module.replace('.', "/")));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let resolver = ImportResolver::new(&root, Some(ModulePath::from_dotted("pkg")), false);
+        (tmp, resolver)
+    }
+
+    fn targets(source: &str, resolver: &ImportResolver) -> Vec<(String, u32)> {
+        let module = ModulePath::from_dotted("pkg.main");
+        get_file_imports(
+            &module,
+            resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap()
+        .into_iter()
+        .filter(|imp| !imp.external)
+        .map(|imp| (imp.target_module.to_dotted(), imp.import_line))
+        .collect()
+    }
+
+    #[test]
+    fn multi_name_import_yields_each_target() {
+        let (_tmp, resolver) = fixture(&["a", "b", "c"]);
+        let found = targets("import os\nimport pkg.a, pkg.b, sys, pkg.c\n", &resolver);
+        assert_eq!(
+            found,
+            [
+                ("pkg.a".to_string(), 2),
+                ("pkg.b".to_string(), 2),
+                ("pkg.c".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn parenthesized_import_reports_each_name_on_its_line() {
+        let (_tmp, resolver) = fixture(&["a", "b", "c"]);
+        let source =
+            "import os\nfrom pkg import (\n    a,\n    b,  # importee: ignore\n    c,\n)\n";
+        let found = targets(source, &resolver);
+        assert_eq!(
+            found,
+            [
+                ("pkg.a".to_string(), 3),
+                ("pkg.b".to_string(), 4),
+                ("pkg.c".to_string(), 5)
+            ]
+        );
+        let module = ModulePath::from_dotted("pkg.main");
+        let imports = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap();
+        let columns: Vec<i32> = imports.iter().skip(1).map(|imp| imp.column).collect();
+        assert_eq!(columns, [4, 4, 4]);
+        let ignored: Vec<bool> = imports
+            .iter()
+            .skip(1)
+            .map(|imp| !imp.ignored_rules.is_empty())
+            .collect();
+        assert_eq!(ignored, [false, true, false]);
+    }
+
+    #[test]
+    fn names_merge_only_with_the_same_ignores() {
+        let (_tmp, resolver) = fixture(&[]);
+        let source = "from pkg import (\n    a,  # importee: ignore\n    b,\n    c,\n)\n";
+        let module = ModulePath::from_dotted("pkg.main");
+        let imports = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap();
+        let found: Vec<(String, Vec<String>, bool)> = imports
+            .into_iter()
+            .map(|imp| {
+                let ignored = !imp.ignored_rules.is_empty();
+                (imp.target_module.to_dotted(), imp.names, ignored)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (String::from("pkg"), vec![String::from("a")], true),
+                (
+                    String::from("pkg"),
+                    vec![String::from("b"), String::from("c")],
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignore_on_the_closing_line_covers_every_name() {
+        let (_tmp, resolver) = fixture(&["a", "b"]);
+        let source =
+            "from pkg import (\n    a,\n    b,\n)  # importee: ignore[Linear]\nimport os\n";
+        let module = ModulePath::from_dotted("pkg.main");
+        let imports = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap();
+        let ignored: Vec<Vec<String>> = imports.into_iter().map(|imp| imp.ignored_rules).collect();
+        let linear = vec![String::from("Linear")];
+        assert_eq!(ignored, [linear.clone(), linear, Vec::new()]);
+    }
+
+    #[test]
+    fn multi_name_from_import_yields_each_submodule() {
+        let (_tmp, resolver) = fixture(&["a", "b", "c"]);
+        let found = targets("from pkg import a, b, c\n", &resolver);
+        let dotted: Vec<&str> = found.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(dotted, ["pkg.a", "pkg.b", "pkg.c"]);
+        assert!(found.iter().all(|(_, line)| *line == 1));
+        for (target, _) in &found {
+            assert_eq!(
+                resolver.classify_module(&ModulePath::from_dotted(target)).0,
+                ModuleKind::Local
+            );
+        }
+    }
+
+    #[test]
+    fn from_import_resolves_each_name_independently() {
+        let (_tmp, resolver) = fixture(&["sub_a.__init__", "mod_c"]);
+        // A subpackage and a symbol, then a symbol first and a module second
+        let found = targets(
+            "from pkg import sub_a, symbol_b\nfrom pkg import helper, mod_c\n",
+            &resolver,
+        );
+        assert_eq!(
+            found,
+            [
+                ("pkg.sub_a".to_string(), 1),
+                ("pkg".to_string(), 1),
+                ("pkg".to_string(), 2),
+                ("pkg.mod_c".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_are_tagged_with_their_kind() {
+        let (_tmp, resolver) = fixture(&["a", "b", "c"]);
+        let module = ModulePath::from_dotted("pkg.main");
+        let source = "import pkg.a\nfrom pkg import b\nfrom . import c\n";
+        let kinds: Vec<(u32, ImportKind)> = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|imp| (imp.import_line, imp.kind))
+        .collect();
+        assert_eq!(
+            kinds,
+            [
+                (1, ImportKind::Import),
+                (2, ImportKind::FromImport),
+                (3, ImportKind::RelativeImport)
+            ]
+        );
+    }
+
+    #[test]
+    fn dynamic_imports_are_collected_when_enabled() {
+        let (_tmp, resolver) = fixture(&["a", "b", "plugins.c"]);
+        let module = ModulePath::from_dotted("pkg.main");
+        let source = "import importlib
+\
+                      def load(name):\n    \
+                      mod = importlib.import_module('pkg.a')\n    \
+                      __import__(\"pkg.plugins.c\")\n    \
+                      importlib.import_module(name)\n    \
+                      return import_module(\"os\")\n";
+        let dynamic = |detect_dynamic_imports| {
+            let run_config = run_of(RunConfig {
+                detect_dynamic_imports,
+                ..RunConfig::default()
+            });
+            get_file_imports(&module, &resolver, &run_config, Some(source))
+                .unwrap()
+                .into_iter()
+                .filter(|imp| imp.kind == ImportKind::DynamicImport)
+                .map(|imp| (imp.target_module.to_dotted(), imp.import_line, imp.external))
+                .collect::<Vec<_>>()
+        };
+        assert!(dynamic(None).is_empty());
+        assert_eq!(
+            dynamic(Some(true)),
+            [
+                ("pkg.a".to_string(), 3, false),
+                ("pkg.plugins.c".to_string(), 4, false),
+                ("os".to_string(), 6, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_imports_climb_one_package_per_dot() {
+        let (_tmp, resolver) = fixture(&[
+            "a.__init__",
+            "a.b.__init__",
+            "a.x",
+            "util.__init__",
+            "util.y",
+        ]);
+        let module = ModulePath::from_dotted("pkg.a.b.mod");
+        let source = "from .. import x\nfrom ...util import y\n";
+        let found: Vec<(String, bool)> = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|imp| (imp.target_module.to_dotted(), imp.external))
+        .collect();
+        assert_eq!(
+            found,
+            [
+                ("pkg.a.x".to_string(), false),
+                ("pkg.util.y".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn columns_are_byte_offsets_within_the_line() {
+        let (_tmp, resolver) = fixture(&["a", "b"]);
+        let module = ModulePath::from_dotted("pkg.main");
+        let source = "import pkg.a\nimport os;\timport pkg.b\n";
+        let found: Vec<(String, u32, i32)> = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap()
+        .into_iter()
+        .filter(|imp| !imp.external)
+        .map(|imp| (imp.target_module.to_dotted(), imp.import_line, imp.column))
+        .collect();
+        // The tab counts as one byte, like every other character
+        assert_eq!(
+            found,
+            [("pkg.a".to_string(), 1, 0), ("pkg.b".to_string(), 2, 11)]
+        );
+    }
+
+    #[test]
+    fn external_imports_are_kept_and_flagged() {
+        let (_tmp, resolver) = fixture(&["a"]);
+        let module = ModulePath::from_dotted("pkg.main");
+        let source = "import os.path\nfrom requests import get\nimport pkg.a\n";
+        let found: Vec<(String, bool)> = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|imp| (imp.target_module.to_dotted(), imp.external))
+        .collect();
+        assert_eq!(
+            found,
+            [
+                ("os.path".to_string(), true),
+                ("requests".to_string(), true),
+                ("pkg.a".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn ignore_comments_are_recorded() {
+        let (_tmp, resolver) = fixture(&["a", "b", "c"]);
+        let module = ModulePath::from_dotted("pkg.main");
+        let source = "import pkg.a  # importee: ignore\n\
+                      import pkg.b  # noqa # importee: ignore[Linear, Forbidden]\n\
+                      import pkg.c  # importee: ignored\n";
+        let found: Vec<(String, Vec<String>)> = get_file_imports(
+            &module,
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|imp| (imp.target_module.to_dotted(), imp.ignored_rules))
+        .collect();
+        assert_eq!(
+            found,
+            [
+                ("pkg.a".to_string(), vec!["*".to_string()]),
+                (
+                    "pkg.b".to_string(),
+                    vec!["Linear".to_string(), "Forbidden".to_string()]
+                ),
+                ("pkg.c".to_string(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn visibility_directive_is_read_from_comment_lines() {
+        let source = "\"\"\"Doc.\"\"\"\n# importee: visibility = internal\nimport os\n";
+        assert_eq!(parse_visibility_directive(source), Some("internal"));
+        assert_eq!(
+            parse_visibility_directive("# importee:visibility=private\n"),
+            Some("private")
+        );
+        // Only whole-line comments count
+        assert_eq!(
+            parse_visibility_directive("x = 1  # importee: visibility=private\n"),
+            None
+        );
+        assert_eq!(parse_visibility_directive("import os\n"), None);
+    }
+
+    #[test]
+    fn dunder_all_literals_are_collected() {
+        let source = "__all__ = ['Client', \"connect\"]\n__all__ += ('Error',)\nx = 1\n";
+        assert_eq!(
+            parse_dunder_all(source),
+            Some(vec![
+                String::from("Client"),
+                String::from("connect"),
+                String::from("Error")
+            ])
+        );
+        assert_eq!(
+            parse_dunder_all("__all__: list[str] = []\n"),
+            Some(Vec::new())
+        );
+        assert_eq!(parse_dunder_all("def __all__():\n    pass\n"), None);
+        assert_eq!(parse_dunder_all("import os\n"), None);
+    }
+}
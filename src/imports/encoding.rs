@@ -0,0 +1,69 @@
+//! Decoding Python sources by their PEP 263 `coding` declaration.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use encoding_rs::Encoding;
+use once_cell::sync::Lazy;
+use regex::bytes::Regex;
+
+/// PEP 263: `# -*- coding: latin-1 -*-`, `# vim: set fileencoding=utf-8 :`, ...
+static CODING_DECLARATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[ \t\f]*#.*?coding[:=][ \t]*([-\w.]+)").unwrap());
+
+/// Read a Python source file as raw bytes and as decoded text.
+pub fn read_source(path: &Path) -> io::Result<(Vec<u8>, String)> {
+    let bytes = fs::read(path)?;
+    let text = decode_source(&bytes);
+    Ok((bytes, text))
+}
+
+/// Decode a Python source with the encoding declared on its first two lines (or its UTF-8
+/// BOM), falling back to lossy UTF-8 for undeclared or unknown encodings.
+pub fn decode_source(bytes: &[u8]) -> String {
+    if let Some(encoding) = declared_encoding(bytes) {
+        let (text, _, _) = encoding.decode(bytes);
+        return text.into_owned();
+    }
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn declared_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return Some(encoding);
+    }
+    // Only the first two lines may declare it
+    bytes.split(|byte| *byte == b'\n').take(2).find_map(|line| {
+        let label = CODING_DECLARATION.captures(line)?.get(1)?.as_bytes();
+        // Python spellings like `latin_1` or `latin-1` aren't all WHATWG labels
+        let normalized: Vec<u8> = label
+            .iter()
+            .map(|byte| if *byte == b'_' { b'-' } else { *byte })
+            .collect();
+        let squashed: Vec<u8> = normalized
+            .iter()
+            .copied()
+            .filter(|byte| *byte != b'-')
+            .collect();
+        Encoding::for_label(&normalized).or_else(|| Encoding::for_label(&squashed))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_source;
+
+    #[test]
+    fn declared_encodings_are_honored() {
+        let latin1 = b"# -*- coding: latin-1 -*-\n# caf\xe9\nimport os\n";
+        assert_eq!(
+            decode_source(latin1),
+            "# -*- coding: latin-1 -*-\n# caf\u{e9}\nimport os\n"
+        );
+        let second_line = b"#!/usr/bin/env python\n# vim: set fileencoding=iso_8859_15 :\n\xa4\n";
+        assert!(decode_source(second_line).ends_with("\u{20ac}\n"));
+        // Undeclared invalid UTF-8 is decoded lossily rather than rejected
+        assert_eq!(decode_source(b"# caf\xe9\n"), "# caf\u{fffd}\n");
+    }
+}
@@ -1,11 +1,60 @@
 use crate::module_path::ModulePath;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug)]
+/// Syntactic form of an import statement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// `import pkg.sub`
+    #[default]
+    Import,
+    /// `from pkg import sub`
+    FromImport,
+    /// `from . import sub`, `from ..pkg import sub`
+    RelativeImport,
+    /// `importlib.import_module("pkg.sub")`, `__import__("pkg.sub")` (see
+    /// `detect_dynamic_imports`)
+    DynamicImport,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ImportLine {
     pub from_module: ModulePath,
     pub target_module: ModulePath,
     pub import_line: u32,
+    /// Byte offset of the import statement within its line (0-based)
+    #[serde(default)]
+    pub column: i32,
+    /// Target resolved outside the project (third-party or standard library)
+    #[serde(default)]
+    pub external: bool,
+    /// Rules suppressed on this line by `# importee: ignore[...]`; `*` suppresses every rule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_rules: Vec<String>,
+    #[serde(default)]
+    pub kind: ImportKind,
+    /// `from x import *`
+    #[serde(default)]
+    pub wildcard: bool,
+    /// Name the import is bound to with `as` (`import numpy as np`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Names imported from the target that aren't submodules (`from pkg import a, b`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+}
+
+/// Marker in `ignored_rules` for a bare `# importee: ignore`.
+pub const IGNORE_ALL: &str = "*";
+
+impl ImportLine {
+    /// Whether issues of `rule_name` are suppressed on this import line.
+    pub fn is_ignored(&self, rule_name: &str) -> bool {
+        self.ignored_rules
+            .iter()
+            .any(|rule| rule == IGNORE_ALL || rule == rule_name)
+    }
 }
 
 impl fmt::Display for ImportLine {
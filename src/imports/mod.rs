@@ -1,3 +1,5 @@
+pub mod artifact;
 pub mod classification;
 pub mod collection;
+pub mod encoding;
 pub mod import_line;
@@ -1,8 +1,36 @@
+mod blame;
 mod configs;
+mod debug_report;
 mod file_processor;
+mod graph;
 mod imports;
 mod module_path;
+mod profile;
 mod py_api;
 mod results;
 mod rules;
+mod run_context;
+#[cfg(test)]
+mod test_support;
 mod walker;
+mod watch;
+
+/// Entry points for the benchmarks in `benches/`; not part of the extension's API.
+#[doc(hidden)]
+pub mod bench {
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::imports::classification::ImportResolver;
+    use crate::imports::collection::get_file_imports;
+    use crate::module_path::ModulePath;
+    use crate::run_context::RunContext;
+
+    /// Collect the imports of `source` as module `bench.module`, returning how many were found.
+    pub fn collect_imports(source: &str) -> usize {
+        let resolver = ImportResolver::new("bench", Some(ModulePath::from_dotted("bench")), false);
+        let module = ModulePath::from_dotted("bench.module");
+        let run = RunContext::new(&ProjectConfig::default(), RunConfig::default());
+        get_file_imports(&module, &resolver, &run, Some(source))
+            .map(|imports| imports.len())
+            .unwrap_or(0)
+    }
+}
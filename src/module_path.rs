@@ -41,6 +41,14 @@ impl ModulePath {
             .all(|(a, b)| a == b)
     }
 
+    /// Whether this ModulePath, or one of its parent packages, matches `pattern`: like
+    /// `starts_with`, but a `*` in a pattern segment matches any characters within one
+    /// segment and a `**` segment matches any number of segments.
+    /// Example: "pkg.*.internal" matches "pkg.a.internal.x" but not "pkg.a.b.internal".
+    pub fn starts_with_pattern(&self, pattern: &ModulePath) -> bool {
+        prefix_matches(&pattern.segments, &self.segments)
+    }
+
     /// Return the relative ModulePath by stripping the given base prefix.
     /// Example: self="importee.path.api", base="importee" => Some("path.api").
     /// If `self` doesn't start with `base`, returns None.
@@ -81,15 +89,57 @@ impl ModulePath {
     /// Interpret this ModulePath as a file module and return its .py file path.
     /// If empty, returns an empty PathBuf.
     pub fn file_path(&self) -> std::path::PathBuf {
+        self.file_path_with_extension("py")
+    }
+
+    /// Like `file_path`, with another extension (e.g. "pyi" for stubs).
+    pub fn file_path_with_extension(&self, extension: &str) -> std::path::PathBuf {
         if let Some((leaf, parent)) = self.split_last() {
             let mut buf = parent.to_dir_pathbuf();
-            buf.push(format!("{}.py", leaf));
+            buf.push(format!("{}.{}", leaf, extension));
             buf
         } else {
             std::path::PathBuf::new()
         }
     }
 
+    /// File holding this module's source, for a module whose top-level package lives under
+    /// `base` (e.g. `src` for the src layout, or an empty path for the cwd): the first
+    /// existing file with one of `extensions` (see `RunConfig::module_extensions`), else the
+    /// one with the first extension.
+    pub fn source_file(&self, base: &std::path::Path, extensions: &[String]) -> std::path::PathBuf {
+        extensions
+            .iter()
+            .map(|extension| base.join(self.file_path_with_extension(extension)))
+            .find(|file| file.is_file())
+            .unwrap_or_else(|| {
+                base.join(
+                    self.file_path_with_extension(extensions.first().map_or("py", |e| e.as_str())),
+                )
+            })
+    }
+
+    /// Build a ModulePath from a relative file path (e.g., "foo/bar.py" => "foo.bar").
+    /// Returns None when the path has no file stem.
+    pub fn from_file_path(path: &std::path::Path) -> Option<ModulePath> {
+        let mut segments: Vec<String> = path
+            .parent()
+            .map(|parent| {
+                parent
+                    .components()
+                    .filter_map(|c| match c {
+                        std::path::Component::Normal(seg) => {
+                            Some(seg.to_string_lossy().to_string())
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        segments.push(path.file_stem()?.to_str()?.to_string());
+        Some(ModulePath::new(segments))
+    }
+
     /// Build a ModulePath from a dotted string (e.g., "foo.bar").
     /// Empty or all-dot strings produce an empty ModulePath.
     pub fn from_dotted(dotted: &str) -> ModulePath {
@@ -196,10 +246,62 @@ impl<'de> Deserialize<'de> for ModulePath {
     }
 }
 
+fn prefix_matches(pattern: &[String], segments: &[String]) -> bool {
+    match pattern.split_first() {
+        // The rest of the path is a submodule of the matched module
+        None => true,
+        Some((first, rest)) if first == "**" => {
+            (0..=segments.len()).any(|skip| prefix_matches(rest, &segments[skip..]))
+        }
+        Some((first, rest)) => segments.split_first().is_some_and(|(segment, tail)| {
+            segment_matches(first, segment) && prefix_matches(rest, tail)
+        }),
+    }
+}
+
+/// fnmatch-style match of one segment, where `*` matches any characters.
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = segment.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 #[cfg(test)]
 mod tests {
     use super::ModulePath;
 
+    #[test]
+    fn wildcard_patterns_match_segments() {
+        let matches = |target: &str, pattern: &str| {
+            ModulePath::from_dotted(target).starts_with_pattern(&ModulePath::from_dotted(pattern))
+        };
+        assert!(matches("pkg.a.internal", "pkg.*.internal"));
+        assert!(matches("pkg.a.internal.models", "pkg.*.internal"));
+        assert!(!matches("pkg.a.b.internal", "pkg.*.internal"));
+        assert!(matches("pkg.a.internal", "pkg.**"));
+        assert!(matches("pkg.a.b.internal", "pkg.**"));
+        assert!(matches("pkg.a.b.internal", "pkg.**.internal"));
+        assert!(matches("app.users.migrations", "*.*.migrations"));
+        assert!(matches("app.test_views", "app.test_*"));
+        assert!(!matches("app.views", "app.test_*"));
+        assert!(matches("app.views", "app"));
+        assert!(!matches("application", "app"));
+    }
+
     #[test]
     fn dotted_roundtrip_and_path() {
         let mp = ModulePath::from_dotted("foo.bar");
@@ -227,4 +329,10 @@ mod tests {
         let out = ModulePath::from_import(&cur, "..d");
         assert_eq!(out.to_dotted(), "a.d");
     }
+
+    #[test]
+    fn from_file_path_strips_extension() {
+        let out = ModulePath::from_file_path(std::path::Path::new("app/src/a.py")).unwrap();
+        assert_eq!(out.to_dotted(), "app.src.a");
+    }
 }
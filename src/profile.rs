@@ -0,0 +1,149 @@
+//! Optional per-phase timings of a run (`profile` run option), for performance tuning.
+//! Phases run on many rayon threads at once, so their durations are summed over threads and
+//! can add up to more than the wall-clock `total_ms`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::configs::RunConfig;
+use crate::run_context::RunContext;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Phase {
+    /// Listing directories
+    Walk,
+    /// Parsing source files
+    Parse,
+    /// Running line and project rules
+    Rules,
+    CacheLoad,
+    CacheSave,
+}
+
+/// Nanoseconds spent per phase, shared by every thread of a run (see `RunContext`).
+#[derive(Debug, Default)]
+pub struct PhaseTimings {
+    started: OnceLock<Instant>,
+    walk: AtomicU64,
+    parse: AtomicU64,
+    rules: AtomicU64,
+    cache_load: AtomicU64,
+    cache_save: AtomicU64,
+}
+
+impl PhaseTimings {
+    fn counter(&self, phase: Phase) -> &AtomicU64 {
+        match phase {
+            Phase::Walk => &self.walk,
+            Phase::Parse => &self.parse,
+            Phase::Rules => &self.rules,
+            Phase::CacheLoad => &self.cache_load,
+            Phase::CacheSave => &self.cache_save,
+        }
+    }
+}
+
+/// `timings` of the serialized result, in milliseconds.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct Timings {
+    pub total_ms: f64,
+    pub walk_ms: f64,
+    pub parse_ms: f64,
+    pub rules_ms: f64,
+    pub cache_load_ms: f64,
+    pub cache_save_ms: f64,
+}
+
+impl Timings {
+    /// Add the phase durations of another run, e.g. of another project checked along; the
+    /// `total_ms` wall clock is left alone.
+    pub fn add_phases(&mut self, other: &Timings) {
+        self.walk_ms += other.walk_ms;
+        self.parse_ms += other.parse_ms;
+        self.rules_ms += other.rules_ms;
+        self.cache_load_ms += other.cache_load_ms;
+        self.cache_save_ms += other.cache_save_ms;
+    }
+}
+
+fn enabled(run_config: &RunConfig) -> bool {
+    run_config.profile.unwrap_or(false)
+}
+
+/// Start the run's wall clock.
+pub fn start(run_config: &RunContext) {
+    if enabled(run_config) {
+        run_config.timings.started.get_or_init(Instant::now);
+    }
+}
+
+/// Run `f`, adding its duration to `phase` when profiling. Costs a single check otherwise.
+pub fn time<T>(run_config: &RunContext, phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !enabled(run_config) {
+        return f();
+    }
+    let start = Instant::now();
+    let out = f();
+    let nanos = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    run_config
+        .timings
+        .counter(phase)
+        .fetch_add(nanos, Ordering::Relaxed);
+    out
+}
+
+/// Timings so far, or `None` when not profiling.
+pub fn snapshot(run_config: &RunContext) -> Option<Timings> {
+    if !enabled(run_config) {
+        return None;
+    }
+    let timings = &run_config.timings;
+    let ms = |phase: Phase| timings.counter(phase).load(Ordering::Relaxed) as f64 / 1e6;
+    Some(Timings {
+        total_ms: timings
+            .started
+            .get()
+            .map_or(0.0, |started| started.elapsed().as_secs_f64() * 1e3),
+        walk_ms: ms(Phase::Walk),
+        parse_ms: ms(Phase::Parse),
+        rules_ms: ms(Phase::Rules),
+        cache_load_ms: ms(Phase::CacheLoad),
+        cache_save_ms: ms(Phase::CacheSave),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::test_support::TempProject;
+    use crate::walker::run_check_imports;
+
+    #[test]
+    fn timings_are_only_reported_when_profiling() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/low.py", "import app.high\n");
+        project.write("app/high.py", "");
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": ["low", "high"]}]},
+        }))
+        .unwrap();
+        let run = |profile: Option<bool>| RunConfig {
+            no_cache: Some(true),
+            profile,
+            ..RunConfig::default()
+        };
+
+        let timings = run_check_imports(config.clone(), run(Some(true)))
+            .timings
+            .unwrap();
+        assert!(timings.total_ms > 0.0);
+        assert!(timings.parse_ms > 0.0);
+        assert_eq!(timings.cache_save_ms, 0.0);
+        assert!(run_check_imports(config, run(None)).timings.is_none());
+    }
+}
@@ -1,28 +1,379 @@
+// pyo3 0.22's generated wrappers trip this lint on recent toolchains
+#![allow(clippy::useless_conversion)]
+
+use std::cell::RefCell;
+use std::sync::mpsc;
+
 use pyo3::prelude::*;
 
 use crate::configs::{ProjectConfig, RunConfig};
-use crate::walker::run_check_imports;
+use crate::graph::DependencyGraph;
+use crate::imports::artifact::{read_artifact, write_artifact, Artifact, ArtifactError};
+use crate::module_path::ModulePath;
+use crate::results::CheckResult;
+use crate::run_context::RunContext;
+use crate::walker::{
+    run_check_file, run_check_imports, run_check_imports_in, run_check_projects, run_check_source,
+    run_collect_imports, run_evaluate_imports, run_list_files, CollectedImports,
+};
 
-/// Run the importee checker, parse the project and run config and return the results as a string
-#[pyfunction]
-fn check_imports(project_config: String, run_config: String) -> PyResult<String> {
-    let project_config: ProjectConfig = serde_json::from_str(&project_config).map_err(|e| {
+/// Parse the JSON project and run configs passed from Python
+fn parse_configs(project_config: &str, run_config: &str) -> PyResult<(ProjectConfig, RunConfig)> {
+    let project_config: ProjectConfig = serde_json::from_str(project_config).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("project_config json error: {}", e))
     })?;
-    let run_config: RunConfig = serde_json::from_str(&run_config).map_err(|e| {
+    Ok((project_config, parse_run_config(run_config)?))
+}
+
+fn parse_run_config(run_config: &str) -> PyResult<RunConfig> {
+    serde_json::from_str(run_config).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("run_config json error: {}", e))
-    })?;
+    })
+}
 
-    let result = run_check_imports(project_config, run_config);
-    let json = serde_json::to_string(&result).map_err(|e| {
+/// Serialize a check result to the JSON handed back to Python
+fn result_json(result: &CheckResult) -> PyResult<String> {
+    serde_json::to_string(result).map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("serialize error: {}", e))
+    })
+}
+
+/// Run the importee checker, parse the project and run config and return the results as a string
+#[pyfunction]
+fn check_imports(project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    result_json(&run_check_imports(project_config, run_config))
+}
+
+/// Check several independent projects, given as a JSON array of project configs, and return
+/// their merged results as a string
+#[pyfunction]
+fn check_projects(projects: String, run_config: String) -> PyResult<String> {
+    let projects: Vec<ProjectConfig> = serde_json::from_str(&projects).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("projects json error: {}", e))
     })?;
-    Ok(json)
+    result_json(&run_check_projects(
+        projects,
+        parse_run_config(&run_config)?,
+    ))
+}
+
+/// Check a single file (e.g. on save in an editor) and return the results as a string
+#[pyfunction]
+fn check_file(project_config: String, run_config: String, file_path: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    result_json(&run_check_file(
+        project_config,
+        run_config,
+        std::path::Path::new(&file_path),
+    ))
+}
+
+/// Check the source of a module given by dotted name, e.g. an unsaved editor buffer, without
+/// reading its file, and return the results as a string
+#[pyfunction]
+fn check_source(
+    project_config: String,
+    run_config: String,
+    module_dotted: String,
+    source: String,
+) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    result_json(&run_check_source(
+        project_config,
+        run_config,
+        &ModulePath::from_dotted(&module_dotted),
+        &source,
+    ))
+}
+
+/// Run the importee checker and return the issues as CSV, for spreadsheet triage
+#[pyfunction]
+fn check_imports_csv(project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    Ok(run_check_imports(project_config, run_config).to_csv())
+}
+
+/// Run the importee checker and return the issues as TOML grouped by file, for reading
+#[pyfunction]
+fn check_imports_toml(project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    Ok(run_check_imports(project_config, run_config).to_toml())
+}
+
+/// Run the importee checker and return the results as a JUnit XML report, with a test case
+/// per file the check covers
+#[pyfunction]
+fn check_imports_junit(project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    Ok(run_check_imports(project_config, run_config).to_junit_xml())
+}
+
+/// Run the importee checker and return the results as a SARIF 2.1.0 log
+#[pyfunction]
+fn check_imports_sarif(project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    serde_json::to_string_pretty(&run_check_imports(project_config, run_config).to_sarif()).map_err(
+        |e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("serialize error: {}", e)),
+    )
+}
+
+/// Collect every file and import of the project, raising its diagnostics (e.g. the cache
+/// warning) as Python `RuntimeWarning`s since there is no result to carry them.
+fn collect(
+    py: Python<'_>,
+    project_config: &ProjectConfig,
+    run_config: &RunConfig,
+) -> PyResult<CollectedImports> {
+    let collected = run_collect_imports(project_config, run_config);
+    for diagnostic in &collected.diagnostics {
+        let category = py.get_type_bound::<pyo3::exceptions::PyRuntimeWarning>();
+        PyErr::warn_bound(py, &category, diagnostic, 1)?;
+    }
+    Ok(collected)
+}
+
+fn artifact_err(e: ArtifactError) -> PyErr {
+    match e {
+        ArtifactError::Io(_) => PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()),
+        _ => PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()),
+    }
+}
+
+/// Walk the project and write every file and collected import to an artifact at
+/// `out_path`, without evaluating rules. Returns the number of imports written.
+#[pyfunction]
+fn collect_only(
+    py: Python<'_>,
+    project_config: String,
+    run_config: String,
+    out_path: String,
+) -> PyResult<usize> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    let collected = collect(py, &project_config, &run_config)?;
+    let artifact = Artifact {
+        files: collected.files,
+        imports: collected.imports,
+    };
+    write_artifact(std::path::Path::new(&out_path), &artifact).map_err(artifact_err)?;
+    Ok(artifact.imports.len())
+}
+
+/// Evaluate rules against an artifact written by `collect_only` and return the results as
+/// a string, without reading any source file
+#[pyfunction]
+fn evaluate_from_artifact(
+    project_config: String,
+    run_config: String,
+    artifact_path: String,
+) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    let artifact = read_artifact(std::path::Path::new(&artifact_path)).map_err(artifact_err)?;
+    result_json(&run_evaluate_imports(
+        &project_config,
+        &run_config,
+        &artifact,
+    ))
+}
+
+/// Export the first-party dependency graph as JSON `{"nodes": [...], "edges": [...]}`,
+/// where each edge carries the number of imports creating it
+#[pyfunction]
+fn dump_edges(py: Python<'_>, project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    let imports = collect(py, &project_config, &run_config)?.imports;
+    serde_json::to_string(&DependencyGraph::from_imports(&imports)).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("serialize error: {}", e))
+    })
+}
+
+/// Export the first-party dependency graph as a JSON adjacency list `{"pkg.a": ["pkg.b"]}`,
+/// without evaluating any rule. Lists are deduplicated and sorted
+#[pyfunction]
+fn dependency_graph(
+    py: Python<'_>,
+    project_config: String,
+    run_config: String,
+) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    let imports = collect(py, &project_config, &run_config)?.imports;
+    serde_json::to_string(&DependencyGraph::from_imports(&imports).adjacency()).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("serialize error: {}", e))
+    })
+}
+
+/// Dry run: list the files a check would scan as a JSON array of paths, without checking
+/// them. Useful to see why a file is (not) linted
+#[pyfunction]
+fn list_files(project_config: String, run_config: String) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    serde_json::to_string(&run_list_files(&project_config, &run_config)).map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("serialize error: {}", e))
+    })
+}
+
+/// Like `check_imports`, calling `callback(files_done, files_total)` after each file, where
+/// the total is the number of files a full check scans. The check runs on worker threads
+/// with the GIL released; the callback is only called from the calling thread. An exception
+/// raised by the callback is re-raised once the check is over.
+#[pyfunction]
+fn check_imports_with_progress(
+    py: Python<'_>,
+    project_config: String,
+    run_config: String,
+    callback: PyObject,
+) -> PyResult<String> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    let total = run_list_files(&project_config, &run_config).len();
+    let (sender, receiver) = mpsc::channel();
+    let run = RunContext::new(&project_config, run_config).with_progress(sender);
+
+    let (result, callback_error) = py.allow_threads(move || {
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(move || run_check_imports_in(project_config, &run));
+            // Ends once the run is over and has dropped its sender
+            let mut callback_error = None;
+            for done in receiver {
+                if callback_error.is_none() {
+                    callback_error = Python::with_gil(|py| callback.call1(py, (done, total)).err());
+                }
+            }
+            (worker.join(), callback_error)
+        })
+    });
+    if let Some(e) = callback_error {
+        return Err(e);
+    }
+    let result = result
+        .map_err(|_| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("check_imports panicked"))?;
+    result_json(&result)
+}
+
+/// Watch the source directories until interrupted, calling `callback(path, result_json)`
+/// with the `check_imports`-style results of each changed module file (an empty result when
+/// it was deleted). Changes are debounced; project rules are not evaluated. Waiting happens
+/// with the GIL released; an exception raised by the callback ends the watch and is re-raised.
+#[pyfunction]
+fn watch(
+    py: Python<'_>,
+    project_config: String,
+    run_config: String,
+    callback: PyObject,
+) -> PyResult<()> {
+    let (project_config, run_config) = parse_configs(&project_config, &run_config)?;
+    let (outcome, error) = py.allow_threads(move || {
+        let error: RefCell<Option<PyErr>> = RefCell::new(None);
+        let outcome = crate::watch::watch_sources(
+            &project_config,
+            &run_config,
+            |path, result| {
+                let called = Python::with_gil(|py| {
+                    callback
+                        .call1(py, (path.to_string_lossy(), result_json(&result)?))
+                        .map(|_| ())
+                });
+                if let Err(e) = called {
+                    error.borrow_mut().get_or_insert(e);
+                }
+            },
+            || {
+                // Lets Ctrl-C end the watch
+                if let Err(e) = Python::with_gil(|py| py.check_signals()) {
+                    error.borrow_mut().get_or_insert(e);
+                }
+                error.borrow().is_none()
+            },
+        );
+        (outcome, error.into_inner())
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+    outcome.map_err(|e| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("watch error: {}", e))
+    })
+}
+
+/// Dotted module path with the same semantics as the checker, e.g. for resolving relative
+/// imports the way it does
+#[pyclass(name = "ModulePath", frozen, eq)]
+#[derive(Clone, PartialEq)]
+struct PyModulePath(ModulePath);
+
+#[pymethods]
+impl PyModulePath {
+    #[new]
+    fn new(segments: Vec<String>) -> Self {
+        PyModulePath(ModulePath::new(segments))
+    }
+
+    #[staticmethod]
+    fn from_dotted(dotted: &str) -> Self {
+        PyModulePath(ModulePath::from_dotted(dotted))
+    }
+
+    /// Resolve `import` (e.g. `..models`) as written in the module `current`
+    #[staticmethod]
+    fn from_import(current: &PyModulePath, import: &str) -> Self {
+        PyModulePath(ModulePath::from_import(&current.0, import))
+    }
+
+    #[getter]
+    fn segments(&self) -> Vec<String> {
+        self.0.segments().to_vec()
+    }
+
+    fn to_dotted(&self) -> String {
+        self.0.to_dotted()
+    }
+
+    /// The rest of the path below `base`, or None when it is not under `base`
+    fn relative_from(&self, base: &PyModulePath) -> Option<Self> {
+        self.0.relative_from(&base.0).map(PyModulePath)
+    }
+
+    fn starts_with(&self, base: &PyModulePath) -> bool {
+        self.0.starts_with(&base.0)
+    }
+
+    fn append(&self, segment: String) -> Self {
+        PyModulePath(self.0.append(segment))
+    }
+
+    /// `(last, parent)`, or None for the empty path
+    fn split_last(&self) -> Option<(String, Self)> {
+        self.0
+            .split_last()
+            .map(|(last, parent)| (last, PyModulePath(parent)))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_dotted()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ModulePath('{}')", self.0.to_dotted())
+    }
 }
 
 /// Python module definition
 #[pymodule]
 fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(check_imports, m)?)?;
+    m.add_function(wrap_pyfunction!(check_file, m)?)?;
+    m.add_function(wrap_pyfunction!(check_source, m)?)?;
+    m.add_function(wrap_pyfunction!(check_projects, m)?)?;
+    m.add_function(wrap_pyfunction!(check_imports_with_progress, m)?)?;
+    m.add_function(wrap_pyfunction!(check_imports_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(check_imports_toml, m)?)?;
+    m.add_function(wrap_pyfunction!(check_imports_sarif, m)?)?;
+    m.add_function(wrap_pyfunction!(check_imports_junit, m)?)?;
+    m.add_function(wrap_pyfunction!(collect_only, m)?)?;
+    m.add_function(wrap_pyfunction!(dump_edges, m)?)?;
+    m.add_function(wrap_pyfunction!(dependency_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_from_artifact, m)?)?;
+    m.add_function(wrap_pyfunction!(list_files, m)?)?;
+    m.add_function(wrap_pyfunction!(watch, m)?)?;
+    m.add_class::<PyModulePath>()?;
     Ok(())
 }
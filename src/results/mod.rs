@@ -1,20 +1,571 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
 
-#[derive(Serialize, Debug, Default)]
+use crate::module_path::ModulePath;
+use crate::profile::Timings;
+
+#[derive(Serialize, Debug)]
 pub struct CheckResult {
+    /// No issue of `error` severity (warnings and infos don't fail a run)
+    pub ok: bool,
+    /// Issues of `error` severity
+    pub error_count: usize,
     pub issues: Vec<Issue>,
+    pub summary: Summary,
+    /// Run-level messages that are not tied to an import (e.g. cache problems)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+    /// Time spent per phase, with the `profile` run option
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<Timings>,
+    /// Files the rules were run on, as reported, so `to_junit_xml` can list those without
+    /// issues too
+    #[serde(skip)]
+    pub files: Vec<String>,
 }
 
-#[derive(Serialize, Debug)]
+/// Issue counts of a run, filled in once every issue is known.
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub total: usize,
+    pub by_rule: BTreeMap<String, usize>,
+    pub by_severity: BTreeMap<Severity, usize>,
+    /// Python files the rules were run on
+    pub files_scanned: usize,
+}
+
+impl Summary {
+    pub fn from_issues(issues: &[Issue], files_scanned: usize) -> Self {
+        let mut summary = Summary {
+            total: issues.len(),
+            files_scanned,
+            ..Summary::default()
+        };
+        for issue in issues {
+            *summary.by_rule.entry(issue.rule_name.clone()).or_insert(0) += 1;
+            *summary.by_severity.entry(issue.severity).or_insert(0) += 1;
+        }
+        summary
+    }
+}
+
+/// How serious an issue is. Ordered from least to most severe.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    #[default]
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Issue {
     pub rule_name: String,
+    pub severity: Severity,
     pub path: String,
     pub line: u32,
+    /// Byte column of the import within its line (0-based)
+    pub column: i32,
     pub message: String,
+    /// Author of the import line, with `blame` enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Commit that last changed the import line, with `blame` enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Suggested edit resolving the issue, for rules where a mechanical fix exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+}
+
+/// Replace the text between two positions of the issue's file with `replacement`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub range: FixRange,
+    pub replacement: String,
+}
+
+/// Lines are 1-based and columns 0-based byte offsets, like issue locations; the end is
+/// exclusive, so `line + 1`, column 0 covers a whole line with its newline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FixRange {
+    pub start_line: u32,
+    pub start_column: i32,
+    pub end_line: u32,
+    pub end_column: i32,
 }
 
 impl CheckResult {
     pub fn new() -> Self {
-        Self { issues: Vec::new() }
+        Self {
+            ok: true,
+            error_count: 0,
+            issues: Vec::new(),
+            summary: Summary::default(),
+            diagnostics: Vec::new(),
+            timings: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Fill in `summary`, `ok` and `error_count` once every issue is known.
+    pub fn summarize(&mut self, files_scanned: usize) {
+        self.summary = Summary::from_issues(&self.issues, files_scanned);
+        self.error_count = self
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .count();
+        self.ok = self.error_count == 0;
+    }
+
+    /// One `budget-exceeded` diagnostic per `(module_prefix, max_issues)` budget whose
+    /// directory has more issues than allowed. Issues themselves are kept.
+    pub fn budget_diagnostics(&self, budgets: &[(String, usize)]) -> Vec<String> {
+        let modules: Vec<Option<ModulePath>> = self
+            .issues
+            .iter()
+            .map(|issue| ModulePath::from_file_path(Path::new(&issue.path)))
+            .collect();
+        budgets
+            .iter()
+            .filter_map(|(prefix, max_issues)| {
+                let prefix_mp = ModulePath::from_dotted(prefix);
+                let count = modules
+                    .iter()
+                    .flatten()
+                    .filter(|module| module.starts_with(&prefix_mp))
+                    .count();
+                (count > *max_issues).then(|| {
+                    format!(
+                        "budget-exceeded: '{}' has {} issues (budget {})",
+                        prefix, count, max_issues
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Render issues as CSV (`path,line,rule_name,severity,message`) with a header row,
+    /// ordered by path then line.
+    pub fn to_csv(&self) -> String {
+        let mut ordered: Vec<&Issue> = self.issues.iter().collect();
+        ordered.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+
+        let mut out = String::from("path,line,rule_name,severity,message\n");
+        for issue in ordered {
+            let fields = [
+                csv_field(&issue.path),
+                issue.line.to_string(),
+                csv_field(&issue.rule_name),
+                issue.severity.as_str().to_string(),
+                csv_field(&issue.message),
+            ];
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Render issues as TOML for human inspection, grouped by file in path order:
+    /// `[[file."<path>".issue]]` tables with `rule`, `line` and `message`.
+    pub fn to_toml(&self) -> String {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            file: BTreeMap<&'a str, FileIssues<'a>>,
+        }
+        #[derive(Serialize)]
+        struct FileIssues<'a> {
+            issue: Vec<TomlIssue<'a>>,
+        }
+        #[derive(Serialize)]
+        struct TomlIssue<'a> {
+            rule: &'a str,
+            line: u32,
+            message: &'a str,
+        }
+
+        let mut report = Report {
+            file: BTreeMap::new(),
+        };
+        for issue in self.issues.iter() {
+            report
+                .file
+                .entry(issue.path.as_str())
+                .or_insert_with(|| FileIssues { issue: Vec::new() })
+                .issue
+                .push(TomlIssue {
+                    rule: &issue.rule_name,
+                    line: issue.line,
+                    message: &issue.message,
+                });
+        }
+        for issues in report.file.values_mut() {
+            issues.issue.sort_by_key(|issue| issue.line);
+        }
+        if report.file.is_empty() {
+            return String::new();
+        }
+        // Strings and integers always serialize
+        toml::to_string(&report).unwrap_or_default()
+    }
+
+    /// Render the results as a JUnit XML `<testsuite>` for CI test runners: one `<testcase>`
+    /// per file of `files` or with issues, ordered by path, and one `<failure>` per issue.
+    pub fn to_junit_xml(&self) -> String {
+        let mut by_file: BTreeMap<&str, Vec<&Issue>> = self
+            .files
+            .iter()
+            .map(|file| (file.as_str(), Vec::new()))
+            .collect();
+        for issue in self.issues.iter() {
+            by_file.entry(issue.path.as_str()).or_default().push(issue);
+        }
+        let failing = by_file.values().filter(|issues| !issues.is_empty()).count();
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuite name=\"importee\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+            by_file.len(),
+            failing
+        ));
+        for (file, mut issues) in by_file {
+            let file = xml_escape(file);
+            if issues.is_empty() {
+                out.push_str(&format!(
+                    "  <testcase classname=\"importee\" name=\"{}\"/>\n",
+                    file
+                ));
+                continue;
+            }
+            issues.sort_by_key(|issue| (issue.line, issue.column));
+            out.push_str(&format!(
+                "  <testcase classname=\"importee\" name=\"{}\">\n",
+                file
+            ));
+            for issue in issues {
+                let message = xml_escape(&issue.message);
+                out.push_str(&format!(
+                    "    <failure type=\"{}\" message=\"{}\">{}:{}: {}</failure>\n",
+                    xml_escape(&issue.rule_name),
+                    message,
+                    file,
+                    issue.line,
+                    message
+                ));
+            }
+            out.push_str("  </testcase>\n");
+        }
+        out.push_str("</testsuite>\n");
+        out
+    }
+
+    /// Render issues as a SARIF 2.1.0 log (e.g. for GitHub code scanning).
+    /// Each distinct rule name is registered once in `tool.driver.rules`.
+    pub fn to_sarif(&self) -> serde_json::Value {
+        let mut rule_ids: Vec<&str> = self.issues.iter().map(|i| i.rule_name.as_str()).collect();
+        rule_ids.sort_unstable();
+        rule_ids.dedup();
+
+        let results: Vec<serde_json::Value> = self
+            .issues
+            .iter()
+            .map(|issue| {
+                let level = match issue.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "note",
+                };
+                // SARIF lines and columns are 1-based; line 0 means "unknown line"
+                let mut location = json!({
+                    "artifactLocation": {"uri": issue.path.replace('\\', "/")},
+                });
+                if issue.line > 0 {
+                    location["region"] =
+                        json!({"startLine": issue.line, "startColumn": issue.column + 1});
+                }
+                json!({
+                    "ruleId": issue.rule_name,
+                    "ruleIndex": rule_ids.binary_search(&issue.rule_name.as_str()).unwrap_or(0),
+                    "level": level,
+                    "message": {"text": issue.message},
+                    "locations": [{"physicalLocation": location}],
+                })
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {"driver": {
+                    "name": "importee",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/NathanDuboisset/importee",
+                    "rules": rule_ids.iter().map(|id| json!({"id": id})).collect::<Vec<_>>(),
+                }},
+                "results": results,
+            }],
+        })
+    }
+}
+
+/// Escape text for an XML attribute or element.
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Other control characters are not allowed in XML 1.0 at all
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CheckResult, Issue, Severity};
+
+    /// Minimal RFC 4180 reader, enough to round-trip `to_csv` output.
+    fn parse_csv(input: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, in_quotes) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => in_quotes = !in_quotes,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                _ => field.push(c),
+            }
+        }
+        rows
+    }
+
+    fn issue(path: &str, line: u32, message: &str) -> Issue {
+        Issue {
+            rule_name: String::from("Linear"),
+            severity: Severity::Warning,
+            path: path.to_string(),
+            line,
+            column: 0,
+            message: message.to_string(),
+            author: None,
+            commit: None,
+            fix: None,
+        }
+    }
+
+    #[test]
+    fn junit_lists_every_file_and_escapes_messages() {
+        let mut result = CheckResult::new();
+        result.files = vec![String::from("pkg/a.py"), String::from("pkg/ok.py")];
+        result
+            .issues
+            .push(issue("pkg/a.py", 3, "imported \"x\" : <a> & 'b'"));
+        result.issues.push(issue("pkg/a.py", 1, "first"));
+        result.issues.push(issue("pkg/b.py", 0, "project rule"));
+
+        assert_eq!(
+            result.to_junit_xml(),
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<testsuite name=\"importee\" tests=\"3\" failures=\"2\" errors=\"0\">\n",
+                "  <testcase classname=\"importee\" name=\"pkg/a.py\">\n",
+                "    <failure type=\"Linear\" message=\"first\">pkg/a.py:1: first</failure>\n",
+                "    <failure type=\"Linear\" message=\"imported &quot;x&quot; : &lt;a&gt; &amp; &apos;b&apos;\">",
+                "pkg/a.py:3: imported &quot;x&quot; : &lt;a&gt; &amp; &apos;b&apos;</failure>\n",
+                "  </testcase>\n",
+                "  <testcase classname=\"importee\" name=\"pkg/b.py\">\n",
+                "    <failure type=\"Linear\" message=\"project rule\">pkg/b.py:0: project rule</failure>\n",
+                "  </testcase>\n",
+                "  <testcase classname=\"importee\" name=\"pkg/ok.py\"/>\n",
+                "</testsuite>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn sarif_maps_issues_and_rules() {
+        let mut result = CheckResult::new();
+        result.issues.push(issue("pkg/a.py", 4, "bad import"));
+        result.issues.push(Issue {
+            rule_name: String::from("Layered"),
+            severity: Severity::Error,
+            path: String::from("pkg/b.py"),
+            line: 0,
+            column: 0,
+            message: String::from("other"),
+            author: None,
+            commit: None,
+            fix: None,
+        });
+        result.issues.push(issue("pkg/c.py", 1, "again"));
+
+        let sarif = result.to_sarif();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            serde_json::json!([{"id": "Layered"}, {"id": "Linear"}])
+        );
+        let first = &run["results"][0];
+        assert_eq!(first["ruleId"], "Linear");
+        assert_eq!(first["ruleIndex"], 1);
+        assert_eq!(first["level"], "warning");
+        assert_eq!(first["message"]["text"], "bad import");
+        let location = &first["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "pkg/a.py");
+        assert_eq!(location["region"]["startLine"], 4);
+        assert_eq!(location["region"]["startColumn"], 1);
+        // Unknown lines carry no region
+        assert!(run["results"][1]["locations"][0]["physicalLocation"]
+            .get("region")
+            .is_none());
+    }
+
+    #[test]
+    fn csv_roundtrip_with_quoting() {
+        let mut result = CheckResult::new();
+        result.issues.push(issue("b.py", 3, "plain"));
+        result
+            .issues
+            .push(issue("a.py", 7, "imported \"x\" : a, b"));
+        result.issues.push(issue("a.py", 2, "multi\nline"));
+
+        let rows = parse_csv(&result.to_csv());
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[0],
+            ["path", "line", "rule_name", "severity", "message"]
+        );
+        assert_eq!(rows[1][..2], ["a.py", "2"]);
+        assert_eq!(rows[1][3], "warning");
+        assert_eq!(rows[1][4], "multi\nline");
+        assert_eq!(rows[2][..2], ["a.py", "7"]);
+        assert_eq!(rows[2][4], "imported \"x\" : a, b");
+        assert_eq!(rows[3][..2], ["b.py", "3"]);
+    }
+
+    #[test]
+    fn budgets_flag_only_directories_over_budget() {
+        let mut result = CheckResult::new();
+        result.issues.push(issue("app/api/a.py", 1, "x"));
+        result.issues.push(issue("app/api/b.py", 1, "x"));
+        result.issues.push(issue("app/core/c.py", 1, "x"));
+        result.issues.push(issue("app/apiary.py", 1, "x"));
+
+        let budgets = [
+            (String::from("app.api"), 1),
+            (String::from("app.core"), 1),
+            (String::from("app"), 4),
+        ];
+        assert_eq!(
+            result.budget_diagnostics(&budgets),
+            ["budget-exceeded: 'app.api' has 2 issues (budget 1)"]
+        );
+        // Issues are kept
+        assert_eq!(result.issues.len(), 4);
+    }
+
+    #[test]
+    fn summary_counts_by_rule_and_severity() {
+        let mut result = CheckResult::new();
+        result.issues.push(issue("a.py", 1, "x"));
+        result.issues.push(issue("b.py", 2, "x"));
+        result.issues.push(Issue {
+            rule_name: String::from("Forbidden"),
+            severity: Severity::Error,
+            ..issue("c.py", 3, "x")
+        });
+        result.summarize(5);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["error_count"], 1);
+        assert_eq!(
+            json["summary"],
+            serde_json::json!({
+                "total": 3,
+                "by_rule": {"Forbidden": 1, "Linear": 2},
+                "by_severity": {"warning": 2, "error": 1},
+                "files_scanned": 5,
+            })
+        );
+
+        // Warnings alone don't fail the run
+        result.issues.pop();
+        result.summarize(5);
+        assert!(result.ok);
+        assert_eq!(result.error_count, 0);
+    }
+
+    #[test]
+    fn toml_groups_issues_by_quoted_file_path() {
+        let mut result = CheckResult::new();
+        result
+            .issues
+            .push(issue("pkg/b.py", 3, "imported \"x\" : bad"));
+        result.issues.push(issue("pkg/my file.py", 2, "second"));
+        result.issues.push(issue("pkg/my file.py", 1, "first"));
+
+        let rendered = result.to_toml();
+        let parsed: toml::Value = toml::from_str(&rendered).unwrap();
+        let issues = parsed["file"]["pkg/my file.py"]["issue"]
+            .as_array()
+            .unwrap();
+        let messages: Vec<&str> = issues
+            .iter()
+            .map(|issue| issue["message"].as_str().unwrap())
+            .collect();
+        assert_eq!(messages, ["first", "second"]);
+        assert_eq!(
+            parsed["file"]["pkg/b.py"]["issue"][0]["message"].as_str(),
+            Some("imported \"x\" : bad")
+        );
+        assert_eq!(
+            parsed["file"]["pkg/b.py"]["issue"][0]["line"].as_integer(),
+            Some(3)
+        );
+        assert!(rendered.contains("[[file.\"pkg/my file.py\".issue]]"));
+        assert_eq!(CheckResult::new().to_toml(), "");
     }
 }
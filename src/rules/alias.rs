@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Forbids binding first-party imports to short aliases (`import app.models as m`), so a
+/// module is called by the same name across the codebase. Aliases of at least `min_length`
+/// characters, and aliases of third-party imports (`import numpy as np`), are fine.
+pub struct NoFirstPartyAliasRule {
+    source_folder: ModulePath,
+    min_length: usize,
+}
+
+impl NoFirstPartyAliasRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, min_length: usize) -> Self {
+        NoFirstPartyAliasRule {
+            source_folder,
+            min_length,
+        }
+    }
+}
+
+impl ImportRule for NoFirstPartyAliasRule {
+    fn name(&self) -> &'static str {
+        "NoFirstPartyAlias"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        match &import.alias {
+            Some(alias) if alias.chars().count() < self.min_length => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "local import '{}' aliased to short name '{}'",
+                    import.target_module.to_dotted(),
+                    alias
+                ),
+            },
+            _ => RuleOutcome {
+                pass: true,
+                reason: String::from("ok: no short alias"),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={} min_length={}", folder, self.min_length)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoFirstPartyAliasRule;
+    use crate::configs::RunConfig;
+    use crate::imports::classification::ImportResolver;
+    use crate::imports::collection::get_file_imports;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::{run_of, TempProject};
+    use std::path::Path;
+
+    #[test]
+    fn short_aliases_of_local_imports_fail() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/models.py", "");
+        project.write("app/services.py", "");
+        let resolver = ImportResolver::new(
+            Path::new("app"),
+            Some(ModulePath::from_dotted("app")),
+            false,
+        );
+        let rule = NoFirstPartyAliasRule::new(ModulePath::default(), 3);
+        let source = "import app.models as m\nfrom app import services as svc\n\
+                      from app.models import User as U\nimport app.services\n";
+        let imports = get_file_imports(
+            &ModulePath::from_dotted("app.api"),
+            &resolver,
+            &run_of(RunConfig::default()),
+            Some(source),
+        )
+        .unwrap();
+        let aliases: Vec<Option<&str>> = imports.iter().map(|imp| imp.alias.as_deref()).collect();
+        assert_eq!(aliases, [Some("m"), Some("svc"), Some("U"), None]);
+
+        let outcomes: Vec<(bool, String)> = imports
+            .iter()
+            .map(|imp| {
+                let outcome = rule.check_line(Path::new("app/api.py"), imp);
+                (outcome.pass, outcome.reason)
+            })
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                (
+                    false,
+                    String::from("local import 'app.models' aliased to short name 'm'")
+                ),
+                (true, String::from("ok: no short alias")),
+                (
+                    false,
+                    String::from("local import 'app.models' aliased to short name 'U'")
+                ),
+                (true, String::from("ok: no short alias")),
+            ]
+        );
+    }
+}
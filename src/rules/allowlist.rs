@@ -0,0 +1,108 @@
+use crate::graph::DependencyGraph;
+use crate::module_path::ModulePath;
+
+use super::{GraphViolation, ProjectRule};
+
+/// A protected package only the listed importers may import, the inverse of a fan-in limit:
+/// every edge into `protected` (or its submodules) from a module outside it and outside
+/// all of `allowed_importers` is reported at the importing line.
+pub struct ReverseAllowlistRule {
+    protected: ModulePath,
+    allowed_importers: Vec<ModulePath>,
+}
+
+impl ReverseAllowlistRule {
+    pub fn new(protected: ModulePath, allowed_importers: Vec<ModulePath>) -> Self {
+        ReverseAllowlistRule {
+            protected,
+            allowed_importers,
+        }
+    }
+
+    fn is_allowed(&self, importer: &ModulePath) -> bool {
+        importer.starts_with(&self.protected)
+            || self
+                .allowed_importers
+                .iter()
+                .any(|allowed| importer.starts_with(allowed))
+    }
+}
+
+impl ProjectRule for ReverseAllowlistRule {
+    fn name(&self) -> &'static str {
+        "ReverseAllowlist"
+    }
+
+    fn describe(&self) -> String {
+        let allowed = self
+            .allowed_importers
+            .iter()
+            .map(|m| m.to_dotted())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            "protected={} allowed_importers=[{}]",
+            self.protected.to_dotted(),
+            allowed
+        )
+    }
+
+    fn check_graph(&self, graph: &DependencyGraph) -> Vec<GraphViolation> {
+        graph
+            .edges
+            .iter()
+            .filter(|edge| ModulePath::from_dotted(&edge.to).starts_with(&self.protected))
+            .filter(|edge| !self.is_allowed(&ModulePath::from_dotted(&edge.from)))
+            .map(|edge| GraphViolation {
+                from: edge.from.clone(),
+                to: Some(edge.to.clone()),
+                reason: format!(
+                    "'{}' may only be imported by its allowlist, not by '{}'",
+                    self.protected.to_dotted(),
+                    edge.from
+                ),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReverseAllowlistRule;
+    use crate::graph::DependencyGraph;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ProjectRule;
+    use crate::test_support::import_of;
+
+    #[test]
+    fn only_allowlisted_modules_import_the_protected_package() {
+        let imports: Vec<ImportLine> = [
+            ("app.auth.login", "app.secret.keys"),
+            ("app.api.views", "app.secret"),
+            ("app.secret.keys", "app.secret.store"),
+            ("app.api.views", "app.secretary"),
+        ]
+        .iter()
+        .map(|(from, to)| import_of(from, to))
+        .collect();
+        let rule = ReverseAllowlistRule::new(
+            ModulePath::from_dotted("app.secret"),
+            vec![ModulePath::from_dotted("app.auth")],
+        );
+
+        let violations = rule.check_graph(&DependencyGraph::from_imports(&imports));
+        let found: Vec<(&str, &str, &str)> = violations
+            .iter()
+            .map(|v| (v.from.as_str(), v.to.as_deref().unwrap(), v.reason.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [(
+                "app.api.views",
+                "app.secret",
+                "'app.secret' may only be imported by its allowlist, not by 'app.api.views'"
+            )]
+        );
+    }
+}
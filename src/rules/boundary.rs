@@ -0,0 +1,161 @@
+use std::path::Path;
+
+use crate::configs::BoundaryDirection;
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Keeps imports from crossing the boundary of the listed packages, e.g. production code
+/// importing from `tests`. With `Inbound`, modules outside a listed package may not import
+/// it (local or external); with `Outbound`, modules inside it may not import the rest of the
+/// project. Packages may use `*` and `**` wildcards, e.g. `**.tests`.
+pub struct BoundaryRule {
+    source_folder: ModulePath,
+    forbidden_sources: Vec<ModulePath>,
+    direction: BoundaryDirection,
+}
+
+impl BoundaryRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(
+        source_folder: ModulePath,
+        forbidden_sources: Vec<ModulePath>,
+        direction: BoundaryDirection,
+    ) -> Self {
+        BoundaryRule {
+            source_folder,
+            forbidden_sources,
+            direction,
+        }
+    }
+}
+
+impl ImportRule for BoundaryRule {
+    fn name(&self) -> &'static str {
+        "Boundary"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let crossed = self.forbidden_sources.iter().find(|prefix| {
+            let inside = import.from_module.starts_with_pattern(prefix);
+            let target_inside = import.target_module.starts_with_pattern(prefix);
+            match self.direction {
+                BoundaryDirection::Inbound => target_inside && !inside,
+                BoundaryDirection::Outbound => inside && !target_inside && !import.external,
+            }
+        });
+        match (crossed, self.direction) {
+            (Some(prefix), BoundaryDirection::Inbound) => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "production module must not import from '{}'",
+                    prefix.to_dotted()
+                ),
+            },
+            (Some(prefix), BoundaryDirection::Outbound) => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "module of '{}' must not import '{}' from outside it",
+                    prefix.to_dotted(),
+                    import.target_module.to_dotted()
+                ),
+            },
+            (None, _) => RuleOutcome {
+                pass: true,
+                reason: String::from("ok: within boundary"),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let sources = self
+            .forbidden_sources
+            .iter()
+            .map(|m| m.to_dotted())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let direction = match self.direction {
+            BoundaryDirection::Inbound => "inbound",
+            BoundaryDirection::Outbound => "outbound",
+        };
+        format!(
+            "folder={} forbidden_sources=[{}] direction={}",
+            folder, sources, direction
+        )
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        // A `tests` package outside the source roots resolves as external
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundaryRule;
+    use crate::configs::BoundaryDirection;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use std::path::Path;
+
+    fn check(direction: BoundaryDirection, from: &str, target: &str) -> (bool, String) {
+        let target = ModulePath::from_dotted(target);
+        let import = ImportLine {
+            from_module: ModulePath::from_dotted(from),
+            external: !["app", "tests"].contains(&target.segments()[0].as_str()),
+            target_module: target,
+            import_line: 1,
+            ..Default::default()
+        };
+        let rule = BoundaryRule::new(
+            ModulePath::default(),
+            vec![ModulePath::from_dotted("tests")],
+            direction,
+        );
+        let outcome = rule.check_line(Path::new("x.py"), &import);
+        (outcome.pass, outcome.reason)
+    }
+
+    #[test]
+    fn inbound_forbids_importing_the_package_from_outside() {
+        let inbound = |from, target| check(BoundaryDirection::Inbound, from, target);
+        assert_eq!(
+            inbound("app.core", "tests.helpers"),
+            (
+                false,
+                String::from("production module must not import from 'tests'")
+            )
+        );
+        assert!(inbound("tests.test_core", "tests.helpers").0);
+        assert!(inbound("tests.test_core", "app.core").0);
+        assert!(inbound("app.core", "testsuite").0);
+    }
+
+    #[test]
+    fn outbound_forbids_leaving_the_package() {
+        let outbound = |from, target| check(BoundaryDirection::Outbound, from, target);
+        assert_eq!(
+            outbound("tests.test_core", "app.core"),
+            (
+                false,
+                String::from("module of 'tests' must not import 'app.core' from outside it")
+            )
+        );
+        assert!(outbound("tests.test_core", "tests.helpers").0);
+        assert!(outbound("tests.test_core", "pytest").0);
+        assert!(outbound("app.core", "app.models").0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Keeps coupling low: a file under `from_prefix` may import at most `max` distinct modules
+/// under `to_prefix`. The import of the first module over the limit fails, listing every
+/// module of `to_prefix` the file imports.
+pub struct CouplingRule {
+    from_prefix: ModulePath,
+    to_prefix: ModulePath,
+    max: usize,
+}
+
+impl CouplingRule {
+    /// An empty `from_prefix` applies the rule to every module.
+    pub fn new(from_prefix: ModulePath, to_prefix: ModulePath, max: usize) -> Self {
+        CouplingRule {
+            from_prefix,
+            to_prefix,
+            max,
+        }
+    }
+}
+
+impl ImportRule for CouplingRule {
+    fn name(&self) -> &'static str {
+        "Coupling"
+    }
+
+    fn check_line(&self, _current_file: &Path, _import: &ImportLine) -> RuleOutcome {
+        RuleOutcome {
+            pass: true,
+            reason: String::from("ok: coupling is checked per file"),
+        }
+    }
+
+    fn check_file(&self, current_file: &Path, imports: &[ImportLine]) -> Vec<RuleOutcome> {
+        // Index of the first import of each distinct target, in file order
+        let mut targets: Vec<(usize, &ModulePath)> = Vec::new();
+        for (idx, import) in imports.iter().enumerate() {
+            let target = &import.target_module;
+            if import.from_module.starts_with(&self.from_prefix)
+                && target.starts_with(&self.to_prefix)
+                && !targets.iter().any(|(_, seen)| *seen == target)
+            {
+                targets.push((idx, target));
+            }
+        }
+        let over = targets.get(self.max).map(|(idx, _)| *idx);
+        let listed: Vec<String> = targets.iter().map(|(_, t)| t.to_dotted()).collect();
+        imports
+            .iter()
+            .enumerate()
+            .map(|(idx, import)| {
+                if Some(idx) != over {
+                    return self.check_line(current_file, import);
+                }
+                RuleOutcome {
+                    pass: false,
+                    reason: format!(
+                        "imports {} modules of '{}' (max {}): {}",
+                        targets.len(),
+                        self.to_prefix.to_dotted(),
+                        self.max,
+                        listed.join(", ")
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    fn describe(&self) -> String {
+        let from = folder_label(&self.from_prefix);
+        format!(
+            "from={} to={} max={}",
+            from,
+            self.to_prefix.to_dotted(),
+            self.max
+        )
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.from_prefix, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::CouplingRule;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+
+    #[test]
+    fn distinct_targets_over_the_limit_fail() {
+        let import = |from: &str, target: &str, line: u32| ImportLine {
+            import_line: line,
+            ..import_of(from, target)
+        };
+        let rule = CouplingRule::new(
+            ModulePath::from_dotted("app.api"),
+            ModulePath::from_dotted("app.core"),
+            2,
+        );
+        let imports = [
+            import("app.api.views", "app.core.models", 1),
+            import("app.api.views", "app.utils", 2),
+            import("app.api.views", "app.core.models", 3),
+            import("app.api.views", "app.core.services", 4),
+            import("app.api.views", "app.core.cache", 5),
+        ];
+        let failures = |imports: &[ImportLine]| -> Vec<(usize, String)> {
+            rule.check_file(Path::new("app/api/views.py"), imports)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, outcome)| !outcome.pass)
+                .map(|(idx, outcome)| (idx, outcome.reason))
+                .collect()
+        };
+        let found = failures(&imports);
+        assert_eq!(
+            found,
+            [(
+                4,
+                String::from(
+                    "imports 3 modules of 'app.core' (max 2): \
+                     app.core.models, app.core.services, app.core.cache"
+                )
+            )]
+        );
+        // At the limit, or outside `from_prefix`, nothing fails
+        assert!(failures(&imports[..4]).is_empty());
+        let elsewhere: Vec<ImportLine> = imports
+            .iter()
+            .map(|imp| ImportLine {
+                from_module: ModulePath::from_dotted("app.cli"),
+                ..imp.clone()
+            })
+            .collect();
+        assert!(failures(&elsewhere).is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use crate::graph::{DependencyGraph, Edge};
+
+use super::{GraphViolation, ProjectRule};
+
+/// Forbids import cycles between first-party modules. Each cycle contains at least one back
+/// edge of a depth-first walk of the graph; every back edge is reported on the import
+/// creating it, with the shortest cycle it closes.
+pub struct NoCyclesRule;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    OnStack,
+    Done,
+}
+
+/// Edges leading back to a module still being explored, walking from each node in order.
+fn back_edges(graph: &DependencyGraph) -> Vec<&Edge> {
+    let mut successors: BTreeMap<&str, Vec<&Edge>> = BTreeMap::new();
+    for edge in graph.edges.iter() {
+        successors.entry(edge.from.as_str()).or_default().push(edge);
+    }
+
+    let mut state: BTreeMap<&str, Visit> = BTreeMap::new();
+    let mut back = Vec::new();
+    for start in graph.nodes.iter() {
+        if state.contains_key(start.as_str()) {
+            continue;
+        }
+        state.insert(start, Visit::OnStack);
+        // Iterative, so deep import chains can't overflow the stack
+        let mut stack: Vec<(&str, usize)> = vec![(start, 0)];
+        while let Some((node, next)) = stack.last_mut() {
+            let Some(edge) = successors.get(*node).and_then(|edges| edges.get(*next)) else {
+                state.insert(node, Visit::Done);
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            match state.get(edge.to.as_str()) {
+                Some(Visit::OnStack) => back.push(*edge),
+                Some(Visit::Done) => {}
+                None => {
+                    state.insert(&edge.to, Visit::OnStack);
+                    stack.push((&edge.to, 0));
+                }
+            }
+        }
+    }
+    back
+}
+
+impl ProjectRule for NoCyclesRule {
+    fn name(&self) -> &'static str {
+        "NoCycles"
+    }
+
+    fn describe(&self) -> String {
+        String::from("no import cycles")
+    }
+
+    fn check_graph(&self, graph: &DependencyGraph) -> Vec<GraphViolation> {
+        back_edges(graph)
+            .into_iter()
+            .map(|edge| {
+                // The back edge goes to a module on the walk stack, which reaches its origin
+                let mut cycle = graph
+                    .reachable_paths(&[edge.to.as_str()], |_| true)
+                    .remove(&edge.from)
+                    .unwrap_or_else(|| vec![edge.to.clone()]);
+                cycle.insert(0, format!("{} (line {})", edge.from, edge.line));
+                GraphViolation {
+                    from: edge.from.clone(),
+                    to: Some(edge.to.clone()),
+                    reason: format!("import cycle {}", cycle.join(" -> ")),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoCyclesRule;
+    use crate::graph::DependencyGraph;
+    use crate::imports::import_line::ImportLine;
+    use crate::rules::ProjectRule;
+    use crate::test_support::import_of;
+
+    fn graph(edges: &[(&str, &str, u32)]) -> DependencyGraph {
+        let imports: Vec<ImportLine> = edges
+            .iter()
+            .map(|(from, to, line)| ImportLine {
+                import_line: *line,
+                ..import_of(from, to)
+            })
+            .collect();
+        DependencyGraph::from_imports(&imports)
+    }
+
+    #[test]
+    fn each_back_edge_reports_its_shortest_cycle() {
+        let violations = NoCyclesRule.check_graph(&graph(&[
+            ("a", "b", 1),
+            ("b", "c", 2),
+            ("c", "a", 3),
+            ("c", "d", 4),
+            ("d", "b", 5),
+            ("e", "e", 6),
+            ("x", "a", 1),
+        ]));
+        let found: Vec<(&str, &str, &str)> = violations
+            .iter()
+            .map(|v| (v.from.as_str(), v.to.as_deref().unwrap(), v.reason.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("c", "a", "import cycle c (line 3) -> a -> b -> c"),
+                ("d", "b", "import cycle d (line 5) -> b -> c -> d"),
+                ("e", "e", "import cycle e (line 6) -> e"),
+            ]
+        );
+    }
+
+    #[test]
+    fn acyclic_graphs_pass() {
+        let violations =
+            NoCyclesRule.check_graph(&graph(&[("a", "b", 1), ("a", "c", 2), ("b", "c", 1)]));
+        assert!(violations.is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{folder_label, ImportRule, RuleOutcome};
+
+/// Limits how deep imports may reach into the packages of a source folder: `app.a.b.c`
+/// is 3 levels deep in `app`. Targets outside the folder are not checked.
+pub struct MaxImportDepthRule {
+    source_folder: ModulePath,
+    max_depth: usize,
+}
+
+impl MaxImportDepthRule {
+    /// An empty `source_folder` counts every segment of the target.
+    pub fn new(source_folder: ModulePath, max_depth: usize) -> Self {
+        MaxImportDepthRule {
+            source_folder,
+            max_depth,
+        }
+    }
+}
+
+impl ImportRule for MaxImportDepthRule {
+    fn name(&self) -> &'static str {
+        "MaxImportDepth"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        let depth = match import.target_module.relative_from(&self.source_folder) {
+            Some(rel) => rel.segments().len(),
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("target not under source folder"),
+                }
+            }
+        };
+        if depth > self.max_depth {
+            RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "import reaches {} levels deep (max {})",
+                    depth, self.max_depth
+                ),
+            }
+        } else {
+            RuleOutcome {
+                pass: true,
+                reason: format!("ok: {} levels deep", depth),
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={} max_depth={}", folder, self.max_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MaxImportDepthRule;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    #[test]
+    fn imports_deeper_than_the_limit_fail() {
+        let rule = MaxImportDepthRule::new(ModulePath::from_dotted("app"), 2);
+        let check = |target: &str| {
+            let import = import_of("app.main", target);
+            let outcome = rule.check_line(Path::new("app/main.py"), &import);
+            (outcome.pass, outcome.reason)
+        };
+        assert!(check("app.core.models").0);
+        assert_eq!(
+            check("app.core.models.user"),
+            (false, String::from("import reaches 3 levels deep (max 2)"))
+        );
+        // Other packages are out of scope
+        assert!(check("lib.a.b.c.d").0);
+    }
+}
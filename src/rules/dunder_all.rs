@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use crate::imports::collection::parse_dunder_all;
+use crate::imports::encoding::read_source;
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Modules defining `__all__` declare their public names: `from pkg import name` must name
+/// one of them (or a submodule). `__all__` is read from the imported module's source (its
+/// `.py` file, or `__init__.py` for a package) the first time the module is imported; modules
+/// without one, and imports made from within the module's own tree, are not restricted.
+pub struct RespectDunderAllRule {
+    source_folder: ModulePath,
+    /// Dotted module -> its `__all__`, if it defines one, shared by all files of the run
+    exported: DashMap<String, Option<Arc<HashSet<String>>>>,
+}
+
+impl RespectDunderAllRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath) -> Self {
+        RespectDunderAllRule {
+            source_folder,
+            exported: DashMap::new(),
+        }
+    }
+
+    fn exported_by(&self, module: &ModulePath) -> Option<Arc<HashSet<String>>> {
+        let key = module.to_dotted();
+        if let Some(exported) = self.exported.get(&key) {
+            return exported.clone();
+        }
+        let package_init: PathBuf = module.to_dir_pathbuf().join("__init__.py");
+        let exported = [module.file_path(), package_init]
+            .iter()
+            .find_map(|path| read_source(path).ok().map(|(_, source)| source))
+            .and_then(|source| parse_dunder_all(&source))
+            .map(|names| Arc::new(names.into_iter().collect::<HashSet<String>>()));
+        self.exported.insert(key, exported.clone());
+        exported
+    }
+}
+
+impl ImportRule for RespectDunderAllRule {
+    fn name(&self) -> &'static str {
+        "DunderAll"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let target = &import.target_module;
+        if import.names.is_empty() || import.from_module.starts_with(target) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ok: no names imported from outside"),
+            };
+        }
+        let Some(exported) = self.exported_by(target) else {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ok: target defines no __all__"),
+            };
+        };
+        let missing: Vec<&str> = import
+            .names
+            .iter()
+            .filter(|name| !exported.contains(name.as_str()))
+            .map(|name| name.as_str())
+            .collect();
+        if missing.is_empty() {
+            RuleOutcome {
+                pass: true,
+                reason: String::from("ok: names listed in __all__"),
+            }
+        } else {
+            RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "'{}' not listed in __all__ of '{}'",
+                    missing.join("', '"),
+                    target.to_dotted()
+                ),
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={}", folder)
+    }
+
+    fn cacheable(&self) -> bool {
+        // `__all__` is defined in the imported modules
+        false
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RespectDunderAllRule;
+    use crate::configs::RunConfig;
+    use crate::imports::classification::ImportResolver;
+    use crate::imports::collection::get_file_imports;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::{run_of, TempProject};
+    use std::path::Path;
+
+    #[test]
+    fn names_missing_from_dunder_all_fail() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write(
+            "app/client/__init__.py",
+            "from .impl import Client, _pool\n__all__ = ['Client']\n",
+        );
+        project.write("app/client/impl.py", "");
+        project.write("app/plain.py", "def helper():\n    pass\n");
+        let resolver = ImportResolver::new(
+            Path::new("app"),
+            Some(ModulePath::from_dotted("app")),
+            false,
+        );
+        let rule = RespectDunderAllRule::new(ModulePath::default());
+        let check = |module: &str, source: &str| {
+            let imports = get_file_imports(
+                &ModulePath::from_dotted(module),
+                &resolver,
+                &run_of(RunConfig::default()),
+                Some(source),
+            )
+            .unwrap();
+            imports
+                .iter()
+                .map(|imp| {
+                    let outcome = rule.check_line(Path::new("x.py"), imp);
+                    (outcome.pass, outcome.reason)
+                })
+                .collect::<Vec<(bool, String)>>()
+        };
+
+        assert_eq!(
+            check("app.api", "from app.client import Client, _pool\n"),
+            [(
+                false,
+                String::from("'_pool' not listed in __all__ of 'app.client'")
+            )]
+        );
+        // Submodules, modules without `__all__` and the package's own tree are not restricted
+        assert!(check("app.api", "from app.client import impl, Client\n")
+            .iter()
+            .all(|(pass, _)| *pass));
+        assert!(check("app.api", "from app.plain import helper\n")[0].0);
+        assert!(check("app.client.impl", "from app.client import _pool\n")[0].0);
+    }
+}
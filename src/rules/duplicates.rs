@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::imports::import_line::{ImportKind, ImportLine};
+use crate::module_path::ModulePath;
+use crate::results::{Fix, FixRange};
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Flags imports of a module the file already imported, usually left behind by a merge.
+/// Every import after the first of the same target fails, local or external. Repeated plain
+/// `import x` statements alone on their line come with a fix removing that line.
+pub struct NoDuplicateImportsRule {
+    source_folder: ModulePath,
+}
+
+impl NoDuplicateImportsRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath) -> Self {
+        NoDuplicateImportsRule { source_folder }
+    }
+}
+
+impl ImportRule for NoDuplicateImportsRule {
+    fn name(&self) -> &'static str {
+        "NoDuplicateImports"
+    }
+
+    fn check_line(&self, _current_file: &Path, _import: &ImportLine) -> RuleOutcome {
+        RuleOutcome {
+            pass: true,
+            reason: String::from("ok: duplicates are checked per file"),
+        }
+    }
+
+    fn check_file(&self, current_file: &Path, imports: &[ImportLine]) -> Vec<RuleOutcome> {
+        let mut first_lines: HashMap<String, u32> = HashMap::new();
+        imports
+            .iter()
+            .map(|import| {
+                if !import.from_module.starts_with(&self.source_folder) {
+                    return self.check_line(current_file, import);
+                }
+                let target = import.target_module.to_dotted();
+                match first_lines.get(&target) {
+                    Some(first) => RuleOutcome {
+                        pass: false,
+                        reason: format!(
+                            "duplicate import of '{}' (first at line {})",
+                            target, first
+                        ),
+                    },
+                    None => {
+                        first_lines.insert(target, import.import_line);
+                        self.check_line(current_file, import)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn fix(&self, import: &ImportLine, imports: &[ImportLine]) -> Option<Fix> {
+        // `from x import a` may bring names the first import doesn't, and a line holding
+        // other imports can't simply go
+        let first = imports
+            .iter()
+            .find(|other| other.target_module == import.target_module)?;
+        let shares_line = imports
+            .iter()
+            .filter(|other| other.import_line == import.import_line)
+            .count()
+            > 1;
+        if import.kind != ImportKind::Import || first.kind != ImportKind::Import || shares_line {
+            return None;
+        }
+        Some(Fix {
+            range: FixRange {
+                start_line: import.import_line,
+                start_column: 0,
+                end_line: import.import_line + 1,
+                end_column: 0,
+            },
+            replacement: String::new(),
+        })
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={}", folder)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::NoDuplicateImportsRule;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+
+    #[test]
+    fn later_imports_of_the_same_target_fail() {
+        let import = |target: &str, line: u32| ImportLine {
+            import_line: line,
+            external: target == "os",
+            ..import_of("app.api", target)
+        };
+        let imports = [
+            import("os", 1),
+            import("app.models", 2),
+            import("os", 3),
+            import("app.models.user", 4),
+            import("app.models", 7),
+        ];
+        let rule = NoDuplicateImportsRule::new(ModulePath::default());
+        let outcomes = rule.check_file(Path::new("app/api.py"), &imports);
+        assert_eq!(outcomes.len(), imports.len());
+        let found: Vec<(usize, String)> = outcomes
+            .into_iter()
+            .enumerate()
+            .filter(|(_, outcome)| !outcome.pass)
+            .map(|(idx, outcome)| (idx, outcome.reason))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    2,
+                    String::from("duplicate import of 'os' (first at line 1)")
+                ),
+                (
+                    4,
+                    String::from("duplicate import of 'app.models' (first at line 2)")
+                ),
+            ]
+        );
+    }
+}
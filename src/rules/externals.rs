@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Blocks third-party dependencies that are not explicitly allowed. Entries name top-level
+/// packages, or submodules and `*`/`**` patterns (`google.cloud.*`) to allow only part of
+/// one.
+pub struct AllowedExternals {
+    source_folder: ModulePath,
+    allowed: Vec<ModulePath>,
+}
+
+impl AllowedExternals {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, allowed: Vec<String>) -> Self {
+        AllowedExternals {
+            source_folder,
+            allowed: allowed
+                .iter()
+                .map(|entry| ModulePath::from_dotted(entry))
+                .collect(),
+        }
+    }
+}
+
+impl ImportRule for AllowedExternals {
+    fn name(&self) -> &'static str {
+        "AllowedExternals"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.external {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("local import"),
+            };
+        }
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let pkg = import
+            .target_module
+            .segments()
+            .first()
+            .map(|s| s.as_str())
+            .unwrap_or("");
+        let allowed = self
+            .allowed
+            .iter()
+            .any(|entry| import.target_module.starts_with_pattern(entry));
+        if allowed {
+            return RuleOutcome {
+                pass: true,
+                reason: format!("ok: '{}' is allowed", pkg),
+            };
+        }
+        RuleOutcome {
+            pass: false,
+            reason: format!("external package '{}' is not in the allowed list", pkg),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let mut allowed: Vec<String> = self.allowed.iter().map(|m| m.to_dotted()).collect();
+        allowed.sort_unstable();
+        format!("folder={} allowed=[{}]", folder, allowed.join(", "))
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        true
+    }
+
+    fn applies_to_fixtures(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AllowedExternals;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(rule: &AllowedExternals, target: &str) -> (bool, String) {
+        let import = ImportLine {
+            external: true,
+            ..import_of("app.main", target)
+        };
+        let outcome = rule.check_line(Path::new("app/main.py"), &import);
+        (outcome.pass, outcome.reason)
+    }
+
+    #[test]
+    fn only_allowed_top_level_packages_pass() {
+        let rule = AllowedExternals::new(ModulePath::default(), vec![String::from("requests")]);
+        assert!(check(&rule, "requests.adapters").0);
+        assert_eq!(
+            check(&rule, "numpy.linalg"),
+            (
+                false,
+                String::from("external package 'numpy' is not in the allowed list")
+            )
+        );
+    }
+
+    #[test]
+    fn allowed_patterns_cover_part_of_a_package() {
+        let rule = AllowedExternals::new(
+            ModulePath::default(),
+            vec![String::from("google.*.storage")],
+        );
+        assert!(check(&rule, "google.cloud.storage").0);
+        assert!(!check(&rule, "google.cloud.bigquery").0);
+        assert!(!check(&rule, "google").0);
+    }
+}
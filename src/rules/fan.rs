@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use crate::graph::DependencyGraph;
+
+use super::{GraphViolation, ProjectRule};
+
+/// Caps how connected a module may get: fan-out is the number of distinct local modules it
+/// imports, fan-in the number of distinct modules importing it. Each module over a limit is
+/// reported once, on its own file.
+pub struct FanRule {
+    max_fan_out: Option<usize>,
+    max_fan_in: Option<usize>,
+}
+
+impl FanRule {
+    pub fn new(max_fan_out: Option<usize>, max_fan_in: Option<usize>) -> Self {
+        FanRule {
+            max_fan_out,
+            max_fan_in,
+        }
+    }
+}
+
+impl ProjectRule for FanRule {
+    fn name(&self) -> &'static str {
+        "Fan"
+    }
+
+    fn describe(&self) -> String {
+        let limit = |max: Option<usize>| max.map_or(String::from("<none>"), |m| m.to_string());
+        format!(
+            "max_fan_out={} max_fan_in={}",
+            limit(self.max_fan_out),
+            limit(self.max_fan_in)
+        )
+    }
+
+    fn check_graph(&self, graph: &DependencyGraph) -> Vec<GraphViolation> {
+        // Edges are already deduplicated per (from, to)
+        let mut fan_out: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut fan_in: BTreeMap<&str, usize> = BTreeMap::new();
+        for edge in graph.edges.iter().filter(|edge| edge.from != edge.to) {
+            *fan_out.entry(edge.from.as_str()).or_insert(0) += 1;
+            *fan_in.entry(edge.to.as_str()).or_insert(0) += 1;
+        }
+
+        let mut violations = Vec::new();
+        let mut check = |counts: &BTreeMap<&str, usize>, max: Option<usize>, kind: &str| {
+            let max = match max {
+                Some(max) => max,
+                None => return,
+            };
+            for (module, count) in counts.iter().filter(|(_, count)| **count > max) {
+                violations.push(GraphViolation {
+                    from: module.to_string(),
+                    to: None,
+                    reason: format!("'{}' has a fan-{} of {} (max {})", module, kind, count, max),
+                });
+            }
+        };
+        check(&fan_out, self.max_fan_out, "out");
+        check(&fan_in, self.max_fan_in, "in");
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FanRule;
+    use crate::rules::ProjectRule;
+    use crate::test_support::graph_of;
+
+    #[test]
+    fn modules_over_the_limits_are_reported_once() {
+        let graph = graph_of(&[
+            ("app.god", "app.a"),
+            ("app.god", "app.b"),
+            ("app.god", "app.b"),
+            ("app.god", "app.c"),
+            ("app.x", "app.c"),
+            ("app.y", "app.c"),
+            ("app.small", "app.a"),
+        ]);
+        let violations = FanRule::new(Some(2), Some(2)).check_graph(&graph);
+        let found: Vec<(&str, &str)> = violations
+            .iter()
+            .map(|v| (v.from.as_str(), v.reason.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("app.god", "'app.god' has a fan-out of 3 (max 2)"),
+                ("app.c", "'app.c' has a fan-in of 3 (max 2)"),
+            ]
+        );
+        assert!(violations.iter().all(|v| v.to.is_none()));
+
+        // Unset limits are not checked
+        assert!(FanRule::new(None, None).check_graph(&graph).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Forbids importing the listed modules or any of their submodules, whether they are local
+/// or external. Entries may use `*` and `**` wildcards, e.g. `*.migrations`. Test fixtures
+/// are checked too.
+pub struct ForbiddenImports {
+    source_folder: ModulePath,
+    forbidden: Vec<ModulePath>,
+}
+
+impl ForbiddenImports {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, forbidden: Vec<ModulePath>) -> Self {
+        ForbiddenImports {
+            source_folder,
+            forbidden,
+        }
+    }
+}
+
+impl ImportRule for ForbiddenImports {
+    fn name(&self) -> &'static str {
+        "Forbidden"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        match self
+            .forbidden
+            .iter()
+            .find(|module| import.target_module.starts_with_pattern(module))
+        {
+            Some(module) => RuleOutcome {
+                pass: false,
+                reason: format!("'{}' is forbidden", module.to_dotted()),
+            },
+            None => RuleOutcome {
+                pass: true,
+                reason: String::from("ok: not forbidden"),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let forbidden = self
+            .forbidden
+            .iter()
+            .map(|m| m.to_dotted())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("folder={} forbidden=[{}]", folder, forbidden)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        true
+    }
+
+    fn applies_to_fixtures(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ForbiddenImports;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn checker(forbidden: &str) -> impl Fn(&str) -> bool {
+        let rule = ForbiddenImports::new(
+            ModulePath::default(),
+            vec![ModulePath::from_dotted(forbidden)],
+        );
+        move |target: &str| {
+            let import = import_of("app.main", target);
+            rule.check_line(Path::new("app/main.py"), &import).pass
+        }
+    }
+
+    #[test]
+    fn forbidden_module_and_submodules_fail() {
+        let check = checker("app.legacy");
+        assert!(!check("app.legacy"));
+        assert!(!check("app.legacy.db"));
+        assert!(check("app.legacy_v2"));
+    }
+
+    #[test]
+    fn wildcard_entries_match_any_package() {
+        let check = checker("**.migrations");
+        assert!(!check("app.users.migrations.0001_initial"));
+        assert!(!check("migrations"));
+        assert!(check("app.users.models"));
+    }
+}
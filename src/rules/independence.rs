@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{folder_label, head_under_folder, ImportRule, RuleOutcome};
+
+/// Mutually isolated submodules inside a configured source folder. Each group is a set of
+/// submodule names; a module of one group may not import a module of another group, in
+/// either direction. Imports within a group, or involving unlisted submodules, are allowed.
+pub struct IndependenceRule {
+    source_folder: ModulePath,
+    groups: Vec<Vec<String>>,
+    group_index: HashMap<String, usize>,
+}
+
+impl IndependenceRule {
+    pub fn new(source_folder: ModulePath, groups: Vec<Vec<String>>) -> Self {
+        let mut group_index = HashMap::new();
+        for (idx, group) in groups.iter().enumerate() {
+            for name in group.iter() {
+                group_index.insert(name.clone(), idx);
+            }
+        }
+        IndependenceRule {
+            source_folder,
+            groups,
+            group_index,
+        }
+    }
+}
+
+impl ImportRule for IndependenceRule {
+    fn name(&self) -> &'static str {
+        "Independence"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        let current_head = match head_under_folder(&self.source_folder, &import.from_module) {
+            Some(h) => h,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("out of scope (not under source folder)"),
+                }
+            }
+        };
+        let target_head = match head_under_folder(&self.source_folder, &import.target_module) {
+            Some(h) => h,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("target not under source folder"),
+                }
+            }
+        };
+        match (
+            self.group_index.get(current_head),
+            self.group_index.get(target_head),
+        ) {
+            (Some(me), Some(other)) if me != other => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "'{}' must be independent of '{}'",
+                    current_head, target_head
+                ),
+            },
+            _ => RuleOutcome {
+                pass: true,
+                reason: format!("ok: {} can import {}", current_head, target_head),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let groups = self
+            .groups
+            .iter()
+            .map(|group| format!("[{}]", group.join(", ")))
+            .collect::<Vec<String>>()
+            .join(" | ");
+        format!("folder={} groups={}", folder, groups)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        if self.source_folder.starts_with(module_path) {
+            return true;
+        }
+        let concerned = match head_under_folder(&self.source_folder, module_path) {
+            Some(head) => self.group_index.contains_key(head),
+            None => false,
+        };
+        if !concerned && verbose {
+            println!(
+                "[{}] not concerned with {} (not in a configured group of {})",
+                self.name(),
+                module_path.to_dotted(),
+                self.source_folder.to_dotted()
+            );
+        }
+        concerned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndependenceRule;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(from: &str, target: &str) -> (bool, String) {
+        let rule = IndependenceRule::new(
+            ModulePath::from_dotted("app"),
+            vec![
+                vec![String::from("domain_a")],
+                vec![String::from("domain_b"), String::from("domain_b_ext")],
+            ],
+        );
+        let import = import_of(from, target);
+        let outcome = rule.check_line(Path::new("app/main.py"), &import);
+        (outcome.pass, outcome.reason)
+    }
+
+    #[test]
+    fn groups_cannot_import_each_other_either_way() {
+        assert_eq!(
+            check("app.domain_a.models", "app.domain_b.models"),
+            (
+                false,
+                String::from("'domain_a' must be independent of 'domain_b'")
+            )
+        );
+        assert!(!check("app.domain_b_ext.views", "app.domain_a").0);
+        // Same group, unlisted submodules and other folders are fine
+        assert!(check("app.domain_b_ext.views", "app.domain_b.models").0);
+        assert!(check("app.domain_a.models", "app.shared.utils").0);
+        assert!(check("lib.domain_a", "app.domain_b").0);
+    }
+}
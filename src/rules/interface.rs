@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Required-import rule for paired packages: every module of an implementation package
+/// (`app.foo.impl`) must import something from its interface package (`app.foo.api`).
+pub struct InterfaceImplementation {
+    parent: ModulePath,
+    interface: String,
+    implementation: String,
+}
+
+impl InterfaceImplementation {
+    /// An empty `parent` applies the rule to every module.
+    pub fn new(parent: ModulePath, interface: String, implementation: String) -> Self {
+        InterfaceImplementation {
+            parent,
+            interface,
+            implementation,
+        }
+    }
+
+    /// The interface package paired with the implementation package `module` belongs to.
+    fn paired_interface(&self, module: &ModulePath) -> Option<ModulePath> {
+        let rel = module.relative_from(&self.parent)?;
+        let idx = rel
+            .segments()
+            .iter()
+            .position(|s| s == &self.implementation)?;
+        let base = self.parent.segments().len() + idx;
+        let mut segments = module.segments()[..base].to_vec();
+        segments.push(self.interface.clone());
+        Some(ModulePath::new(segments))
+    }
+}
+
+impl ImportRule for InterfaceImplementation {
+    fn name(&self) -> &'static str {
+        "InterfaceImplementation"
+    }
+
+    fn check_line(&self, _current_file: &Path, _import: &ImportLine) -> RuleOutcome {
+        // Nothing to say about a single import; see finalize
+        RuleOutcome {
+            pass: true,
+            reason: String::from("checked per module"),
+        }
+    }
+
+    fn finalize(&self, module_path: &ModulePath, imports: &[ImportLine]) -> Option<RuleOutcome> {
+        // Package markers only re-export, they don't implement anything
+        if module_path.segments().last().map(|s| s.as_str()) == Some("__init__") {
+            return None;
+        }
+        let interface = self.paired_interface(module_path)?;
+        if imports
+            .iter()
+            .any(|imp| imp.target_module.starts_with(&interface))
+        {
+            return None;
+        }
+        Some(RuleOutcome {
+            pass: false,
+            reason: format!(
+                "implementation does not import its interface '{}'",
+                interface.to_dotted()
+            ),
+        })
+    }
+
+    fn describe(&self) -> String {
+        let parent = folder_label(&self.parent);
+        format!(
+            "parent={} interface={} implementation={}",
+            parent, self.interface, self.implementation
+        )
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        // Implementation packages can sit at any depth, so the whole parent is walked
+        concerns_folder(self.name(), &self.parent, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InterfaceImplementation;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+
+    fn rule() -> InterfaceImplementation {
+        InterfaceImplementation::new(
+            ModulePath::from_dotted("app"),
+            String::from("api"),
+            String::from("impl"),
+        )
+    }
+
+    fn finalize(module: &str, targets: &[&str]) -> bool {
+        let module = ModulePath::from_dotted(module);
+        let imports: Vec<ImportLine> = targets
+            .iter()
+            .map(|target| ImportLine {
+                from_module: module.clone(),
+                target_module: ModulePath::from_dotted(target),
+                import_line: 1,
+                ..Default::default()
+            })
+            .collect();
+        rule().finalize(&module, &imports).is_none()
+    }
+
+    #[test]
+    fn impl_importing_its_interface_passes() {
+        assert!(finalize("app.foo.impl.service", &["app.foo.api.service"]));
+        assert!(finalize("app.foo.impl", &["app.util", "app.foo.api"]));
+    }
+
+    #[test]
+    fn impl_without_its_interface_fails() {
+        assert!(!finalize("app.foo.impl.service", &["app.util"]));
+        // Another package's interface does not count
+        assert!(!finalize("app.foo.impl.service", &["app.bar.api"]));
+    }
+
+    #[test]
+    fn non_impl_modules_and_package_markers_are_ignored() {
+        assert!(finalize("app.foo.api.service", &[]));
+        assert!(finalize("app.foo.impl.__init__", &[]));
+        assert!(finalize("other.foo.impl.service", &[]));
+    }
+}
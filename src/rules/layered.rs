@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{folder_label, head_under_folder, ImportRule, RuleOutcome};
+
+/// Layered architecture inside a configured source folder.
+/// Layers are listed from lowest to highest; a module may import from its own layer or any
+/// lower one. Several submodules can share a layer.
+pub struct LayeredRule {
+    source_folder: ModulePath,
+    layers: Vec<Vec<String>>,
+    layer_index: HashMap<String, usize>,
+}
+
+impl LayeredRule {
+    pub fn new(source_folder: ModulePath, layers: Vec<Vec<String>>) -> Self {
+        let mut layer_index = HashMap::new();
+        for (idx, layer) in layers.iter().enumerate() {
+            for name in layer.iter() {
+                layer_index.insert(name.clone(), idx);
+            }
+        }
+        LayeredRule {
+            source_folder,
+            layers,
+            layer_index,
+        }
+    }
+}
+
+impl ImportRule for LayeredRule {
+    fn name(&self) -> &'static str {
+        "Layered"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        let current_head = match head_under_folder(&self.source_folder, &import.from_module) {
+            Some(h) => h,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("out of scope (not under source folder)"),
+                }
+            }
+        };
+        let target_head = match head_under_folder(&self.source_folder, &import.target_module) {
+            Some(h) => h,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("target not under source folder"),
+                }
+            }
+        };
+        let me_opt = self.layer_index.get(current_head).copied();
+        let other_opt = self.layer_index.get(target_head).copied();
+        match (me_opt, other_opt) {
+            (Some(me), Some(other)) if other > me => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "layer violation: '{}' (layer {}) cannot import from '{}' (layer {})",
+                    current_head, me, target_head, other
+                ),
+            },
+            _ => RuleOutcome {
+                pass: true,
+                reason: format!("ok: {} can import {}", current_head, target_head),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let layers = if self.layers.is_empty() {
+            String::from("<unspecified>")
+        } else {
+            self.layers
+                .iter()
+                .map(|layer| format!("[{}]", layer.join(", ")))
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        };
+        format!("folder={} layers={}", folder, layers)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        if self.source_folder.starts_with(module_path) {
+            return true;
+        }
+        let concerned = match head_under_folder(&self.source_folder, module_path) {
+            Some(head) => self.layer_index.contains_key(head),
+            None => false,
+        };
+        if !concerned && verbose {
+            println!(
+                "[{}] not concerned with {} (not in a configured layer of {})",
+                self.name(),
+                module_path.to_dotted(),
+                self.source_folder.to_dotted()
+            );
+        }
+        concerned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LayeredRule;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn rule() -> LayeredRule {
+        let layers = vec![
+            vec![String::from("models"), String::from("schemas")],
+            vec![String::from("services")],
+            vec![String::from("api")],
+        ];
+        LayeredRule::new(ModulePath::from_dotted("app"), layers)
+    }
+
+    fn check(from: &str, target: &str) -> bool {
+        let import = import_of(from, target);
+        rule().check_line(Path::new("x.py"), &import).pass
+    }
+
+    #[test]
+    fn lower_and_same_layer_imports_pass() {
+        assert!(check("app.api.routes", "app.services.users"));
+        assert!(check("app.services.users", "app.models.user"));
+        assert!(check("app.models.user", "app.schemas.user"));
+    }
+
+    #[test]
+    fn higher_layer_import_fails() {
+        assert!(!check("app.models.user", "app.services.users"));
+        assert!(!check("app.services.users", "app.api.routes"));
+    }
+
+    #[test]
+    fn unlisted_modules_are_ignored() {
+        assert!(check("app.util", "app.api.routes"));
+        assert!(check("app.models.user", "app.util"));
+        assert!(check("other.x", "app.api"));
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use crate::graph::DependencyGraph;
+use crate::module_path::ModulePath;
+
+use super::{GraphViolation, ProjectRule};
+
+/// Shared utility modules that must stay dependency leaves, transitively: following imports
+/// from a leaf may only reach other configured leaves, never the rest of the project.
+/// Violations are reported on the import leaving the leaves, once per such import.
+pub struct TransitiveLeaf {
+    leaves: Vec<ModulePath>,
+}
+
+impl TransitiveLeaf {
+    pub fn new(leaves: Vec<ModulePath>) -> Self {
+        TransitiveLeaf { leaves }
+    }
+
+    fn is_leaf(&self, dotted: &str) -> bool {
+        let module = ModulePath::from_dotted(dotted);
+        self.leaves.iter().any(|leaf| module.starts_with(leaf))
+    }
+}
+
+impl ProjectRule for TransitiveLeaf {
+    fn name(&self) -> &'static str {
+        "TransitiveLeaf"
+    }
+
+    fn describe(&self) -> String {
+        let leaves = self
+            .leaves
+            .iter()
+            .map(|m| m.to_dotted())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("leaves=[{}]", leaves)
+    }
+
+    fn check_graph(&self, graph: &DependencyGraph) -> Vec<GraphViolation> {
+        let mut violations = Vec::new();
+        let mut reported: HashSet<(String, String)> = HashSet::new();
+        for leaf in self.leaves.iter() {
+            let starts: Vec<&str> = graph
+                .nodes
+                .iter()
+                .filter(|node| ModulePath::from_dotted(node).starts_with(leaf))
+                .map(|node| node.as_str())
+                .collect();
+            let paths = graph.reachable_paths(&starts, |node| self.is_leaf(node));
+            for (reached, path) in paths.iter() {
+                if self.is_leaf(reached) {
+                    continue;
+                }
+                // Every node before the last is a leaf; the last edge leaves them
+                let from = path[path.len() - 2].clone();
+                let to = path[path.len() - 1].clone();
+                if !reported.insert((from.clone(), to.clone())) {
+                    continue;
+                }
+                violations.push(GraphViolation {
+                    from,
+                    to: Some(to),
+                    reason: format!(
+                        "leaf '{}' depends on '{}' via {}",
+                        leaf.to_dotted(),
+                        reached,
+                        path.join(" -> ")
+                    ),
+                });
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransitiveLeaf;
+    use crate::module_path::ModulePath;
+    use crate::rules::ProjectRule;
+    use crate::test_support::graph_of;
+
+    fn rule() -> TransitiveLeaf {
+        TransitiveLeaf::new(vec![
+            ModulePath::from_dotted("app.util"),
+            ModulePath::from_dotted("app.text"),
+        ])
+    }
+
+    #[test]
+    fn clean_leaf_passes() {
+        let graph = graph_of(&[
+            ("app.util.dates", "app.util.base"),
+            ("app.util.dates", "app.text"),
+            ("app.core", "app.util.dates"),
+        ]);
+        assert!(rule().check_graph(&graph).is_empty());
+    }
+
+    #[test]
+    fn transitive_app_dependency_fails() {
+        let graph = graph_of(&[
+            ("app.util.dates", "app.text"),
+            ("app.text", "app.core.settings"),
+        ]);
+        let violations = rule().check_graph(&graph);
+        let found: Vec<(&str, &str, &str)> = violations
+            .iter()
+            .map(|v| (v.from.as_str(), v.to.as_deref().unwrap(), v.reason.as_str()))
+            .collect();
+        // Reported once, on the import leaving the leaves
+        assert_eq!(
+            found,
+            [(
+                "app.text",
+                "app.core.settings",
+                "leaf 'app.util' depends on 'app.core.settings' via app.util.dates -> app.text -> app.core.settings"
+            )]
+        );
+    }
+}
@@ -7,21 +7,45 @@ use crate::module_path::ModulePath;
 use super::{ImportRule, RuleOutcome};
 
 /// Linear order inside a configured source folder.
-/// Uses a configured ordered list of submodules to constrain import order.
+/// Uses configured ordered lists of submodules to constrain import order. Each list is an
+/// independent group: order only applies between submodules of the same group.
 pub struct LinearOrderInFolder {
     source_folder: ModulePath,
-    order_index: HashMap<String, usize>,
+    /// Submodule name -> (group, position within the group)
+    order_index: HashMap<String, (usize, usize)>,
+    groups: Vec<Vec<String>>,
+    /// Only allow imports from strictly earlier submodules, not from the same rank
+    strict: bool,
+    /// Skip imports made by package `__init__` modules, which aggregate their submodules
+    ignore_init: bool,
 }
 
 impl LinearOrderInFolder {
-    pub fn new(source_folder: ModulePath, order: Vec<String>) -> Self {
+    /// `order` is the single group of the classic config; with `groups` it is just one more
+    /// group (ignored when empty).
+    pub fn new(
+        source_folder: ModulePath,
+        order: Vec<String>,
+        groups: Vec<Vec<String>>,
+        strict: bool,
+        ignore_init: bool,
+    ) -> Self {
+        let groups: Vec<Vec<String>> = std::iter::once(order)
+            .chain(groups)
+            .filter(|group| !group.is_empty())
+            .collect();
         let mut order_index = HashMap::new();
-        for (idx, name) in order.iter().enumerate() {
-            order_index.insert(name.clone(), idx);
+        for (group_idx, group) in groups.iter().enumerate() {
+            for (idx, name) in group.iter().enumerate() {
+                order_index.insert(name.clone(), (group_idx, idx));
+            }
         }
         LinearOrderInFolder {
             source_folder,
             order_index,
+            groups,
+            strict,
+            ignore_init,
         }
     }
 }
@@ -31,7 +55,17 @@ impl ImportRule for LinearOrderInFolder {
         "Linear"
     }
 
-    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+    fn check_line(&self, current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if self.ignore_init
+            && current_file
+                .file_stem()
+                .is_some_and(|stem| stem == "__init__")
+        {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ignored: package __init__"),
+            };
+        }
         // Only apply when the current module is under the configured source_folder
         let rel_from = match import.from_module.relative_from(&self.source_folder) {
             Some(mp) => mp,
@@ -77,15 +111,25 @@ impl ImportRule for LinearOrderInFolder {
         }
         let me_opt = self.order_index.get(current_head).copied();
         let other_opt = self.order_index.get(target_head).copied();
-        let pass = match (me_opt, other_opt) {
-            (Some(me), Some(other)) => other <= me,
-            _ => true,
+        // Submodules of different groups are unconstrained
+        let (pass, same_rank) = match (me_opt, other_opt) {
+            (Some((my_group, me)), Some((other_group, other))) if my_group == other_group => {
+                if self.strict {
+                    (other < me, other == me)
+                } else {
+                    (other <= me, false)
+                }
+            }
+            _ => (true, false),
         };
         let reason = if pass {
             format!("ok: {} can import {}", current_head, target_head)
+        } else if same_rank {
+            format!(
+                "'{}' cannot import same-rank '{}' in strict mode",
+                current_head, target_head
+            )
         } else {
-            let mut ordered: Vec<(&String, &usize)> = self.order_index.iter().collect();
-            ordered.sort_by_key(|(_, idx)| **idx);
             format!(
                 "order violation: '{}' cannot import from '{}'",
                 current_head, target_head
@@ -100,21 +144,30 @@ impl ImportRule for LinearOrderInFolder {
         } else {
             self.source_folder.to_dotted()
         };
-        let mut ordered: Vec<(&String, &usize)> = self.order_index.iter().collect();
-        ordered.sort_by_key(|(_, idx)| **idx);
-        let order = if ordered.is_empty() {
+        let order = if self.groups.is_empty() {
             String::from("<unspecified>")
         } else {
-            ordered
-                .into_iter()
-                .map(|(k, _)| k.clone())
+            self.groups
+                .iter()
+                .map(|group| group.join(" -> "))
                 .collect::<Vec<String>>()
-                .join(" -> ")
+                .join(" | ")
         };
-        format!("folder={} order={}", folder, order)
+        let mut described = format!("folder={} order={}", folder, order);
+        if self.strict {
+            described.push_str(" strict");
+        }
+        if self.ignore_init {
+            described.push_str(" ignore_init");
+        }
+        described
     }
 
     fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        // Directories leading to the source folder must be walked to reach it
+        if self.source_folder.starts_with(module_path) {
+            return true;
+        }
         // Check if the module is under the source folder
         let rel_path = match module_path.relative_from(&self.source_folder) {
             Some(mp) => mp,
@@ -152,3 +205,128 @@ impl ImportRule for LinearOrderInFolder {
         concerned
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LinearOrderInFolder;
+    use crate::imports::import_line::{ImportKind, ImportLine};
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(rule: &LinearOrderInFolder, from: &str, target: &str) -> bool {
+        let import = import_of(from, target);
+        rule.check_line(Path::new("app/main.py"), &import).pass
+    }
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn single_order_constrains_every_listed_submodule() {
+        let rule = LinearOrderInFolder::new(
+            ModulePath::from_dotted("app"),
+            strings(&["models", "api"]),
+            Vec::new(),
+            false,
+            false,
+        );
+        assert!(check(&rule, "app.api.views", "app.models.user"));
+        assert!(!check(&rule, "app.models.user", "app.api.views"));
+    }
+
+    #[test]
+    fn concerned_with_the_source_folder_and_its_ancestors() {
+        let rule = LinearOrderInFolder::new(
+            ModulePath::from_dotted("app.core"),
+            strings(&["models", "api"]),
+            Vec::new(),
+            false,
+            false,
+        );
+        let concerned = |module: &str| rule.check_concern(&ModulePath::from_dotted(module), false);
+        assert!(concerned("app"));
+        assert!(concerned("app.core"));
+        assert!(concerned("app.core.models.user"));
+        // Outside the folder, or a submodule missing from the order
+        assert!(!concerned("app.web"));
+        assert!(!concerned("app.core.helpers"));
+    }
+
+    #[test]
+    fn groups_only_order_their_own_members() {
+        let rule = LinearOrderInFolder::new(
+            ModulePath::from_dotted("app"),
+            Vec::new(),
+            vec![
+                strings(&["models", "api"]),
+                strings(&["parsing", "reports"]),
+            ],
+            false,
+            false,
+        );
+        assert!(!check(&rule, "app.models.user", "app.api.views"));
+        assert!(!check(&rule, "app.parsing.csv", "app.reports.daily"));
+        assert!(check(&rule, "app.reports.daily", "app.parsing.csv"));
+        // Cross-group imports are unconstrained, both ways
+        assert!(check(&rule, "app.models.user", "app.reports.daily"));
+        assert!(check(&rule, "app.parsing.csv", "app.api.views"));
+        assert_eq!(
+            rule.describe(),
+            "folder=app order=models -> api | parsing -> reports"
+        );
+    }
+
+    #[test]
+    fn strict_mode_forbids_same_rank_imports() {
+        let rule = LinearOrderInFolder::new(
+            ModulePath::from_dotted("app"),
+            strings(&["models", "api"]),
+            Vec::new(),
+            true,
+            false,
+        );
+        assert!(check(&rule, "app.api.views", "app.models.user"));
+        assert!(!check(&rule, "app.models.user", "app.api.views"));
+        let import = import_of("app.models.user", "app.models.base");
+        let outcome = rule.check_line(Path::new("app/models/user.py"), &import);
+        assert!(!outcome.pass);
+        assert_eq!(
+            outcome.reason,
+            "'models' cannot import same-rank 'models' in strict mode"
+        );
+    }
+
+    #[test]
+    fn package_init_imports_are_skipped_with_ignore_init() {
+        let import = ImportLine {
+            kind: ImportKind::FromImport,
+            ..import_of("app.models", "app.api.views")
+        };
+        let rule = |ignore_init| {
+            LinearOrderInFolder::new(
+                ModulePath::from_dotted("app"),
+                strings(&["models", "api"]),
+                Vec::new(),
+                false,
+                ignore_init,
+            )
+        };
+        let init = Path::new("app/models/__init__.py");
+
+        assert!(!rule(false).check_line(init, &import).pass);
+        assert!(rule(true).check_line(init, &import).pass);
+        // Other modules of the package are still checked
+        assert!(
+            !rule(true)
+                .check_line(Path::new("app/models/user.py"), &import)
+                .pass
+        );
+        assert_eq!(
+            rule(true).describe(),
+            "folder=app order=models -> api ignore_init"
+        );
+    }
+}
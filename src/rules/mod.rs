@@ -1,9 +1,38 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::configs::{ProjectConfig, RunConfig};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::configs::{ProjectConfig, RuleOptions, RunConfig};
+use crate::graph::DependencyGraph;
+use crate::imports::classification::ResolverCache;
 use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+use crate::results::{Fix, Severity};
 
+pub mod alias;
+pub mod allowlist;
+pub mod boundary;
+pub mod coupling;
+pub mod cycles;
+pub mod depth;
+pub mod dunder_all;
+pub mod duplicates;
+pub mod externals;
+pub mod fan;
+pub mod forbidden;
+pub mod independence;
+pub mod interface;
+pub mod layered;
+pub mod leaf;
 pub mod linear;
+pub mod private;
+pub mod public;
+pub mod relative;
+pub mod reserved;
+pub mod scope;
+pub mod versions;
+pub mod visibility;
+pub mod wildcard;
 
 #[derive(Debug, Clone)]
 pub struct RuleOutcome {
@@ -16,52 +45,722 @@ pub trait ImportRule: Sync {
     fn check_line(&self, current_file: &Path, import: &ImportLine) -> RuleOutcome;
     /// Human-readable summary of this rule's configuration for display.
     fn describe(&self) -> String;
-    /// Check if the given module path is controlled/concerned by this rule.
+    /// Check if the given module path is controlled/concerned by this rule. Directories the
+    /// walker reaches that no rule is concerned with are skipped. Defaults to every module.
     /// If verbose is true, the rule should print debug info explaining why it's not concerned.
-    fn check_concern(&self, module_path: &crate::module_path::ModulePath, verbose: bool) -> bool;
+    fn check_concern(&self, _module_path: &ModulePath, _verbose: bool) -> bool {
+        true
+    }
+    /// Severity of the issues this rule reports.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    /// Configured `message_template` of the issues this rule reports (see `render_message`).
+    fn message_template(&self) -> Option<&str> {
+        None
+    }
+    /// File-level check run once all imports of a module are known, for rules about what a
+    /// module must (rather than must not) import. A failing outcome is reported on the file.
+    fn finalize(&self, _module_path: &ModulePath, _imports: &[ImportLine]) -> Option<RuleOutcome> {
+        None
+    }
+    /// Check all imports of a file at once, for rules whose outcome for an import depends on
+    /// the file's other imports (duplicates, counts, ...). Returns one outcome per import, in
+    /// order; defaults to `check_line` on each.
+    fn check_file(&self, current_file: &Path, imports: &[ImportLine]) -> Vec<RuleOutcome> {
+        imports
+            .iter()
+            .map(|import| self.check_line(current_file, import))
+            .collect()
+    }
+    /// Suggested edit for a failing outcome of `import`, given all imports of its file.
+    fn fix(&self, _import: &ImportLine, _imports: &[ImportLine]) -> Option<Fix> {
+        None
+    }
+    /// Whether `check_line` should also see imports resolved outside the project.
+    fn wants_external(&self) -> bool {
+        false
+    }
+    /// Whether the outcome for a file depends on that file alone, so its issues can be cached
+    /// until it changes. Rules reading other files opt out.
+    fn cacheable(&self) -> bool {
+        true
+    }
+    /// Whether test fixtures such as `conftest.py` are checked. Architecture rules leave them
+    /// alone; dependency rules opt in.
+    fn applies_to_fixtures(&self) -> bool {
+        false
+    }
+    /// Whether `file` (project-relative) is checked, per the rule's `applies_to` globs. Finer
+    /// grained than `check_concern`, which only sees module paths.
+    fn applies_to_file(&self, _file: &Path) -> bool {
+        true
+    }
 }
 
-pub fn build_rules(project: &ProjectConfig, config: &RunConfig) -> Vec<Box<dyn ImportRule>> {
-    let mut rules: Vec<Box<dyn ImportRule>> = Vec::new();
-    for linear in project.rules.linear.clone().into_iter() {
+/// A dependency `from -> to` that breaks a project rule. It is reported on the import
+/// statement creating that edge. Without `to`, it flags the module `from` as a whole,
+/// reported on its file (a package's `__init__` file).
+#[derive(Debug, Clone)]
+pub struct GraphViolation {
+    pub from: String,
+    pub to: Option<String>,
+    pub reason: String,
+}
+
+/// Rule evaluated once over the whole first-party dependency graph, for constraints that no
+/// single import can break on its own (transitive dependencies, cycles, ...).
+pub trait ProjectRule: Sync {
+    fn name(&self) -> &'static str;
+    /// Human-readable summary of this rule's configuration for display.
+    fn describe(&self) -> String;
+    fn check_graph(&self, graph: &DependencyGraph) -> Vec<GraphViolation>;
+    /// Severity of the issues this rule reports.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    /// Configured `message_template` of the issues this rule reports (see `render_message`).
+    fn message_template(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Render an issue `message_template`: each `{name}` placeholder of `fields` is replaced by
+/// its value, in one pass (values aren't re-expanded). Unknown placeholders are kept as is.
+pub fn render_message(template: &str, fields: &[(&str, &str)]) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            fields
+                .iter()
+                .find(|(field, _)| *field == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                message.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+/// `check_concern` of rules scoped to the modules under `folder`: the directories leading
+/// to it are walked too, to reach it.
+pub fn concerns_folder(
+    name: &str,
+    folder: &ModulePath,
+    module_path: &ModulePath,
+    verbose: bool,
+) -> bool {
+    let concerned = module_path.starts_with(folder) || folder.starts_with(module_path);
+    if !concerned && verbose {
+        println!(
+            "[{}] not concerned with {} (not under {})",
+            name,
+            module_path.to_dotted(),
+            folder.to_dotted()
+        );
+    }
+    concerned
+}
+
+/// First segment of `module` below `folder` (the submodule of `folder` it lives in), None
+/// outside `folder` or for `folder` itself.
+pub fn head_under_folder<'a>(folder: &ModulePath, module: &'a ModulePath) -> Option<&'a str> {
+    if !module.starts_with(folder) {
+        return None;
+    }
+    module
+        .segments()
+        .get(folder.segments().len())
+        .map(|s| s.as_str())
+}
+
+/// `folder` as shown by `describe`; the empty folder is the whole project.
+pub fn folder_label(folder: &ModulePath) -> String {
+    if folder.is_empty() {
+        String::from("<all>")
+    } else {
+        folder.to_dotted()
+    }
+}
+
+/// A built rule together with the options common to every rule definition.
+struct ConfiguredRule<R: ?Sized> {
+    rule: Box<R>,
+    severity: Severity,
+    /// `applies_to` patterns as configured, for `describe`
+    applies_to: Vec<String>,
+    message_template: Option<String>,
+    /// Compiled `applies_to`; `None` checks every file
+    files: Option<GlobSet>,
+}
+
+impl ImportRule for ConfiguredRule<dyn ImportRule> {
+    fn name(&self) -> &'static str {
+        self.rule.name()
+    }
+
+    fn check_line(&self, current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        self.rule.check_line(current_file, import)
+    }
+
+    fn describe(&self) -> String {
+        let mut description = format!(
+            "{} severity={}",
+            self.rule.describe(),
+            self.severity.as_str()
+        );
+        if !self.applies_to.is_empty() {
+            description.push_str(&format!(" applies_to=[{}]", self.applies_to.join(", ")));
+        }
+        // Part of the key of cached issues
+        if let Some(template) = &self.message_template {
+            description.push_str(&format!(" message_template={:?}", template));
+        }
+        description
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        self.rule.check_concern(module_path, verbose)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn message_template(&self) -> Option<&str> {
+        self.message_template.as_deref()
+    }
+
+    fn finalize(&self, module_path: &ModulePath, imports: &[ImportLine]) -> Option<RuleOutcome> {
+        self.rule.finalize(module_path, imports)
+    }
+
+    fn check_file(&self, current_file: &Path, imports: &[ImportLine]) -> Vec<RuleOutcome> {
+        self.rule.check_file(current_file, imports)
+    }
+
+    fn fix(&self, import: &ImportLine, imports: &[ImportLine]) -> Option<Fix> {
+        self.rule.fix(import, imports)
+    }
+
+    fn wants_external(&self) -> bool {
+        self.rule.wants_external()
+    }
+
+    fn cacheable(&self) -> bool {
+        self.rule.cacheable()
+    }
+
+    fn applies_to_fixtures(&self) -> bool {
+        self.rule.applies_to_fixtures()
+    }
+
+    fn applies_to_file(&self, file: &Path) -> bool {
+        self.files.as_ref().is_none_or(|files| files.is_match(file))
+    }
+}
+
+impl ProjectRule for ConfiguredRule<dyn ProjectRule> {
+    fn name(&self) -> &'static str {
+        self.rule.name()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} severity={}",
+            self.rule.describe(),
+            self.severity.as_str()
+        )
+    }
+
+    fn check_graph(&self, graph: &DependencyGraph) -> Vec<GraphViolation> {
+        self.rule.check_graph(graph)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn message_template(&self) -> Option<&str> {
+        self.message_template.as_deref()
+    }
+}
+
+/// Attach the configured severity (defaulting to error), `applies_to` globs and message
+/// template to a rule. Invalid patterns are skipped (and reported in verbose mode).
+fn configure(
+    rule: Box<dyn ImportRule>,
+    options: &RuleOptions,
+    verbose: bool,
+) -> Box<dyn ImportRule> {
+    let applies_to = &options.applies_to;
+    let mut files = GlobSetBuilder::new();
+    for pattern in applies_to {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                files.add(glob);
+            }
+            Err(e) => {
+                if verbose {
+                    eprintln!(
+                        "[{}] invalid applies_to pattern '{}': {}",
+                        rule.name(),
+                        pattern,
+                        e
+                    );
+                }
+            }
+        }
+    }
+    let files = if applies_to.is_empty() {
+        None
+    } else {
+        files.build().ok()
+    };
+    Box::new(ConfiguredRule {
+        rule,
+        severity: options.severity.unwrap_or_default(),
+        applies_to: applies_to.clone(),
+        message_template: options.message_template.clone(),
+        files,
+    })
+}
+
+/// Same as `configure`, for project rules.
+fn configure_project(rule: Box<dyn ProjectRule>, options: &RuleOptions) -> Box<dyn ProjectRule> {
+    Box::new(ConfiguredRule {
+        rule,
+        severity: options.severity.unwrap_or_default(),
+        applies_to: Vec::new(),
+        message_template: options.message_template.clone(),
+        files: None,
+    })
+}
+
+/// A misconfiguration of the project's rules, located at the directory it concerns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+/// Linear rules whose source module directory, ordered submodules or order are missing.
+/// These only warn in verbose mode, or become `Config` issues with `strict_config`.
+pub fn config_problems(project: &ProjectConfig) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    for linear in project.rules.linear.iter() {
         let mut source_mp = linear.source_module.clone();
         if source_mp.is_empty() {
             source_mp = project.source_modules.first().cloned().unwrap_or_default();
         }
-
-        // Validate configured source and ordered submodules exist
         let src_dir = source_mp.to_dir_pathbuf();
-        let verbose = config.verbose.unwrap_or(false);
+        if linear.order.is_empty() && linear.groups.iter().all(|group| group.is_empty()) {
+            problems.push(ConfigProblem {
+                path: src_dir.clone(),
+                message: format!(
+                    "linear rule on '{}' has an empty order",
+                    source_mp.to_dotted()
+                ),
+            });
+        }
         if !src_dir.is_dir() {
-            if verbose {
-                eprintln!(
-                    "[linear] warning: source module '{}' directory not found at {}",
+            problems.push(ConfigProblem {
+                message: format!(
+                    "source module '{}' directory not found at {}",
                     source_mp.to_dotted(),
                     src_dir.to_string_lossy()
-                );
+                ),
+                path: src_dir,
+            });
+            continue;
+        }
+        for elem in linear.order.iter().chain(linear.groups.iter().flatten()) {
+            let sub_dir = src_dir.join(elem);
+            let sub_file = src_dir.join(format!("{}.py", elem));
+            if !sub_dir.is_dir() && !sub_file.is_file() {
+                problems.push(ConfigProblem {
+                    path: src_dir.clone(),
+                    message: format!(
+                        "'{}' not found under '{}' (looked for {} or {})",
+                        elem,
+                        source_mp.to_dotted(),
+                        sub_dir.to_string_lossy(),
+                        sub_file.to_string_lossy()
+                    ),
+                });
             }
-        } else {
-            for elem in &linear.order {
-                let sub_dir = src_dir.join(elem);
-                let sub_file = src_dir.join(format!("{}.py", elem));
-                if !sub_dir.is_dir() && !sub_file.is_file() {
-                    if verbose {
-                        eprintln!(
-                            "[linear] warning: '{}' not found under '{}' (looked for {} or {})",
-                            elem,
-                            source_mp.to_dotted(),
-                            sub_dir.to_string_lossy(),
-                            sub_file.to_string_lossy()
-                        );
-                    }
+        }
+    }
+    problems
+}
+
+pub fn build_rules(project: &ProjectConfig, config: &RunConfig) -> Vec<Box<dyn ImportRule>> {
+    let verbose = config.verbose.unwrap_or(false);
+    if verbose {
+        for problem in config_problems(project) {
+            eprintln!("[linear] warning: {}", problem.message);
+        }
+    }
+    let mut rules: Vec<Box<dyn ImportRule>> = Vec::new();
+    for linear in project.rules.linear.clone().into_iter() {
+        let mut source_mp = linear.source_module.clone();
+        if source_mp.is_empty() {
+            source_mp = project.source_modules.first().cloned().unwrap_or_default();
+        }
+
+        rules.push(configure(
+            Box::new(crate::rules::linear::LinearOrderInFolder::new(
+                source_mp,
+                linear.order,
+                linear.groups,
+                linear.strict.unwrap_or(false),
+                linear.ignore_init.unwrap_or(false),
+            )),
+            &linear.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.layered.iter() {
+        let mut source_mp = def.source_module.clone();
+        if source_mp.is_empty() {
+            source_mp = project.source_modules.first().cloned().unwrap_or_default();
+        }
+        rules.push(configure(
+            Box::new(crate::rules::layered::LayeredRule::new(
+                source_mp,
+                def.layers.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.independence.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::independence::IndependenceRule::new(
+                def.source_module.clone(),
+                def.groups.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.allowed_externals.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::externals::AllowedExternals::new(
+                def.source_module.clone(),
+                def.allowed.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.forbidden.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::forbidden::ForbiddenImports::new(
+                def.source_module.clone(),
+                def.modules.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.interface_implementation.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::interface::InterfaceImplementation::new(
+                def.parent.clone(),
+                def.interface.clone(),
+                def.implementation.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.version_isolation.iter() {
+        let pattern = def
+            .version_pattern
+            .as_deref()
+            .unwrap_or(crate::rules::versions::DEFAULT_VERSION_PATTERN);
+        match regex::Regex::new(pattern) {
+            Ok(version_pattern) => rules.push(configure(
+                Box::new(crate::rules::versions::VersionIsolation::new(
+                    def.parent.clone(),
+                    version_pattern,
+                )),
+                &def.options,
+                verbose,
+            )),
+            Err(e) => {
+                if verbose {
+                    eprintln!(
+                        "[versions] warning: invalid version_pattern '{}': {}",
+                        pattern, e
+                    );
                 }
             }
         }
+    }
+    for def in project.rules.reserved_names.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::reserved::ReservedNames::new(
+                def.source_module.clone(),
+                def.extra.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.visibility.iter() {
+        let cache = ResolverCache::new();
+        let resolvers = crate::walker::source_modules(project)
+            .iter()
+            .map(|source| crate::walker::resolver_for_source(source, project, config, &cache))
+            .collect();
+        rules.push(configure(
+            Box::new(crate::rules::visibility::ModuleVisibility::new(
+                def.source_module.clone(),
+                resolvers,
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.respect_dunder_all.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::dunder_all::RespectDunderAllRule::new(
+                def.source_module.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.max_import_depth.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::depth::MaxImportDepthRule::new(
+                def.source_module.clone(),
+                def.max_depth,
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.no_relative_imports.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::relative::NoRelativeImportsRule::new(
+                def.source_module.clone(),
+                def.prefer,
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.boundary.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::boundary::BoundaryRule::new(
+                def.source_module.clone(),
+                def.forbidden_sources.clone(),
+                def.direction,
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.test_scope.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::scope::TestScopeRule::new(
+                def.tests_module.clone(),
+                def.subject_module.clone(),
+                def.test_prefix.clone(),
+                def.common.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.public_api.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::public::PublicApiRule::new(
+                def.source_module.clone(),
+                def.packages.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.no_private_imports.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::private::NoPrivateImportsRule::new(
+                def.source_module.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.no_first_party_alias.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::alias::NoFirstPartyAliasRule::new(
+                def.source_module.clone(),
+                def.min_length,
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.coupling.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::coupling::CouplingRule::new(
+                def.from_prefix.clone(),
+                def.to_prefix.clone(),
+                def.max,
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.no_duplicate_imports.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::duplicates::NoDuplicateImportsRule::new(
+                def.source_module.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    for def in project.rules.no_wildcard.iter() {
+        rules.push(configure(
+            Box::new(crate::rules::wildcard::NoWildcardRule::new(
+                def.source_module.clone(),
+            )),
+            &def.options,
+            verbose,
+        ));
+    }
+    rules.retain(|rule| !config.disables_rule(rule.name()));
+    rules
+}
 
-        rules.push(Box::new(crate::rules::linear::LinearOrderInFolder::new(
-            source_mp,
-            linear.order,
-        )));
+/// Build the rules evaluated over the whole dependency graph.
+pub fn build_project_rules(
+    project: &ProjectConfig,
+    config: &RunConfig,
+) -> Vec<Box<dyn ProjectRule>> {
+    let mut rules: Vec<Box<dyn ProjectRule>> = Vec::new();
+    for def in project.rules.fan.iter() {
+        rules.push(configure_project(
+            Box::new(crate::rules::fan::FanRule::new(
+                def.max_fan_out,
+                def.max_fan_in,
+            )),
+            &def.options,
+        ));
+    }
+    for def in project.rules.no_cycles.iter() {
+        rules.push(configure_project(
+            Box::new(crate::rules::cycles::NoCyclesRule),
+            &def.options,
+        ));
     }
+    for def in project.rules.transitive_leaf.iter() {
+        rules.push(configure_project(
+            Box::new(crate::rules::leaf::TransitiveLeaf::new(def.modules.clone())),
+            &def.options,
+        ));
+    }
+    for def in project.rules.reverse_allowlist.iter() {
+        rules.push(configure_project(
+            Box::new(crate::rules::allowlist::ReverseAllowlistRule::new(
+                def.protected.clone(),
+                def.allowed_importers.clone(),
+            )),
+            &def.options,
+        ));
+    }
+    rules.retain(|rule| !config.disables_rule(rule.name()));
     rules
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{build_rules, render_message};
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::results::Severity;
+    use crate::test_support::import_of;
+
+    #[test]
+    fn severity_defaults_to_error_and_can_be_configured() {
+        let project: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [
+                {"order": ["a", "b"]},
+                {"order": ["c", "d"], "severity": "warning"}
+            ]},
+        }))
+        .unwrap();
+        let rules = build_rules(&project, &RunConfig::default());
+        let severities: Vec<Severity> = rules.iter().map(|r| r.severity()).collect();
+        assert_eq!(severities, [Severity::Error, Severity::Warning]);
+    }
+
+    #[test]
+    fn layered_source_module_defaults_to_first_source_module() {
+        let project: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"layered": [{"layers": [["models"], ["api"]]}]},
+        }))
+        .unwrap();
+        let rules = build_rules(&project, &RunConfig::default());
+        assert_eq!(
+            rules[0].describe(),
+            "folder=app layers=[models] -> [api] severity=error"
+        );
+    }
+
+    #[test]
+    fn render_message_substitutes_known_placeholders_once() {
+        let fields = [("target", "app.{reason}"), ("reason", "not allowed")];
+        assert_eq!(
+            render_message("{target}: {reason} {other} {", &fields),
+            "app.{reason}: not allowed {other} {"
+        );
+        assert_eq!(
+            render_message("no placeholders", &fields),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn check_file_defaults_to_check_line_on_each_import() {
+        let project: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": ["a", "b"]}]},
+        }))
+        .unwrap();
+        let rules = build_rules(&project, &RunConfig::default());
+        let file = Path::new("app/a.py");
+        let imports = [import_of("app.a", "app.b"), import_of("app.b", "app.a")];
+        let passes: Vec<bool> = rules[0]
+            .check_file(file, &imports)
+            .into_iter()
+            .map(|outcome| outcome.pass)
+            .collect();
+        let expected: Vec<bool> = imports
+            .iter()
+            .map(|import| rules[0].check_line(file, import).pass)
+            .collect();
+        assert_eq!(passes, expected);
+    }
+}
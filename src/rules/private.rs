@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Forbids reaching into another package's private modules, e.g. `pkg._internal.thing`.
+/// Segments shared with the importing module's package don't count, and a module may import
+/// the privates of its siblings (same parent package). Dunder segments such as `__init__`
+/// are public.
+pub struct NoPrivateImportsRule {
+    source_folder: ModulePath,
+}
+
+impl NoPrivateImportsRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath) -> Self {
+        NoPrivateImportsRule { source_folder }
+    }
+}
+
+fn is_private(segment: &str) -> bool {
+    let dunder = segment.len() > 4 && segment.starts_with("__") && segment.ends_with("__");
+    segment.starts_with('_') && !dunder
+}
+
+impl ImportRule for NoPrivateImportsRule {
+    fn name(&self) -> &'static str {
+        "NoPrivateImports"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let own_package = import
+            .from_module
+            .split_last()
+            .map(|(_, parent)| parent)
+            .unwrap_or_default();
+        let target_package = import
+            .target_module
+            .split_last()
+            .map(|(_, parent)| parent)
+            .unwrap_or_default();
+        if own_package == target_package {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ok: sibling module"),
+            };
+        }
+        let shared = own_package
+            .segments()
+            .iter()
+            .zip(import.target_module.segments())
+            .take_while(|(own, target)| own == target)
+            .count();
+        let private = import
+            .target_module
+            .segments()
+            .iter()
+            .enumerate()
+            .skip(shared.max(1))
+            .find(|(_, segment)| is_private(segment));
+        match private {
+            Some((_, segment)) => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "'{}' is private to '{}'",
+                    segment,
+                    import.target_module.to_dotted()
+                ),
+            },
+            None => RuleOutcome {
+                pass: true,
+                reason: String::from("ok: no private module"),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={}", folder)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoPrivateImportsRule;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(from: &str, target: &str) -> (bool, String) {
+        let import = import_of(from, target);
+        let rule = NoPrivateImportsRule::new(ModulePath::default());
+        let outcome = rule.check_line(Path::new("x.py"), &import);
+        (outcome.pass, outcome.reason)
+    }
+
+    #[test]
+    fn private_modules_of_other_packages_fail() {
+        assert_eq!(
+            check("app.api.views", "app.core._internal.thing"),
+            (
+                false,
+                String::from("'_internal' is private to 'app.core._internal.thing'")
+            )
+        );
+        assert!(!check("app.api.views", "app.core._helpers").0);
+        assert!(!check("app.core.engine", "app.core._internal.thing").0);
+    }
+
+    #[test]
+    fn siblings_own_package_and_dunders_pass() {
+        assert!(check("app.core.engine", "app.core._helpers").0);
+        assert!(check("app._impl.a", "app._impl.sub.b").0);
+        assert!(check("app.api.views", "app.core.__init__").0);
+        assert!(check("app.api.views", "_thread").0);
+        assert!(check("app.api.views", "app.core.models").0);
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Packages exposing their public API in `__init__.py`: modules outside a listed package may
+/// import the package and the names it defines (`from pkg import thing`), but not reach
+/// deeper (`pkg.internal.module`). Modules inside the package are not restricted.
+pub struct PublicApiRule {
+    source_folder: ModulePath,
+    packages: Vec<ModulePath>,
+}
+
+impl PublicApiRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, packages: Vec<ModulePath>) -> Self {
+        PublicApiRule {
+            source_folder,
+            packages,
+        }
+    }
+}
+
+impl ImportRule for PublicApiRule {
+    fn name(&self) -> &'static str {
+        "PublicApi"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let bypassed = self.packages.iter().find(|package| {
+            !import.from_module.starts_with(package)
+                && import
+                    .target_module
+                    .relative_from(package)
+                    .is_some_and(|rel| rel.segments().len() > 1)
+        });
+        match bypassed {
+            Some(package) => RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "import '{}' bypasses public API of '{}'",
+                    import.target_module.to_dotted(),
+                    package.to_dotted()
+                ),
+            },
+            None => RuleOutcome {
+                pass: true,
+                reason: String::from("ok: through public API"),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let packages = self
+            .packages
+            .iter()
+            .map(|m| m.to_dotted())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("folder={} packages=[{}]", folder, packages)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublicApiRule;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    #[test]
+    fn outside_importers_only_reach_the_package_surface() {
+        let rule = PublicApiRule::new(
+            ModulePath::default(),
+            vec![ModulePath::from_dotted("app.billing")],
+        );
+        let check = |from: &str, target: &str| {
+            let import = import_of(from, target);
+            let outcome = rule.check_line(Path::new("x.py"), &import);
+            (outcome.pass, outcome.reason)
+        };
+        assert!(check("app.api", "app.billing").0);
+        assert!(check("app.api", "app.billing.Invoice").0);
+        assert_eq!(
+            check("app.api", "app.billing.internal.tax"),
+            (
+                false,
+                String::from(
+                    "import 'app.billing.internal.tax' bypasses public API of 'app.billing'"
+                )
+            )
+        );
+        assert!(check("app.billing.service", "app.billing.internal.tax").0);
+        assert!(check("app.api", "app.billing_v2.internal.tax").0);
+    }
+}
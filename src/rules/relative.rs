@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use crate::configs::ImportStyle;
+use crate::imports::import_line::{ImportKind, ImportLine};
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Enforces one import style for local imports. Preferring absolute imports (the default)
+/// forbids relative ones; preferring relative imports forbids absolute imports of modules in
+/// the importer's own top-level package, the only ones that could be written relatively.
+pub struct NoRelativeImportsRule {
+    source_folder: ModulePath,
+    prefer: ImportStyle,
+}
+
+impl NoRelativeImportsRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, prefer: ImportStyle) -> Self {
+        NoRelativeImportsRule {
+            source_folder,
+            prefer,
+        }
+    }
+}
+
+impl ImportRule for NoRelativeImportsRule {
+    fn name(&self) -> &'static str {
+        "NoRelativeImports"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let relative = import.kind == ImportKind::RelativeImport;
+        match self.prefer {
+            ImportStyle::Absolute if relative => RuleOutcome {
+                pass: false,
+                reason: String::from("relative import not allowed; use absolute path"),
+            },
+            ImportStyle::Relative
+                if !relative
+                    && import.from_module.segments().first()
+                        == import.target_module.segments().first() =>
+            {
+                RuleOutcome {
+                    pass: false,
+                    reason: String::from("absolute import not allowed; use relative path"),
+                }
+            }
+            _ => RuleOutcome {
+                pass: true,
+                reason: String::from("ok: preferred import style"),
+            },
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        let prefer = match self.prefer {
+            ImportStyle::Absolute => "absolute",
+            ImportStyle::Relative => "relative",
+        };
+        format!("folder={} prefer={}", folder, prefer)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoRelativeImportsRule;
+    use crate::configs::ImportStyle;
+    use crate::imports::import_line::{ImportKind, ImportLine};
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(rule: &NoRelativeImportsRule, target: &str, kind: ImportKind) -> (bool, String) {
+        let import = ImportLine {
+            kind,
+            ..import_of("app.core.engine", target)
+        };
+        let outcome = rule.check_line(Path::new("app/core/engine.py"), &import);
+        (outcome.pass, outcome.reason)
+    }
+
+    #[test]
+    fn relative_imports_fail_when_absolute_is_preferred() {
+        let rule = NoRelativeImportsRule::new(ModulePath::default(), ImportStyle::Absolute);
+        assert_eq!(
+            check(&rule, "app.core.models", ImportKind::RelativeImport),
+            (
+                false,
+                String::from("relative import not allowed; use absolute path")
+            )
+        );
+        assert!(check(&rule, "app.core.models", ImportKind::FromImport).0);
+        assert!(check(&rule, "app.core.models", ImportKind::Import).0);
+
+        let scoped =
+            NoRelativeImportsRule::new(ModulePath::from_dotted("app.api"), ImportStyle::Absolute);
+        assert!(check(&scoped, "app.core.models", ImportKind::RelativeImport).0);
+    }
+
+    #[test]
+    fn local_absolute_imports_fail_when_relative_is_preferred() {
+        let rule = NoRelativeImportsRule::new(ModulePath::default(), ImportStyle::Relative);
+        assert!(check(&rule, "app.core.models", ImportKind::RelativeImport).0);
+        assert_eq!(
+            check(&rule, "app.core.models", ImportKind::FromImport),
+            (
+                false,
+                String::from("absolute import not allowed; use relative path")
+            )
+        );
+        assert!(!check(&rule, "app.api", ImportKind::Import).0);
+        // Another top-level package cannot be imported relatively
+        assert!(check(&rule, "shared.utils", ImportKind::Import).0);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Python builtin function and type names.
+const BUILTINS: &[&str] = &[
+    "abs",
+    "aiter",
+    "all",
+    "anext",
+    "any",
+    "ascii",
+    "bin",
+    "bool",
+    "breakpoint",
+    "bytearray",
+    "bytes",
+    "callable",
+    "chr",
+    "classmethod",
+    "compile",
+    "complex",
+    "delattr",
+    "dict",
+    "dir",
+    "divmod",
+    "enumerate",
+    "eval",
+    "exec",
+    "filter",
+    "float",
+    "format",
+    "frozenset",
+    "getattr",
+    "globals",
+    "hasattr",
+    "hash",
+    "help",
+    "hex",
+    "id",
+    "input",
+    "int",
+    "isinstance",
+    "issubclass",
+    "iter",
+    "len",
+    "list",
+    "locals",
+    "map",
+    "max",
+    "memoryview",
+    "min",
+    "next",
+    "object",
+    "oct",
+    "open",
+    "ord",
+    "pow",
+    "print",
+    "property",
+    "range",
+    "repr",
+    "reversed",
+    "round",
+    "set",
+    "setattr",
+    "slice",
+    "sorted",
+    "staticmethod",
+    "str",
+    "sum",
+    "super",
+    "tuple",
+    "type",
+    "vars",
+    "zip",
+];
+
+/// Soft keywords: valid identifiers that act as keywords in some contexts.
+const SOFT_KEYWORDS: &[&str] = &["match", "case", "type", "_"];
+
+/// Flags imports of local modules whose leaf name collides with a builtin or soft keyword
+/// (e.g. `type.py`, `list.py`). The bundled list can be extended from config.
+pub struct ReservedNames {
+    source_folder: ModulePath,
+    reserved: HashSet<String>,
+}
+
+impl ReservedNames {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, extra: Vec<String>) -> Self {
+        let reserved = BUILTINS
+            .iter()
+            .chain(SOFT_KEYWORDS)
+            .map(|name| name.to_string())
+            .chain(extra)
+            .collect();
+        ReservedNames {
+            source_folder,
+            reserved,
+        }
+    }
+}
+
+impl ImportRule for ReservedNames {
+    fn name(&self) -> &'static str {
+        "ReservedName"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let leaf = import
+            .target_module
+            .segments()
+            .last()
+            .map(|s| s.as_str())
+            .unwrap_or("");
+        if self.reserved.contains(leaf) {
+            return RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "module name '{}' collides with a Python builtin or keyword",
+                    leaf
+                ),
+            };
+        }
+        RuleOutcome {
+            pass: true,
+            reason: format!("ok: '{}' is not reserved", leaf),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={} reserved_names={}", folder, self.reserved.len())
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReservedNames;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(rule: &ReservedNames, target: &str) -> bool {
+        let import = import_of("app.main", target);
+        rule.check_line(Path::new("app/main.py"), &import).pass
+    }
+
+    #[test]
+    fn builtin_and_keyword_names_are_flagged() {
+        let rule = ReservedNames::new(ModulePath::default(), Vec::new());
+        assert!(!check(&rule, "app.type"));
+        assert!(!check(&rule, "app.utils.match"));
+        assert!(check(&rule, "app.users"));
+    }
+
+    #[test]
+    fn reserved_list_is_extensible() {
+        let rule = ReservedNames::new(ModulePath::default(), vec![String::from("typing")]);
+        assert!(!check(&rule, "app.typing"));
+    }
+}
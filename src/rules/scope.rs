@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, ImportRule, RuleOutcome};
+
+/// Keeps each test file to its subject module: `tests/foo/test_bar.py` may only import
+/// `<subject_module>.foo.bar` (and its submodules) plus the `common` modules, e.g. test
+/// utilities. The subject is derived by stripping `tests_module` from the test's module path
+/// and `test_prefix` from its file name. Other files under `tests_module` are not checked,
+/// nor are third-party imports.
+pub struct TestScopeRule {
+    tests_module: ModulePath,
+    subject_module: ModulePath,
+    test_prefix: String,
+    common: Vec<ModulePath>,
+}
+
+impl TestScopeRule {
+    pub fn new(
+        tests_module: ModulePath,
+        subject_module: ModulePath,
+        test_prefix: String,
+        common: Vec<ModulePath>,
+    ) -> Self {
+        TestScopeRule {
+            tests_module,
+            subject_module,
+            test_prefix,
+            common,
+        }
+    }
+
+    /// Subject of the test in `current_file` (module `module`), or None when it is not a
+    /// test file of `tests_module`.
+    fn subject(&self, current_file: &Path, module: &ModulePath) -> Option<ModulePath> {
+        let stem = current_file.file_stem()?.to_str()?;
+        let name = stem.strip_prefix(self.test_prefix.as_str())?;
+        let rel = module.relative_from(&self.tests_module)?;
+        let (last, package) = rel.split_last()?;
+        if last != stem || name.is_empty() {
+            return None;
+        }
+        let mut segments = self.subject_module.segments().to_vec();
+        segments.extend(package.segments().iter().cloned());
+        segments.push(name.to_string());
+        Some(ModulePath::new(segments))
+    }
+}
+
+impl ImportRule for TestScopeRule {
+    fn name(&self) -> &'static str {
+        "TestScope"
+    }
+
+    fn check_line(&self, current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        let subject = match self.subject(current_file, &import.from_module) {
+            Some(subject) => subject,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("out of scope (not a test file)"),
+                }
+            }
+        };
+        // The subject package is usually another source root, resolved as external here
+        let first_party = !import.external
+            || (!self.subject_module.is_empty()
+                && import.target_module.starts_with(&self.subject_module));
+        if !first_party {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("external import"),
+            };
+        }
+        if import.target_module.starts_with(&subject) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ok: subject of the test"),
+            };
+        }
+        if self
+            .common
+            .iter()
+            .any(|common| import.target_module.starts_with_pattern(common))
+        {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ok: common module"),
+            };
+        }
+        RuleOutcome {
+            pass: false,
+            reason: format!(
+                "test of '{}' must not import '{}'",
+                subject.to_dotted(),
+                import.target_module.to_dotted()
+            ),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let common = self
+            .common
+            .iter()
+            .map(|m| m.to_dotted())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            "tests={} subject={} test_prefix={} common=[{}]",
+            self.tests_module.to_dotted(),
+            self.subject_module.to_dotted(),
+            self.test_prefix,
+            common
+        )
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.tests_module, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestScopeRule;
+    use crate::imports::import_line::ImportLine;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use std::path::Path;
+
+    fn check(file: &str, from: &str, target: &str) -> (bool, String) {
+        let rule = TestScopeRule::new(
+            ModulePath::from_dotted("tests"),
+            ModulePath::from_dotted("app"),
+            String::from("test_"),
+            vec![ModulePath::from_dotted("tests.utils")],
+        );
+        let import = ImportLine {
+            external: !target.starts_with("tests"),
+            ..import_of(from, target)
+        };
+        let outcome = rule.check_line(Path::new(file), &import);
+        (outcome.pass, outcome.reason)
+    }
+
+    #[test]
+    fn tests_only_import_their_subject_and_common_modules() {
+        let test = |target| check("tests/foo/test_bar.py", "tests.foo.test_bar", target);
+        assert!(test("app.foo.bar").0);
+        assert!(test("app.foo.bar.models").0);
+        assert!(test("tests.utils.factories").0);
+        assert_eq!(
+            test("app.foo.baz"),
+            (
+                false,
+                String::from("test of 'app.foo.bar' must not import 'app.foo.baz'")
+            )
+        );
+        assert!(!test("app.foo.barista").0);
+        assert!(test("pytest").0);
+    }
+
+    #[test]
+    fn other_files_are_out_of_scope() {
+        assert!(check("tests/conftest.py", "tests.conftest", "app.db").0);
+        assert!(check("tests/foo/__init__.py", "tests.foo", "app.db").0);
+        assert!(check("app/test_x.py", "app.test_x", "app.db").0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, ImportRule, RuleOutcome};
+
+/// Default pattern for version package names (`v1`, `v2`, ...).
+pub const DEFAULT_VERSION_PATTERN: &str = r"^v\d+$";
+
+/// Keeps versioned sibling packages (e.g. `app.api.v1` and `app.api.v2`) independent.
+/// Imports between two different version siblings under `parent` fail; imports of shared,
+/// non-versioned code under the same parent are allowed.
+pub struct VersionIsolation {
+    parent: ModulePath,
+    version_pattern: Regex,
+}
+
+impl VersionIsolation {
+    pub fn new(parent: ModulePath, version_pattern: Regex) -> Self {
+        VersionIsolation {
+            parent,
+            version_pattern,
+        }
+    }
+
+    /// The version sibling a module belongs to, if it lives under a versioned child of `parent`.
+    fn version_of<'a>(&self, module: &'a ModulePath) -> Option<&'a str> {
+        let prefix_len = self.parent.segments().len();
+        if !module.starts_with(&self.parent) {
+            return None;
+        }
+        module
+            .segments()
+            .get(prefix_len)
+            .map(|s| s.as_str())
+            .filter(|head| self.version_pattern.is_match(head))
+    }
+}
+
+impl ImportRule for VersionIsolation {
+    fn name(&self) -> &'static str {
+        "VersionIsolation"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        let current = match self.version_of(&import.from_module) {
+            Some(v) => v,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("out of scope (not in a version package)"),
+                }
+            }
+        };
+        let target = match self.version_of(&import.target_module) {
+            Some(v) => v,
+            None => {
+                return RuleOutcome {
+                    pass: true,
+                    reason: String::from("target is not versioned"),
+                }
+            }
+        };
+        if current == target {
+            return RuleOutcome {
+                pass: true,
+                reason: format!("ok: same version '{}'", current),
+            };
+        }
+        RuleOutcome {
+            pass: false,
+            reason: format!(
+                "version violation: '{}' cannot import from version '{}'",
+                current, target
+            ),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "parent={} versions={}",
+            self.parent.to_dotted(),
+            self.version_pattern.as_str()
+        )
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        // Concerned with the parent's subtree and with the directories leading to it
+        concerns_folder(self.name(), &self.parent, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VersionIsolation, DEFAULT_VERSION_PATTERN};
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::import_of;
+    use regex::Regex;
+    use std::path::Path;
+
+    fn rule() -> VersionIsolation {
+        VersionIsolation::new(
+            ModulePath::from_dotted("app.api"),
+            Regex::new(DEFAULT_VERSION_PATTERN).unwrap(),
+        )
+    }
+
+    #[test]
+    fn cross_version_import_fails() {
+        let outcome = rule().check_line(
+            Path::new("x.py"),
+            &import_of("app.api.v1.users", "app.api.v2.users"),
+        );
+        assert!(!outcome.pass);
+        assert!(outcome
+            .reason
+            .contains("'v1' cannot import from version 'v2'"));
+    }
+
+    #[test]
+    fn shared_and_same_version_imports_pass() {
+        let rule = rule();
+        assert!(
+            rule.check_line(
+                Path::new("x.py"),
+                &import_of("app.api.v1.users", "app.api.shared.auth")
+            )
+            .pass
+        );
+        assert!(
+            rule.check_line(
+                Path::new("x.py"),
+                &import_of("app.api.v1.users", "app.api.v1.models")
+            )
+            .pass
+        );
+        assert!(
+            rule.check_line(
+                Path::new("x.py"),
+                &import_of("app.core", "app.api.v2.users")
+            )
+            .pass
+        );
+    }
+}
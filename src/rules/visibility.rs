@@ -0,0 +1,240 @@
+use std::path::Path;
+
+use dashmap::DashMap;
+
+use crate::imports::classification::ImportResolver;
+use crate::imports::collection::parse_visibility_directive;
+use crate::imports::encoding::read_source;
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Visibility a module declares with a `# importee: visibility=<value>` comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Importable from anywhere (the default)
+    Public,
+    /// Importable only from within the package containing the module
+    Internal,
+    /// Importable only from within the module's own tree (its submodules, for a package)
+    Private,
+}
+
+impl Visibility {
+    /// Unknown values are treated as public.
+    fn parse(value: &str) -> Self {
+        match value {
+            "internal" => Visibility::Internal,
+            "private" => Visibility::Private,
+            _ => Visibility::Public,
+        }
+    }
+}
+
+/// Enforces the visibility modules declare for themselves. The directive is read from the
+/// imported module's source (its module file, or `__init__` for a package, as the project's
+/// resolvers find it) the first time the module is imported; modules without one are public.
+/// A package's visibility also covers its submodules, and the most restrictive visibility
+/// along the imported module's packages applies.
+pub struct ModuleVisibility {
+    source_folder: ModulePath,
+    /// One resolver per source module, locating the files of imported modules
+    resolvers: Vec<ImportResolver>,
+    /// Dotted module -> declared visibility, shared by all files of the run
+    declared: DashMap<String, Visibility>,
+}
+
+impl ModuleVisibility {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath, resolvers: Vec<ImportResolver>) -> Self {
+        ModuleVisibility {
+            source_folder,
+            resolvers,
+            declared: DashMap::new(),
+        }
+    }
+
+    fn visibility_of(&self, module: &ModulePath) -> Visibility {
+        let key = module.to_dotted();
+        if let Some(visibility) = self.declared.get(&key) {
+            return *visibility;
+        }
+        let visibility = self
+            .resolvers
+            .iter()
+            .find_map(|resolver| resolver.module_source(module))
+            .and_then(|path| read_source(&path).ok().map(|(_, source)| source))
+            .and_then(|source| parse_visibility_directive(&source).map(Visibility::parse))
+            .unwrap_or(Visibility::Public);
+        self.declared.insert(key, visibility);
+        visibility
+    }
+
+    /// The narrowest scope `target` may be imported from, given the visibility declared by
+    /// it and by each of its packages, with that visibility's name and the declaring module.
+    /// None when all of them are public.
+    fn scope_of(&self, target: &ModulePath) -> Option<(ModulePath, &'static str, ModulePath)> {
+        let segments = target.segments();
+        (1..=segments.len())
+            .map(|len| ModulePath::new(segments[..len].to_vec()))
+            .filter_map(|module| match self.visibility_of(&module) {
+                Visibility::Public => None,
+                Visibility::Internal => {
+                    let package = module.split_last().map(|(_, parent)| parent)?;
+                    Some((package, "internal", module))
+                }
+                Visibility::Private => Some((module.clone(), "private", module)),
+            })
+            .max_by_key(|(scope, _, _)| scope.segments().len())
+    }
+}
+
+impl ImportRule for ModuleVisibility {
+    fn name(&self) -> &'static str {
+        "Visibility"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        let target = &import.target_module;
+        let Some((scope, kind, declared_by)) = self.scope_of(target) else {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("ok: target is public"),
+            };
+        };
+        if import.from_module.starts_with(&scope) {
+            RuleOutcome {
+                pass: true,
+                reason: format!("ok: imported from within '{}'", scope.to_dotted()),
+            }
+        } else if declared_by == *target {
+            RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "'{}' is {} to '{}'",
+                    target.to_dotted(),
+                    kind,
+                    scope.to_dotted()
+                ),
+            }
+        } else {
+            RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "'{}' is in '{}', {} to '{}'",
+                    target.to_dotted(),
+                    declared_by.to_dotted(),
+                    kind,
+                    scope.to_dotted()
+                ),
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={}", folder)
+    }
+
+    fn cacheable(&self) -> bool {
+        // Visibility is declared in the imported modules
+        false
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModuleVisibility;
+    use crate::imports::classification::ImportResolver;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::{import_of, TempProject};
+    use std::path::Path;
+
+    /// The rule over the whole project, for files under the `app` source.
+    fn rule() -> ModuleVisibility {
+        let resolver = ImportResolver::new("app", Some(ModulePath::from_dotted("app")), false)
+            .with_src_layout(None);
+        ModuleVisibility::new(ModulePath::default(), vec![resolver])
+    }
+
+    fn check(rule: &ModuleVisibility, from: &str, target: &str) -> bool {
+        let import = import_of(from, target);
+        rule.check_line(Path::new("app/main.py"), &import).pass
+    }
+
+    #[test]
+    fn internal_module_is_only_importable_within_its_package() {
+        let project = TempProject::new();
+        project.write("app/core/secret.py", "# importee: visibility=internal\n");
+        project.write("app/core/plain.py", "");
+        let rule = rule();
+
+        assert!(check(&rule, "app.core.engine", "app.core.secret"));
+        assert!(check(&rule, "app.core.sub.deep", "app.core.secret"));
+        assert!(!check(&rule, "app.api.views", "app.core.secret"));
+        // Undeclared modules are public
+        assert!(check(&rule, "app.api.views", "app.core.plain"));
+    }
+
+    #[test]
+    fn private_package_is_only_importable_from_its_own_tree() {
+        let project = TempProject::new();
+        project.write("app/vault/__init__.py", "# importee: visibility=private\n");
+        project.write("app/vault/store.py", "");
+        let rule = rule();
+
+        assert!(check(&rule, "app.vault.store", "app.vault"));
+        assert!(!check(&rule, "app.other", "app.vault"));
+        // The package's submodules are just as private
+        assert!(!check(&rule, "app.other", "app.vault.store"));
+    }
+
+    #[test]
+    fn most_restrictive_visibility_along_the_packages_applies() {
+        let project = TempProject::new();
+        project.write("app/core/__init__.py", "# importee: visibility=internal\n");
+        project.write(
+            "app/core/vault/__init__.py",
+            "# importee: visibility=private\n",
+        );
+        project.write("app/core/vault/keys.py", "");
+        let rule = rule();
+
+        assert!(check(&rule, "app.core.vault.store", "app.core.vault.keys"));
+        assert!(!check(&rule, "app.core.engine", "app.core.vault.keys"));
+        assert!(check(&rule, "app.core.engine", "app.core.other"));
+        assert!(!check(&rule, "lib.views", "app.core.other"));
+        let outcome = rule.check_line(
+            Path::new("app/core/engine.py"),
+            &import_of("app.core.engine", "app.core.vault.keys"),
+        );
+        assert_eq!(
+            outcome.reason,
+            "'app.core.vault.keys' is in 'app.core.vault', private to 'app.core.vault'"
+        );
+    }
+
+    #[test]
+    fn directives_are_read_where_the_resolver_finds_the_module() {
+        let project = TempProject::new();
+        project.write(
+            "src/app/core/secret.py",
+            "# importee: visibility=internal\n",
+        );
+        let rule = rule();
+
+        assert!(!check(&rule, "app.api.views", "app.core.secret"));
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+
+use super::{concerns_folder, folder_label, ImportRule, RuleOutcome};
+
+/// Forbids `from x import *`, whether `x` is local or external.
+pub struct NoWildcardRule {
+    source_folder: ModulePath,
+}
+
+impl NoWildcardRule {
+    /// An empty `source_folder` applies the rule to every module.
+    pub fn new(source_folder: ModulePath) -> Self {
+        NoWildcardRule { source_folder }
+    }
+}
+
+impl ImportRule for NoWildcardRule {
+    fn name(&self) -> &'static str {
+        "NoWildcard"
+    }
+
+    fn check_line(&self, _current_file: &Path, import: &ImportLine) -> RuleOutcome {
+        if !import.from_module.starts_with(&self.source_folder) {
+            return RuleOutcome {
+                pass: true,
+                reason: String::from("out of scope (not under source folder)"),
+            };
+        }
+        if import.wildcard {
+            RuleOutcome {
+                pass: false,
+                reason: format!(
+                    "wildcard import from '{}' is not allowed",
+                    import.target_module.to_dotted()
+                ),
+            }
+        } else {
+            RuleOutcome {
+                pass: true,
+                reason: String::from("ok: not a wildcard import"),
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        let folder = folder_label(&self.source_folder);
+        format!("folder={}", folder)
+    }
+
+    fn check_concern(&self, module_path: &ModulePath, verbose: bool) -> bool {
+        concerns_folder(self.name(), &self.source_folder, module_path, verbose)
+    }
+
+    fn wants_external(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoWildcardRule;
+    use crate::configs::RunConfig;
+    use crate::imports::classification::ImportResolver;
+    use crate::imports::collection::get_file_imports;
+    use crate::module_path::ModulePath;
+    use crate::rules::ImportRule;
+    use crate::test_support::{run_of, TempProject};
+    use std::path::Path;
+
+    #[test]
+    fn wildcard_imports_fail_within_the_source_folder() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/core/__init__.py", "");
+        project.write("app/core/models.py", "");
+        let resolver = ImportResolver::new(
+            Path::new("app"),
+            Some(ModulePath::from_dotted("app")),
+            false,
+        );
+        let rule = NoWildcardRule::new(ModulePath::from_dotted("app.api"));
+        let source =
+            "from app.core.models import *\nfrom os.path import *\nfrom app.core import models\n";
+        let outcomes = |module: &str| -> Vec<(bool, String)> {
+            get_file_imports(
+                &ModulePath::from_dotted(module),
+                &resolver,
+                &run_of(RunConfig::default()),
+                Some(source),
+            )
+            .unwrap()
+            .iter()
+            .map(|imp| {
+                let outcome = rule.check_line(Path::new("app/api/views.py"), imp);
+                (outcome.pass, outcome.reason)
+            })
+            .collect()
+        };
+
+        let inside = outcomes("app.api.views");
+        assert_eq!(
+            inside,
+            [
+                (
+                    false,
+                    String::from("wildcard import from 'app.core.models' is not allowed")
+                ),
+                (
+                    false,
+                    String::from("wildcard import from 'os.path' is not allowed")
+                ),
+                (true, String::from("ok: not a wildcard import")),
+            ]
+        );
+        assert!(outcomes("app.core.engine").iter().all(|(pass, _)| *pass));
+    }
+}
@@ -0,0 +1,75 @@
+//! State of one check run, shared by every thread checking its files. Each entry point
+//! creates its own context from the (plain data) `RunConfig`, so runs never share state.
+
+use std::io::Write;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+use crate::configs::{DirOverrides, IgnoreList, ProjectConfig, RunConfig};
+use crate::debug_report::DebugSink;
+use crate::profile::PhaseTimings;
+
+pub struct RunContext {
+    pub config: RunConfig,
+    /// Hash of the configuration cache entries are valid for (see
+    /// `file_processor::config_hash`)
+    pub config_hash: String,
+    /// Set by any thread that failed to write the cache
+    pub cache_write_failed: AtomicBool,
+    /// Set once a file had issues, for `fail_fast`
+    pub issue_found: AtomicBool,
+    /// Writer behind `debug_report`
+    pub debug_sink: DebugSink,
+    /// Phase durations behind `profile`
+    pub timings: PhaseTimings,
+    /// Per-directory `.importee.toml` overrides discovered so far
+    pub overrides: DirOverrides,
+    /// The project's `ignore` entries, compiled once
+    pub ignores: IgnoreList,
+    /// Receives the number of files processed so far after each file
+    pub progress: Option<Sender<usize>>,
+    /// Where `ndjson_stream` writes issues (stdout unless replaced, e.g. by tests)
+    pub stream: Mutex<Box<dyn Write + Send>>,
+}
+
+impl RunContext {
+    /// A fresh run of `project_config`, starting its `profile` clock.
+    pub fn new(project_config: &ProjectConfig, config: RunConfig) -> Self {
+        let run = RunContext {
+            config_hash: crate::file_processor::config_hash(project_config, &config),
+            cache_write_failed: AtomicBool::new(false),
+            issue_found: AtomicBool::new(false),
+            debug_sink: DebugSink::default(),
+            timings: PhaseTimings::default(),
+            overrides: DirOverrides::default(),
+            ignores: IgnoreList::new(&project_config.ignore, config.verbose.unwrap_or(false)),
+            progress: None,
+            stream: Mutex::new(Box::new(std::io::stdout())),
+            config,
+        };
+        crate::profile::start(&run);
+        run
+    }
+
+    /// Report progress to `progress` after each file.
+    pub fn with_progress(mut self, progress: Sender<usize>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Whether `fail_fast` asks to check no further files.
+    pub fn stopped(&self) -> bool {
+        self.config.fail_fast.unwrap_or(false) && self.issue_found.load(Ordering::Relaxed)
+    }
+}
+
+/// Settings are read through the context, e.g. `run.verbose`.
+impl Deref for RunContext {
+    type Target = RunConfig;
+
+    fn deref(&self) -> &RunConfig {
+        &self.config
+    }
+}
@@ -0,0 +1,109 @@
+//! Helpers shared by unit tests.
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::configs::{ProjectConfig, RunConfig};
+use crate::graph::DependencyGraph;
+use crate::imports::import_line::ImportLine;
+use crate::module_path::ModulePath;
+use crate::run_context::RunContext;
+
+/// Serializes tests that change the process working directory.
+static CWD_LOCK: Mutex<()> = Mutex::new(());
+
+/// A temporary project directory that is the process cwd while it is alive.
+/// The walker resolves module paths relative to the cwd, so walker-level tests need one.
+pub struct TempProject {
+    dir: tempfile::TempDir,
+    previous_cwd: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TempProject {
+    pub fn new() -> Self {
+        let lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+        let previous_cwd = std::env::current_dir().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        TempProject {
+            dir,
+            previous_cwd,
+            _lock: lock,
+        }
+    }
+
+    /// Write a file (creating parent directories) relative to the project root.
+    pub fn write(&self, rel: &str, content: impl AsRef<[u8]>) {
+        let path = self.dir.path().join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Run git in the project root, committing as `Test Author`.
+    pub fn git(&self, args: &[&str]) {
+        let output = Command::new("git")
+            .args([
+                "-c",
+                "user.name=Test Author",
+                "-c",
+                "user.email=test@example.com",
+            ])
+            .args(args)
+            .current_dir(self.dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+}
+
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous_cwd);
+    }
+}
+
+/// A plain `import target` on line 1 of `from`; override other fields with
+/// `ImportLine { .., ..import_of(from, target) }`.
+pub fn import_of(from: &str, target: &str) -> ImportLine {
+    ImportLine {
+        from_module: ModulePath::from_dotted(from),
+        target_module: ModulePath::from_dotted(target),
+        import_line: 1,
+        ..Default::default()
+    }
+}
+
+/// Dependency graph with one import per `(from, to)` edge.
+pub fn graph_of(edges: &[(&str, &str)]) -> DependencyGraph {
+    let imports: Vec<ImportLine> = edges.iter().map(|(from, to)| import_of(from, to)).collect();
+    DependencyGraph::from_imports(&imports)
+}
+
+/// A run of an empty project with `config`, for calling file-level functions directly.
+pub fn run_of(config: RunConfig) -> RunContext {
+    RunContext::new(&ProjectConfig::default(), config)
+}
+
+/// Writer collecting what a run streams (see `RunContext::stream`), readable once it is over.
+#[derive(Clone, Default)]
+pub struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
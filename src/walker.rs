@@ -1,40 +1,281 @@
 use crate::configs::{ProjectConfig, RunConfig};
-use crate::imports::classification::ImportResolver;
+use crate::graph::DependencyGraph;
+use crate::imports::artifact::{Artifact, CollectedFile};
+use crate::imports::classification::{ImportResolver, ResolverCache};
+use crate::imports::import_line::ImportLine;
 use crate::module_path::ModulePath;
-use crate::results::{CheckResult, Issue};
-use crate::rules::ImportRule;
-use globset::{Glob, GlobSetBuilder};
+use crate::profile::{Phase, Timings};
+use crate::results::{CheckResult, Issue, Severity};
+use crate::rules::{ImportRule, ProjectRule};
+use crate::run_context::RunContext;
+use dashmap::DashSet;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use rayon::prelude::*;
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 
-pub fn run_check_imports(project_config: ProjectConfig, run_config: RunConfig) -> CheckResult {
-    let mut result = CheckResult::new();
+/// Compiled path glob patterns (`exclude`, `include`, `test_fixtures`).
+/// Directory matching also tries each pattern with a trailing `/**` stripped, so `tests/**`
+/// prunes the `tests` directory itself instead of visiting and rejecting every file in it.
+struct PathPatterns {
+    files: GlobSet,
+    dirs: GlobSet,
+}
+
+impl PathPatterns {
+    /// `kind` names the config option in warnings about invalid patterns.
+    fn build(patterns: &[String], kind: &str, verbose: bool) -> Option<Self> {
+        let mut files = GlobSetBuilder::new();
+        let mut dirs = GlobSetBuilder::new();
+        let mut any = false;
+        for pattern in patterns {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    files.add(glob.clone());
+                    dirs.add(glob);
+                    if let Some(dir_pattern) = pattern.strip_suffix("/**") {
+                        if let Ok(dir_glob) = Glob::new(dir_pattern) {
+                            dirs.add(dir_glob);
+                        }
+                    }
+                    any = true;
+                }
+                Err(e) => {
+                    if verbose {
+                        eprintln!("[core] invalid {} pattern '{}': {}", kind, pattern, e);
+                    }
+                }
+            }
+        }
+        if !any {
+            return None;
+        }
+        Some(PathPatterns {
+            files: files.build().ok()?,
+            dirs: dirs.build().ok()?,
+        })
+    }
+
+    /// Paths a module is matched under: relative to its source module root, and as walked
+    /// from the project root.
+    fn candidates(path: &ModulePath, source: &ModulePath, as_file: bool) -> Vec<PathBuf> {
+        let to_path = |mp: &ModulePath| {
+            if as_file {
+                mp.file_path()
+            } else {
+                mp.to_dir_pathbuf()
+            }
+        };
+        let mut out = vec![to_path(path)];
+        if let Some(rel) = path.relative_from(source) {
+            if !rel.is_empty() && !source.is_empty() {
+                out.push(to_path(&rel));
+            }
+        }
+        out
+    }
+
+    fn matches_dir(&self, path: &ModulePath, source: &ModulePath) -> bool {
+        Self::candidates(path, source, false)
+            .iter()
+            .any(|p| self.dirs.is_match(p))
+    }
+
+    fn matches_file(&self, path: &ModulePath, source: &ModulePath) -> bool {
+        Self::candidates(path, source, true)
+            .iter()
+            .any(|p| self.files.is_match(p))
+    }
+}
 
-    // Determine sources: use source_modules or fallback to cwd
-    let sources: Vec<ModulePath> = if !project_config.source_modules.is_empty() {
+/// Path-based filters applied while walking, compiled once per run.
+struct WalkFilters {
+    exclude: Option<PathPatterns>,
+    include: Option<PathPatterns>,
+    fixtures: Option<PathPatterns>,
+    /// Canonical directories walked so far, per source directory, so symlinks are followed
+    /// at most once and loops end
+    visited: DashSet<(PathBuf, PathBuf)>,
+    /// Whether a walk with rules skips subtrees and files no rule is concerned with; not
+    /// when project rules need the imports of every file
+    prune: bool,
+}
+
+impl WalkFilters {
+    fn new(project_config: &ProjectConfig, verbose: bool) -> Self {
+        WalkFilters {
+            exclude: PathPatterns::build(&project_config.exclude, "exclude", verbose),
+            include: PathPatterns::build(&project_config.include, "include", verbose),
+            fixtures: PathPatterns::build(&project_config.test_fixtures, "test_fixtures", verbose),
+            visited: DashSet::new(),
+            prune: true,
+        }
+    }
+
+    /// Record a directory walked from the source directory `source_dir`.
+    fn mark_visited(&self, dir: &Path, source_dir: &Path) {
+        if let Ok(canonical) = fs::canonicalize(dir) {
+            self.visited.insert((source_dir.to_path_buf(), canonical));
+        }
+    }
+
+    /// Whether a symlinked directory is walked: not when it points inside the source (which
+    /// is walked anyway, and may be one of its parents) or to a directory already walked.
+    fn enters_symlink(&self, dir: &Path, source_dir: &Path) -> bool {
+        let canonical = match fs::canonicalize(dir) {
+            Ok(canonical) => canonical,
+            Err(_) => return false,
+        };
+        let inside_source =
+            fs::canonicalize(source_dir).is_ok_and(|root| canonical.starts_with(root));
+        !inside_source && self.visited.insert((source_dir.to_path_buf(), canonical))
+    }
+
+    fn excludes_dir(&self, path: &ModulePath, source: &ModulePath) -> bool {
+        self.exclude
+            .as_ref()
+            .is_some_and(|patterns| patterns.matches_dir(path, source))
+    }
+
+    fn excludes_file(&self, path: &ModulePath, source: &ModulePath) -> bool {
+        self.exclude
+            .as_ref()
+            .is_some_and(|patterns| patterns.matches_file(path, source))
+    }
+
+    /// Whether a file passes the `include` allowlist; everything does without one. Only
+    /// files are matched, so directories are always walked.
+    fn includes_file(&self, path: &ModulePath, source: &ModulePath) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|patterns| patterns.matches_file(path, source))
+    }
+
+    /// Test fixtures (e.g. `conftest.py`) are only checked by rules that opt in.
+    fn rules_for_file<'a>(
+        &self,
+        path: &ModulePath,
+        source: &ModulePath,
+        rules: &[&'a dyn ImportRule],
+    ) -> Vec<&'a dyn ImportRule> {
+        let is_fixture = self
+            .fixtures
+            .as_ref()
+            .is_some_and(|patterns| patterns.matches_file(path, source));
+        rules
+            .iter()
+            .copied()
+            .filter(|rule| !is_fixture || rule.applies_to_fixtures())
+            .collect()
+    }
+}
+
+/// The configured source module owning `module`: the longest matching prefix.
+fn owning_source(project_config: &ProjectConfig, module: &ModulePath) -> Option<ModulePath> {
+    source_modules(project_config)
+        .into_iter()
+        .filter(|source| module.starts_with(source))
+        .max_by_key(|source| source.segments().len())
+}
+
+/// Configured source modules, or the cwd root when none are configured.
+pub fn source_modules(project_config: &ProjectConfig) -> Vec<ModulePath> {
+    if !project_config.source_modules.is_empty() {
         project_config.source_modules.clone()
     } else {
         vec![ModulePath::new(vec![])] // empty path represents cwd root
+    }
+}
+
+/// Build the import resolver rooted at a source module; filesystem lookups go through the
+/// run-wide `cache`. Each of the project's `extra_source_roots` backs the root module with
+/// `<extra root>/<root module>`.
+pub fn resolver_for_source(
+    module_path: &ModulePath,
+    project_config: &ProjectConfig,
+    run_config: &RunConfig,
+    cache: &ResolverCache,
+) -> ImportResolver {
+    let base = cache.layout_base(module_path, run_config.src_layout);
+    let is_dir = base.join(module_path.to_dir_pathbuf()).is_dir();
+    // The whole dotted path of a package source, e.g. `company.product.core`; a module
+    // source stands for its package
+    let root_module = match module_path.split_last() {
+        Some((_, parent)) if !is_dir && !parent.is_empty() => parent,
+        _ => module_path.clone(),
     };
+    let extra_roots: Vec<PathBuf> = project_config
+        .extra_source_roots
+        .iter()
+        .map(|extra| Path::new(extra).join(root_module.to_dir_pathbuf()))
+        .collect();
+    let root_dir = if is_dir {
+        module_path.to_dir_pathbuf()
+    } else {
+        module_path
+            .file_path()
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .to_path_buf()
+    };
+    ImportResolver::with_shared_cache(
+        root_dir,
+        Some(root_module),
+        run_config.verbose.unwrap_or(false),
+        cache,
+    )
+    .with_namespace_packages(run_config.namespace_packages.unwrap_or(false))
+    .with_resolve_reexports(run_config.resolve_reexports.unwrap_or(false))
+    .with_extensions(run_config.module_extensions())
+    .with_src_layout(run_config.src_layout)
+    .with_extra_roots(extra_roots)
+}
 
-    // OPTIMIZATION: Build rules once at the top level instead of per-file
-    let rules = crate::rules::build_rules(&project_config, &run_config);
+pub fn run_check_imports(project_config: ProjectConfig, run_config: RunConfig) -> CheckResult {
+    let run_config = RunContext::new(&project_config, run_config);
+    run_check_imports_in(project_config, &run_config)
+}
 
-    // Build exclusion GlobSet from exclude patterns
-    let mut exclude_builder = GlobSetBuilder::new();
-    for pattern in &project_config.exclude {
-        match Glob::new(pattern) {
-            Ok(glob) => {
-                exclude_builder.add(glob);
-            }
+/// Like `run_check_imports`, in a run context set up by the caller (e.g. with a progress
+/// listener).
+pub fn run_check_imports_in(project_config: ProjectConfig, run_config: &RunContext) -> CheckResult {
+    if let Some(jobs) = run_config.jobs {
+        match rayon::ThreadPoolBuilder::new().num_threads(jobs).build() {
+            Ok(pool) => return pool.install(|| check_imports(project_config, run_config)),
             Err(e) => {
                 if run_config.verbose.unwrap_or(false) {
-                    eprintln!("[core] invalid exclude pattern '{}': {}", pattern, e);
+                    println!(
+                        "[core] could not start {} threads ({}), using the global pool",
+                        jobs, e
+                    );
                 }
             }
         }
     }
-    let exclude_set = exclude_builder.build().ok();
+    check_imports(project_config, run_config)
+}
+
+/// Body of `run_check_imports`, on the current rayon pool.
+fn check_imports(project_config: ProjectConfig, run_config: &RunContext) -> CheckResult {
+    let mut result = CheckResult::new();
+
+    let sources = source_modules(&project_config);
+
+    // OPTIMIZATION: Build rules once at the top level instead of per-file
+    let rules = crate::rules::build_rules(&project_config, run_config);
+    let project_rules = crate::rules::build_project_rules(&project_config, run_config);
+
+    // Compile exclude and fixture patterns once for the whole run
+    let mut filters = WalkFilters::new(&project_config, run_config.verbose.unwrap_or(false));
+    filters.prune = project_rules.is_empty();
+    let cache = ResolverCache::new();
+    let files_scanned = AtomicUsize::new(0);
+    let streamed_errors = AtomicUsize::new(0);
 
     // Print active rules once if verbose
     if run_config.verbose.unwrap_or(false) {
@@ -42,108 +283,944 @@ pub fn run_check_imports(project_config: ProjectConfig, run_config: RunConfig) -
         for rule in rules.iter() {
             println!("  - {}: {}", rule.name(), rule.describe());
         }
+        for rule in project_rules.iter() {
+            println!("  - {}: {}", rule.name(), rule.describe());
+        }
         if !project_config.exclude.is_empty() {
             println!("[core] exclude patterns: {:?}", project_config.exclude);
         }
     }
 
-    // Walk each source in parallel
-    let all_issues: Vec<Issue> = sources
-        .par_iter()
-        .flat_map(|module_path| {
-            if run_config.verbose.unwrap_or(false) {
-                println!(
-                    "[core] walking {} ({})",
-                    module_path.to_dotted(),
-                    module_path.to_dir_pathbuf().to_string_lossy()
-                );
+    // With `changed_since`, only check the files git reports as changed
+    let changed = run_config.changed_since.as_deref().and_then(|git_ref| {
+        let files = changed_files(git_ref, &run_config.module_extensions());
+        if files.is_none() && run_config.verbose.unwrap_or(false) {
+            println!(
+                "[core] git diff against '{}' failed, checking every file",
+                git_ref
+            );
+        }
+        files
+    });
+    let (checked, walked_imports) = match changed {
+        Some(files) => {
+            let checked: Vec<(String, Vec<Issue>)> = files
+                .par_iter()
+                .filter_map(|file| {
+                    if run_config.stopped() {
+                        return None;
+                    }
+                    let (file, issues) = check_listed_file(
+                        &project_config,
+                        run_config,
+                        &rules,
+                        &filters,
+                        &cache,
+                        file,
+                        &files_scanned,
+                    )?;
+                    Some((file, stream_issues(issues, run_config, &streamed_errors)))
+                })
+                .collect();
+            (checked, None)
+        }
+        // Otherwise walk each source in parallel, keeping the imports project rules need
+        None => {
+            let extensions = run_config.module_extensions();
+            let walked: Vec<(String, Vec<Issue>, Vec<ImportLine>)> = sources
+                .par_iter()
+                .flat_map(|module_path| {
+                    let resolver =
+                        resolver_for_source(module_path, &project_config, run_config, &cache);
+                    let base = cache.layout_base(module_path, run_config.src_layout);
+                    if run_config.verbose.unwrap_or(false) {
+                        println!(
+                            "[core] walking {} ({})",
+                            module_path.to_dotted(),
+                            base.join(module_path.to_dir_pathbuf()).to_string_lossy()
+                        );
+                    }
+                    walk_path_parallel(
+                        module_path,
+                        WalkRoot {
+                            source: module_path,
+                            base: &base,
+                        },
+                        run_config,
+                        Some(&rules),
+                        &filters,
+                        &files_scanned,
+                        &|file, relevant_rules: &[&dyn ImportRule]| {
+                            let (issues, imports) =
+                                crate::file_processor::process_file_with_imports(
+                                    file,
+                                    run_config,
+                                    &resolver,
+                                    relevant_rules,
+                                );
+                            let imports = if project_rules.is_empty() {
+                                Vec::new()
+                            } else {
+                                imports
+                            };
+                            let path = file.source_file(&base, &extensions);
+                            vec![(
+                                path.to_string_lossy().to_string(),
+                                stream_issues(issues, run_config, &streamed_errors),
+                                imports,
+                            )]
+                        },
+                    )
+                })
+                .collect();
+            let mut checked = Vec::new();
+            let mut imports = Vec::new();
+            for (file, file_issues, file_imports) in walked {
+                checked.push((file, file_issues));
+                imports.extend(file_imports);
+            }
+            (checked, Some(imports))
+        }
+    };
+
+    for (file, issues) in checked {
+        result.files.push(file);
+        result.issues.extend(issues);
+    }
+    result
+        .issues
+        .extend(missing_source_issues(&sources, run_config, &cache));
+    if run_config.strict_config.unwrap_or(false) {
+        result.issues.extend(config_issues(&project_config));
+    }
+
+    // Project rules need every import, not just those of files the line rules look at: the
+    // walk above visits every file for them, while checking changed files does not
+    if !project_rules.is_empty() && !run_config.stopped() {
+        let imports = walked_imports.unwrap_or_else(|| {
+            collect_imports(&project_config, run_config, &filters, &cache).imports
+        });
+        result
+            .issues
+            .extend(crate::profile::time(run_config, Phase::Rules, || {
+                evaluate_project_rules(&project_rules, &imports, &|module| {
+                    module_file(module, run_config, &cache)
+                })
+            }));
+    }
+
+    let remaining = std::mem::take(&mut result.issues);
+    result.issues = stream_issues(remaining, run_config, &streamed_errors);
+    let mut result = finish_result(result, run_config, files_scanned.into_inner());
+    let streamed_errors = streamed_errors.into_inner();
+    if streamed_errors > 0 {
+        result.error_count = streamed_errors;
+        result.ok = false;
+    }
+    result
+}
+
+/// With `ndjson_stream`, write `issues` to the run's stream (stdout by default) under its
+/// lock, so lines of files checked concurrently don't interleave, and return none;
+/// `streamed_errors` keeps the result's `ok` and `error_count` meaningful. Returns `issues`
+/// unchanged otherwise.
+fn stream_issues(
+    issues: Vec<Issue>,
+    run_config: &RunContext,
+    streamed_errors: &AtomicUsize,
+) -> Vec<Issue> {
+    if !run_config.ndjson_stream.unwrap_or(false) || issues.is_empty() {
+        return issues;
+    }
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    streamed_errors.fetch_add(errors, Ordering::Relaxed);
+    // A closed pipe (e.g. `| head`) just drops the rest of the stream
+    let mut out = run_config.stream.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = write_ndjson(&mut *out, issues, run_config);
+    Vec::new()
+}
+
+/// One JSON object per issue and line, with paths as `report_path` gives them.
+fn write_ndjson(
+    out: &mut impl Write,
+    issues: Vec<Issue>,
+    run_config: &RunConfig,
+) -> std::io::Result<()> {
+    for mut issue in issues {
+        issue.path = report_path(&issue.path, run_config);
+        serde_json::to_writer(&mut *out, &issue)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
+/// `Config` issues for source modules with neither a directory nor a module file, which
+/// would otherwise yield no issues and look like a clean run. Reported even without
+/// `strict_config`.
+fn missing_source_issues(
+    sources: &[ModulePath],
+    run_config: &RunContext,
+    cache: &ResolverCache,
+) -> Vec<Issue> {
+    let extensions = run_config.module_extensions();
+    sources
+        .iter()
+        .filter(|source| !source.is_empty())
+        .filter_map(|source| {
+            let base = cache.layout_base(source, run_config.src_layout);
+            let dir = base.join(source.to_dir_pathbuf());
+            let found = dir.is_dir()
+                || extensions.iter().any(|extension| {
+                    base.join(source.file_path_with_extension(extension))
+                        .is_file()
+                });
+            if found {
+                return None;
             }
+            Some(Issue {
+                rule_name: String::from("Config"),
+                severity: Severity::Error,
+                path: dir.to_string_lossy().to_string(),
+                line: 0,
+                column: 0,
+                message: format!(
+                    "source module '{}' not found (looked for {} or {})",
+                    source.to_dotted(),
+                    dir.to_string_lossy(),
+                    base.join(source.file_path()).to_string_lossy()
+                ),
+                author: None,
+                commit: None,
+                fix: None,
+            })
+        })
+        .collect()
+}
 
-            let root_module = module_path.segments().first().cloned();
-            let root_dir = if module_path.to_dir_pathbuf().is_dir() {
-                module_path.to_dir_pathbuf()
-            } else {
-                module_path
-                    .file_path()
-                    .parent()
-                    .unwrap_or_else(|| std::path::Path::new("."))
-                    .to_path_buf()
+/// `strict_config` issues for the project's misconfigured rules.
+fn config_issues(project_config: &ProjectConfig) -> Vec<Issue> {
+    crate::rules::config_problems(project_config)
+        .into_iter()
+        .map(|problem| Issue {
+            rule_name: String::from("Config"),
+            severity: Severity::Error,
+            path: problem.path.to_string_lossy().to_string(),
+            line: 0,
+            column: 0,
+            message: problem.message,
+            author: None,
+            commit: None,
+            fix: None,
+        })
+        .collect()
+}
+
+/// Run-level post-processing shared by every check entry point: blame annotations,
+/// summary, directory budgets and diagnostics.
+fn finish_result(
+    mut result: CheckResult,
+    run_config: &RunContext,
+    files_scanned: usize,
+) -> CheckResult {
+    // Rayon yields files in arbitrary order; sort so output is diffable
+    sort_issues(&mut result.issues);
+    if run_config.blame.unwrap_or(false) {
+        crate::blame::annotate(&mut result.issues);
+    }
+    result.summarize(files_scanned);
+    let budgets = result.budget_diagnostics(&run_config.dir_budgets);
+    result.diagnostics.extend(budgets);
+    // Blame and budgets need the paths as walked
+    if run_config.relative_to.is_some() {
+        for issue in result.issues.iter_mut() {
+            issue.path = report_path(&issue.path, run_config);
+        }
+        for file in result.files.iter_mut() {
+            *file = report_path(file, run_config);
+        }
+    }
+    result.files.sort();
+    result
+        .diagnostics
+        .extend(crate::file_processor::cache_write_diagnostic(run_config));
+    result
+        .diagnostics
+        .extend(crate::debug_report::flush(run_config));
+    result.timings = crate::profile::snapshot(run_config);
+    result
+}
+
+/// `path` relative to the `relative_to` directory, when set and `path` is under it.
+pub fn report_path(path: &str, run_config: &RunConfig) -> String {
+    let Some(base) = run_config.relative_to.as_deref() else {
+        return path.to_string();
+    };
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let absolute = |path: &Path| cwd.join(path);
+    match absolute(Path::new(path)).strip_prefix(absolute(Path::new(base))) {
+        Ok(relative) => relative.to_string_lossy().to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+fn sort_issues(issues: &mut [Issue]) {
+    issues.sort_by(|a, b| {
+        (&a.path, a.line, &a.rule_name, a.column, &a.message).cmp(&(
+            &b.path,
+            b.line,
+            &b.rule_name,
+            b.column,
+            &b.message,
+        ))
+    });
+}
+
+/// File a module is reported on: its module file or, for a package, its `__init__` file,
+/// under the layout base of its top-level package.
+fn module_file(module: &ModulePath, run_config: &RunConfig, cache: &ResolverCache) -> PathBuf {
+    let base = cache.layout_base(module, run_config.src_layout);
+    let extensions = run_config.module_extensions();
+    if base.join(module.to_dir_pathbuf()).is_dir() {
+        module
+            .append(String::from("__init__"))
+            .source_file(&base, &extensions)
+    } else {
+        module.source_file(&base, &extensions)
+    }
+}
+
+/// Run project rules over the dependency graph of `imports`, reporting each violation on the
+/// first import statement creating the offending edge, in the file `locate` gives for the
+/// importing module.
+fn evaluate_project_rules(
+    rules: &[Box<dyn ProjectRule>],
+    imports: &[ImportLine],
+    locate: &dyn Fn(&ModulePath) -> PathBuf,
+) -> Vec<Issue> {
+    let graph = DependencyGraph::from_imports(imports);
+    let mut issues = Vec::new();
+    for rule in rules.iter() {
+        for violation in rule.check_graph(&graph) {
+            let render = |line: u32, default: String| match rule.message_template() {
+                Some(template) => crate::rules::render_message(
+                    template,
+                    &[
+                        ("rule", rule.name()),
+                        ("target", violation.to.as_deref().unwrap_or_default()),
+                        ("from", &violation.from),
+                        ("line", &line.to_string()),
+                        ("reason", &violation.reason),
+                    ],
+                ),
+                None => default,
             };
-            let resolver =
-                ImportResolver::new(root_dir, root_module, run_config.verbose.unwrap_or(false));
+            let from = ModulePath::from_dotted(&violation.from);
+            let Some(to) = violation.to.as_deref() else {
+                issues.push(Issue {
+                    rule_name: rule.name().to_string(),
+                    severity: rule.severity(),
+                    path: locate(&from).to_string_lossy().to_string(),
+                    line: 0,
+                    column: 0,
+                    message: render(0, violation.reason.clone()),
+                    author: None,
+                    commit: None,
+                    fix: None,
+                });
+                continue;
+            };
+            let import = imports
+                .iter()
+                .filter(|imp| {
+                    imp.from_module.to_dotted() == violation.from
+                        && imp.target_module.to_dotted() == to
+                })
+                .min_by_key(|imp| imp.import_line);
+            if import.is_some_and(|imp| imp.is_ignored(rule.name())) {
+                continue;
+            }
+            let (line, column) = import.map_or((0, 0), |imp| (imp.import_line, imp.column));
+            let path = locate(&from);
+            issues.push(Issue {
+                rule_name: rule.name().to_string(),
+                severity: rule.severity(),
+                path: path.to_string_lossy().to_string(),
+                line,
+                column,
+                message: render(line, format!("imported \"{}\" : {}", to, violation.reason)),
+                author: None,
+                commit: None,
+                fix: None,
+            });
+        }
+    }
+    issues
+}
 
+/// Check several independent projects (e.g. of a monorepo) in one run, each with its own
+/// sources, excludes, rules and resolvers, and merge their results. With a shared
+/// `cache_dir`, each project caches under its own `project-<index>` subdirectory.
+pub fn run_check_projects(projects: Vec<ProjectConfig>, run_config: RunConfig) -> CheckResult {
+    check_projects(projects, run_config, &RunContext::new)
+}
+
+/// Body of `run_check_projects`, checking each project in the run context `context` creates.
+fn check_projects(
+    projects: Vec<ProjectConfig>,
+    run_config: RunConfig,
+    context: &dyn Fn(&ProjectConfig, RunConfig) -> RunContext,
+) -> CheckResult {
+    let started = Instant::now();
+    let mut merged = CheckResult::new();
+    let mut files_scanned = 0;
+    let mut error_count = 0;
+    let mut ok = true;
+    let mut timings: Option<Timings> = None;
+    for (index, project_config) in projects.into_iter().enumerate() {
+        let mut project_run = run_config.clone();
+        if let Some(dir) = run_config.cache_dir.as_deref() {
+            let dir = Path::new(dir).join(format!("project-{}", index));
+            project_run.cache_dir = Some(dir.to_string_lossy().to_string());
+        }
+        let project_run = context(&project_config, project_run);
+        let result = run_check_imports_in(project_config, &project_run);
+        files_scanned += result.summary.files_scanned;
+        // Streamed issues are not returned, only counted
+        error_count += result.error_count;
+        ok &= result.ok;
+        if let Some(project_timings) = &result.timings {
+            timings
+                .get_or_insert_with(Timings::default)
+                .add_phases(project_timings);
+        }
+        merged.issues.extend(result.issues);
+        merged.files.extend(result.files);
+        for diagnostic in result.diagnostics {
+            // Run-level messages such as the cache warning would repeat once per project
+            if !merged.diagnostics.contains(&diagnostic) {
+                merged.diagnostics.push(diagnostic);
+            }
+        }
+    }
+    sort_issues(&mut merged.issues);
+    merged.files.sort();
+    merged.summarize(files_scanned);
+    merged.error_count = error_count;
+    merged.ok = ok;
+    merged.timings = timings.map(|timings| Timings {
+        total_ms: started.elapsed().as_secs_f64() * 1e3,
+        ..timings
+    });
+    merged
+}
+
+/// Check a single file, e.g. on save in an editor, without walking the tree.
+/// The file is attributed to the configured source module whose path is its longest prefix;
+/// non-`.py` files, excluded files and files outside every source yield no issues.
+/// Project rules need the whole dependency graph and are not evaluated here.
+pub fn run_check_file(
+    project_config: ProjectConfig,
+    run_config: RunConfig,
+    file_path: &Path,
+) -> CheckResult {
+    let mut result = CheckResult::new();
+    let run_config = RunContext::new(&project_config, run_config);
+    let rules = crate::rules::build_rules(&project_config, &run_config);
+    let filters = WalkFilters::new(&project_config, run_config.verbose.unwrap_or(false));
+    let files_scanned = AtomicUsize::new(0);
+    if let Some((file, issues)) = check_listed_file(
+        &project_config,
+        &run_config,
+        &rules,
+        &filters,
+        &ResolverCache::new(),
+        file_path,
+        &files_scanned,
+    ) {
+        result.files.push(file);
+        result.issues.extend(issues);
+    }
+    finish_result(result, &run_config, files_scanned.into_inner())
+}
+
+/// Check the source text of a module, e.g. an unsaved editor buffer, as if it were the
+/// module's file; like `run_check_file` otherwise. The module's file is neither read nor
+/// needed, while its imports still resolve against the files on disk.
+pub fn run_check_source(
+    project_config: ProjectConfig,
+    run_config: RunConfig,
+    module_path: &ModulePath,
+    source: &str,
+) -> CheckResult {
+    let mut result = CheckResult::new();
+    let run_config = RunContext::new(&project_config, run_config);
+    let rules = crate::rules::build_rules(&project_config, &run_config);
+    let filters = WalkFilters::new(&project_config, run_config.verbose.unwrap_or(false));
+    let checked = check_module(
+        &project_config,
+        &run_config,
+        &rules,
+        &filters,
+        &ResolverCache::new(),
+        module_path,
+        Some(source),
+    );
+    let files_scanned = usize::from(checked.is_some());
+    result.issues.extend(checked.unwrap_or_default());
+    finish_result(result, &run_config, files_scanned)
+}
+
+/// Check one file given by path rather than found by walking (see `run_check_file`).
+fn check_listed_file(
+    project_config: &ProjectConfig,
+    run_config: &RunContext,
+    rules: &[Box<dyn ImportRule>],
+    filters: &WalkFilters,
+    cache: &ResolverCache,
+    file_path: &Path,
+    files_scanned: &AtomicUsize,
+) -> Option<(String, Vec<Issue>)> {
+    let stem = module_stem(file_path, &run_config.module_extensions())?;
+
+    // Module paths are cwd-relative, so strip the cwd from absolute paths
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let rel_path = file_path.strip_prefix(&cwd).unwrap_or(file_path);
+    let module_path = ModulePath::from_file_path(&rel_path.with_file_name(stem))?;
+
+    let issues = check_module(
+        project_config,
+        run_config,
+        rules,
+        filters,
+        cache,
+        &module_path,
+        None,
+    )?;
+    file_done(files_scanned, run_config);
+    Some((rel_path.to_string_lossy().to_string(), issues))
+}
+
+/// Check one module outside a walk, from its file or from `unsaved` source text. `None`
+/// for modules outside every source and excluded modules, which are not checked.
+fn check_module(
+    project_config: &ProjectConfig,
+    run_config: &RunContext,
+    rules: &[Box<dyn ImportRule>],
+    filters: &WalkFilters,
+    cache: &ResolverCache,
+    module_path: &ModulePath,
+    unsaved: Option<&str>,
+) -> Option<Vec<Issue>> {
+    let source = owning_source(project_config, module_path)?;
+
+    let verbose = run_config.verbose.unwrap_or(false);
+    if filters.excludes_file(module_path, &source) || !filters.includes_file(module_path, &source) {
+        return None;
+    }
+
+    let concerned: Vec<&dyn ImportRule> = rules
+        .iter()
+        .map(|rule| rule.as_ref())
+        .filter(|rule| rule.check_concern(module_path, verbose))
+        .collect();
+    let relevant_rules = filters.rules_for_file(module_path, &source, &concerned);
+    let resolver = resolver_for_source(&source, project_config, run_config, cache);
+    Some(match unsaved {
+        Some(text) => crate::file_processor::process_source_with_rules(
+            module_path,
+            text,
+            run_config,
+            &resolver,
+            &relevant_rules,
+        ),
+        None => crate::file_processor::process_file_with_rules(
+            module_path,
+            run_config,
+            &resolver,
+            &relevant_rules,
+        ),
+    })
+}
+
+/// Count a processed file, and tell the run's progress listener, if any.
+fn file_done(files_scanned: &AtomicUsize, run_config: &RunContext) {
+    let done = files_scanned.fetch_add(1, Ordering::Relaxed) + 1;
+    if let Some(progress) = &run_config.progress {
+        // The listener may have gone away; the check goes on regardless
+        let _ = progress.send(done);
+    }
+}
+
+/// Module name of a file holding a module to check: its name without the longest matching
+/// module extension (so `py.jinja` beats `jinja`). None for other files, and for files
+/// shadowed by one with an earlier extension, such as `.pyi` stubs next to a `.py` file.
+fn module_stem(path: &Path, extensions: &[String]) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let (extension, stem) = extensions
+        .iter()
+        .filter_map(|extension| {
+            let stem = name.strip_suffix(extension.as_str())?.strip_suffix('.')?;
+            Some((extension, stem))
+        })
+        .filter(|(_, stem)| !stem.is_empty())
+        .max_by_key(|(extension, _)| extension.len())?;
+    let shadowed = extensions
+        .iter()
+        .take_while(|earlier| *earlier != extension)
+        .any(|earlier| {
+            path.with_file_name(format!("{}.{}", stem, earlier))
+                .is_file()
+        });
+    if shadowed {
+        None
+    } else {
+        Some(stem.to_string())
+    }
+}
+
+/// Module files (see `module_stem`) changed relative to `git_ref` (committed, staged or not)
+/// or new and untracked (but not ignored), as paths relative to the cwd. None when git is
+/// unavailable or the ref is unknown.
+fn changed_files(git_ref: &str, extensions: &[String]) -> Option<Vec<PathBuf>> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git").args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let changed = git(&["diff", "--name-only", "--relative", git_ref, "--"])?;
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+    let mut files: Vec<PathBuf> = changed
+        .lines()
+        .chain(untracked.lines())
+        .map(PathBuf::from)
+        // Deleted files show up in the diff too
+        .filter(|path| path.is_file())
+        .filter(|path| module_stem(path, extensions).is_some())
+        .collect();
+    files.sort();
+    files.dedup();
+    Some(files)
+}
+
+/// Files and imports collected by `run_collect_imports`.
+pub struct CollectedImports {
+    pub files: Vec<CollectedFile>,
+    pub imports: Vec<ImportLine>,
+    /// Run-level messages, as in `CheckResult::diagnostics`
+    pub diagnostics: Vec<String>,
+}
+
+/// Walk every source and collect the imports of all files, without evaluating any rule.
+/// Rules don't prune the walk here, so the result can be evaluated against any config.
+pub fn run_collect_imports(
+    project_config: &ProjectConfig,
+    run_config: &RunConfig,
+) -> CollectedImports {
+    let run_config = &RunContext::new(project_config, run_config.clone());
+    let filters = WalkFilters::new(project_config, run_config.verbose.unwrap_or(false));
+    let Artifact { files, imports } =
+        collect_imports(project_config, run_config, &filters, &ResolverCache::new());
+    CollectedImports {
+        files,
+        imports,
+        diagnostics: crate::file_processor::cache_write_diagnostic(run_config)
+            .into_iter()
+            .chain(crate::debug_report::flush(run_config))
+            .collect(),
+    }
+}
+
+fn collect_imports(
+    project_config: &ProjectConfig,
+    run_config: &RunContext,
+    filters: &WalkFilters,
+    cache: &ResolverCache,
+) -> Artifact {
+    let files_scanned = AtomicUsize::new(0);
+    let extensions = run_config.module_extensions();
+    let collected: Vec<(CollectedFile, Vec<ImportLine>)> = source_modules(project_config)
+        .par_iter()
+        .flat_map(|module_path| {
+            let resolver = resolver_for_source(module_path, project_config, run_config, cache);
+            let base = cache.layout_base(module_path, run_config.src_layout);
+            walk_path_parallel(
+                module_path,
+                WalkRoot {
+                    source: module_path,
+                    base: &base,
+                },
+                run_config,
+                None,
+                filters,
+                &files_scanned,
+                &|file, _: &[&dyn ImportRule]| {
+                    let imports =
+                        crate::file_processor::load_file_imports(file, run_config, &resolver)
+                            .unwrap_or_default();
+                    let path = file.source_file(&base, &extensions);
+                    let collected = CollectedFile {
+                        module: file.clone(),
+                        path: path.to_string_lossy().to_string(),
+                    };
+                    vec![(collected, imports)]
+                },
+            )
+        })
+        .collect();
+    let mut artifact = Artifact::default();
+    for (file, imports) in collected {
+        artifact.files.push(file);
+        artifact.imports.extend(imports);
+    }
+    artifact
+}
+
+/// Files a check would scan given the configured sources and exclude patterns, as sorted
+/// cwd-relative paths, without parsing any of them. Rules don't prune the walk here.
+pub fn run_list_files(project_config: &ProjectConfig, run_config: &RunConfig) -> Vec<String> {
+    let run_config = &RunContext::new(project_config, run_config.clone());
+    let filters = WalkFilters::new(project_config, run_config.verbose.unwrap_or(false));
+    let extensions = run_config.module_extensions();
+    let cache = ResolverCache::new();
+    let files_scanned = AtomicUsize::new(0);
+    let mut files: Vec<String> = source_modules(project_config)
+        .par_iter()
+        .flat_map(|module_path| {
+            let base = cache.layout_base(module_path, run_config.src_layout);
             walk_path_parallel(
                 module_path,
-                &run_config,
-                &resolver,
-                &rules,
-                exclude_set.as_ref(),
+                WalkRoot {
+                    source: module_path,
+                    base: &base,
+                },
+                run_config,
+                None,
+                &filters,
+                &files_scanned,
+                &|file, _: &[&dyn ImportRule]| {
+                    vec![file
+                        .source_file(&base, &extensions)
+                        .to_string_lossy()
+                        .to_string()]
+                },
             )
         })
         .collect();
+    files.sort();
+    files.dedup();
+    files
+}
 
-    result.issues.extend(all_issues);
+/// Evaluate rules over previously collected files and imports, without touching source files.
+pub fn run_evaluate_imports(
+    project_config: &ProjectConfig,
+    run_config: &RunConfig,
+    artifact: &Artifact,
+) -> CheckResult {
+    let mut result = CheckResult::new();
+    let verbose = run_config.verbose.unwrap_or(false);
+    let run_config = &RunContext::new(project_config, run_config.clone());
+    let rules = crate::rules::build_rules(project_config, run_config);
+    let filters = WalkFilters::new(project_config, verbose);
+    let mut files_scanned = 0;
+
+    // Group by importing module; files without imports still get their `finalize`
+    let mut by_module: BTreeMap<String, Vec<ImportLine>> = BTreeMap::new();
+    for imp in artifact.imports.iter() {
+        by_module
+            .entry(imp.from_module.to_dotted())
+            .or_default()
+            .push(imp.clone());
+    }
+    let mut files: Vec<&CollectedFile> = artifact.files.iter().collect();
+    // Sorted so evaluation order is deterministic
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    for collected in files {
+        let module_path = &collected.module;
+        let concerned: Vec<&dyn ImportRule> = rules
+            .iter()
+            .map(|rule| rule.as_ref())
+            .filter(|rule| rule.check_concern(module_path, verbose))
+            .collect();
+        let source = owning_source(project_config, module_path).unwrap_or_default();
+        let file = PathBuf::from(&collected.path);
+        let mut relevant_rules = filters.rules_for_file(module_path, &source, &concerned);
+        relevant_rules.retain(|rule| rule.applies_to_file(&file));
+        if relevant_rules.is_empty() {
+            continue;
+        }
+        files_scanned += 1;
+        result.files.push(collected.path.clone());
+        let imports = by_module
+            .get(&module_path.to_dotted())
+            .map_or(&[][..], |imports| imports.as_slice());
+        result
+            .issues
+            .extend(crate::file_processor::evaluate_imports(
+                module_path,
+                &file,
+                imports,
+                run_config,
+                &relevant_rules,
+            ));
+    }
+    // Modules are reported on their recorded file (a package on its `__init__` file)
+    let mut recorded: BTreeMap<String, &str> = BTreeMap::new();
+    for file in artifact.files.iter() {
+        let module = file.module.to_dotted();
+        if let Some(package) = module.strip_suffix(".__init__") {
+            recorded.insert(package.to_string(), &file.path);
+        }
+        recorded.insert(module, &file.path);
+    }
+    let cache = ResolverCache::new();
+    let locate = |module: &ModulePath| match recorded.get(&module.to_dotted()) {
+        Some(path) => PathBuf::from(path),
+        None => module_file(module, run_config, &cache),
+    };
+    let project_rules = crate::rules::build_project_rules(project_config, run_config);
     result
+        .issues
+        .extend(crate::profile::time(run_config, Phase::Rules, || {
+            evaluate_project_rules(&project_rules, &artifact.imports, &locate)
+        }));
+    finish_result(result, run_config, files_scanned)
+}
+
+/// Paths of a directory's entries. With `respect_gitignore`, entries ignored by the
+/// repository's `.gitignore` files (or `.git/info/exclude`) are left out.
+fn list_dir(dir: &Path, respect_gitignore: bool) -> std::io::Result<Vec<PathBuf>> {
+    if !respect_gitignore {
+        return fs::read_dir(dir)
+            .map(|read_dir| read_dir.flatten().map(|entry| entry.path()).collect());
+    }
+    // Only listing one level keeps the walk (and its rule pruning) below in charge
+    Ok(ignore::WalkBuilder::new(dir)
+        .max_depth(Some(1))
+        .hidden(false)
+        .ignore(false)
+        .build()
+        .flatten()
+        .filter(|entry| entry.depth() == 1)
+        .map(|entry| entry.into_path())
+        .collect())
 }
 
-/// Walk a path (file or directory) and process it in parallel
-/// Rules are filtered at each level based on check_concern to avoid unnecessary checks
-fn walk_path_parallel(
+/// A source module being walked, and the directory its module paths are relative to (`src`
+/// under the src layout, the cwd otherwise).
+#[derive(Clone, Copy)]
+struct WalkRoot<'a> {
+    /// Matched against path patterns along with the walked paths themselves
+    source: &'a ModulePath,
+    base: &'a Path,
+}
+
+/// Walk a path (file or directory) in parallel and call `visit` on every Python file.
+/// When `rules` is set they are filtered at each level based on check_concern, and subtrees
+/// no rule is concerned with are skipped; `visit` receives the rules relevant to the file.
+/// `root` is the configured source module the walk started from.
+/// `files_scanned` is incremented for every file passed to `visit`.
+fn walk_path_parallel<T, F>(
     path: &ModulePath,
-    run_config: &RunConfig,
-    resolver: &ImportResolver,
-    rules: &[Box<dyn ImportRule>],
-    exclude_set: Option<&globset::GlobSet>,
-) -> Vec<Issue> {
+    root: WalkRoot,
+    run_config: &RunContext,
+    rules: Option<&[Box<dyn ImportRule>]>,
+    filters: &WalkFilters,
+    files_scanned: &AtomicUsize,
+    visit: &F,
+) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&ModulePath, &[&dyn ImportRule]) -> Vec<T> + Sync,
+{
+    // Files already being checked finish; nothing new is started
+    if run_config.stopped() {
+        return Vec::new();
+    }
+    let WalkRoot { source, base } = root;
     let verbose = run_config.verbose.unwrap_or(false);
+    let extensions = run_config.module_extensions();
+    let target = base.join(path.to_dir_pathbuf());
+    let is_dir = target.is_dir();
 
     // Check if path matches exclusion patterns
-    if let Some(excludes) = exclude_set {
-        let file_path = path.file_path();
-        if excludes.is_match(&file_path) {
-            if verbose {
-                println!(
-                    "[walker] excluded {} (matches exclude pattern)",
-                    path.to_dotted()
-                );
-            }
-            return Vec::new();
+    let excluded = if is_dir {
+        filters.excludes_dir(path, source)
+    } else {
+        filters.excludes_file(path, source)
+    };
+    if excluded {
+        if verbose {
+            println!(
+                "[walker] excluded {} (matches exclude pattern)",
+                path.to_dotted()
+            );
         }
+        return Vec::new();
     }
 
     // OPTIMIZATION: Filter rules that are concerned with this path
-    let relevant_rules: Vec<&Box<dyn ImportRule>> = rules
+    let relevant_rules: Vec<&dyn ImportRule> = rules
+        .unwrap_or_default()
         .iter()
+        .map(|rule| rule.as_ref())
         .filter(|rule| rule.check_concern(path, verbose))
         .collect();
 
-    // OPTIMIZATION: If no rules apply to this path, skip entirely
-    if relevant_rules.is_empty() {
+    // OPTIMIZATION: If no rules apply to this path, skip entirely. Unresolved imports are
+    // reported for every file, so nothing is pruned when they are asked for (nor when
+    // `filters` keeps every file for project rules).
+    let prune = filters.prune && rules.is_some() && !run_config.report_unresolved.unwrap_or(false);
+    if prune && relevant_rules.is_empty() {
         if verbose {
             println!("[walker] skipping {} - no rules apply", path.to_dotted());
         }
         return Vec::new();
     }
 
-    let target = path.to_dir_pathbuf();
+    // Process a file with only the relevant rules
+    let visit_file = |file: &ModulePath| {
+        if run_config.stopped() {
+            return Vec::new();
+        }
+        let file_rules = filters.rules_for_file(file, source, &relevant_rules);
+        if prune && file_rules.is_empty() {
+            if verbose {
+                println!("[walker] skipping {} - no rules apply", file.to_dotted());
+            }
+            return Vec::new();
+        }
+        let out = visit(file, &file_rules);
+        file_done(files_scanned, run_config);
+        out
+    };
 
     // If it's a directory, walk it recursively
-    if target.is_dir() {
-        let entries = match fs::read_dir(&target) {
-            Ok(read_dir) => read_dir,
+    if is_dir {
+        let source_dir = base.join(source.to_dir_pathbuf());
+        filters.mark_visited(&target, &source_dir);
+        // Collect entries to process
+        let entries = crate::profile::time(run_config, Phase::Walk, || {
+            list_dir(&target, run_config.respect_gitignore.unwrap_or(false))
+        });
+        let entries = match entries {
+            Ok(entries) => entries,
             Err(_) => return Vec::new(),
         };
 
-        // Collect entries to process
-        let entries: Vec<_> = entries.flatten().collect();
-
         // Process all entries in parallel
         entries
             .par_iter()
-            .flat_map(|entry| {
-                let file_name_os = entry.file_name();
-                let file_name = file_name_os.to_string_lossy();
-                let entry_path = entry.path();
+            .flat_map(|entry_path| {
+                let file_name = entry_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default();
 
                 // Skip Python cache directories explicitly
                 if entry_path.is_dir() && file_name == "__pycache__" {
@@ -152,36 +1229,1228 @@ fn walk_path_parallel(
 
                 if entry_path.is_dir() {
                     let new_module_path = path.append(file_name.to_string());
-                    // Recursively walk subdirectory - rules will be filtered again
-                    walk_path_parallel(&new_module_path, run_config, resolver, rules, exclude_set)
-                } else if entry_path.is_file() {
-                    // Only process .py files; ignore .pyi, .pyc, .so, etc.
-                    if entry_path.extension().and_then(|e| e.to_str()) != Some("py") {
+                    let symlink = fs::symlink_metadata(entry_path)
+                        .is_ok_and(|meta| meta.file_type().is_symlink());
+                    if symlink
+                        && !(run_config.follow_symlinks.unwrap_or(false)
+                            && filters.enters_symlink(entry_path, &source_dir))
+                    {
+                        if verbose {
+                            println!(
+                                "[walker] skipping {} (symlinked directory)",
+                                new_module_path.to_dotted()
+                            );
+                        }
                         return Vec::new();
                     }
-                    // Append stem (module name without extension) to ModulePath
-                    let stem = match entry_path.file_stem().and_then(|s| s.to_str()) {
-                        Some(s) => s.to_string(),
-                        None => return Vec::new(),
-                    };
-                    let new_module_path = path.append(stem);
-
-                    // Process file with only the relevant rules
-                    crate::file_processor::process_file_with_rules(
+                    // Recursively walk subdirectory - rules will be filtered again
+                    walk_path_parallel(
                         &new_module_path,
+                        root,
                         run_config,
-                        resolver,
-                        &relevant_rules,
+                        rules,
+                        filters,
+                        files_scanned,
+                        visit,
                     )
+                } else if entry_path.is_file() {
+                    // Only process module files (.py, stubs when asked); ignore .pyc, .so, etc.
+                    // and append their stem (module name without extension) to ModulePath
+                    let stem = match module_stem(entry_path, &extensions) {
+                        Some(stem) => stem,
+                        None => return Vec::new(),
+                    };
+                    let new_module_path = path.append(stem);
+                    if filters.excludes_file(&new_module_path, source) {
+                        if verbose {
+                            println!(
+                                "[walker] excluded {} (matches exclude pattern)",
+                                new_module_path.to_dotted()
+                            );
+                        }
+                        return Vec::new();
+                    }
+                    if !filters.includes_file(&new_module_path, source) {
+                        if verbose {
+                            println!(
+                                "[walker] skipping {} (matches no include pattern)",
+                                new_module_path.to_dotted()
+                            );
+                        }
+                        return Vec::new();
+                    }
+                    visit_file(&new_module_path)
                 } else {
                     Vec::new()
                 }
             })
             .collect()
-    } else if target.is_file() || path.file_path().is_file() {
+    } else if target.is_file() || path.source_file(base, &extensions).is_file() {
         // It's a single file - process it directly with relevant rules
-        crate::file_processor::process_file_with_rules(path, run_config, resolver, &relevant_rules)
+        visit_file(path)
     } else {
         Vec::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_projects, run_check_imports, run_check_imports_in, run_check_projects,
+        run_check_source, run_list_files,
+    };
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::run_context::RunContext;
+    use crate::test_support::{Captured, TempProject};
+
+    /// `app/src/a.py` and `app/tests/t.py` both import the later `high` module.
+    fn layered_project() -> TempProject {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/high.py", "");
+        project.write("app/src/__init__.py", "");
+        project.write("app/src/a.py", "import app.high\n");
+        project.write("app/tests/__init__.py", "");
+        project.write("app/tests/t.py", "import app.high\n");
+        project
+    }
+
+    fn config(exclude: &[&str]) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": ["src", "tests", "high"]}]},
+            "exclude": exclude,
+        }))
+        .unwrap()
+    }
+
+    fn issue_paths(project: &ProjectConfig) -> Vec<String> {
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let mut paths: Vec<String> = run_check_imports(project.clone(), run)
+            .issues
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn summary_counts_scanned_files() {
+        let _project = layered_project();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let summary = run_check_imports(config(&["**/tests/**"]), run).summary;
+        assert_eq!(summary.total, 1);
+        assert_eq!(summary.by_rule["Linear"], 1);
+        // app/__init__, app/high and app/src/{__init__,a}; tests/ is excluded
+        assert_eq!(summary.files_scanned, 4);
+    }
+
+    #[test]
+    fn src_layout_sources_are_walked_under_src() {
+        let project = TempProject::new();
+        project.write("src/app/__init__.py", "");
+        project.write("src/app/high.py", "");
+        project.write("src/app/src/__init__.py", "");
+        project.write("src/app/src/a.py", "import app.high\n");
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let result = run_check_imports(config(&[]), run.clone());
+        let issues: Vec<String> = result
+            .issues
+            .iter()
+            .map(|issue| format!("{} {}", issue.rule_name, issue.path))
+            .collect();
+        assert_eq!(issues, ["Linear src/app/src/a.py"]);
+        assert_eq!(result.summary.files_scanned, 4);
+        assert_eq!(
+            run_list_files(&config(&[]), &run),
+            [
+                "src/app/__init__.py",
+                "src/app/high.py",
+                "src/app/src/__init__.py",
+                "src/app/src/a.py"
+            ]
+        );
+    }
+
+    #[test]
+    fn issues_are_reported_in_a_stable_order() {
+        let project = layered_project();
+        for i in 0..8 {
+            project.write(
+                &format!("app/src/m{}.py", i),
+                "import app.tests.t\nimport app.high\n",
+            );
+        }
+        let run = || {
+            let run = RunConfig {
+                no_cache: Some(true),
+                ..RunConfig::default()
+            };
+            serde_json::to_string(&run_check_imports(config(&[]), run)).unwrap()
+        };
+        let first = run();
+        assert_eq!(first, run());
+
+        let issues = run_check_imports(
+            config(&[]),
+            RunConfig {
+                no_cache: Some(true),
+                ..RunConfig::default()
+            },
+        )
+        .issues;
+        let keys: Vec<(&str, u32)> = issues
+            .iter()
+            .map(|issue| (issue.path.as_str(), issue.line))
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(keys, sorted);
+    }
+
+    #[test]
+    fn directory_override_relaxes_its_subtree() {
+        let project = layered_project();
+        project.write("app/tests/.importee.toml", "disable = [\"Linear\"]\n");
+        project.write("app/src/gen_a.py", "import app.high\n");
+        project.write("app/src/.importee.toml", "exclude = [\"gen_*.py\"]\n");
+        assert_eq!(issue_paths(&config(&[])), ["app/src/a.py"]);
+    }
+
+    #[test]
+    fn ignore_entries_drop_matching_issues() {
+        let _project = layered_project();
+        let with_ignore = |ignore: serde_json::Value| ProjectConfig {
+            ignore: serde_json::from_value(ignore).unwrap(),
+            ..config(&[])
+        };
+        assert_eq!(
+            issue_paths(&with_ignore(
+                serde_json::json!([{"path": "app/tests/*.py"}])
+            )),
+            ["app/src/a.py"]
+        );
+        let by_rule_and_target = with_ignore(serde_json::json!([
+            {"path": "**/a.py", "rule": "Linear", "target": "app.high"},
+            {"path": "app/tests/t.py", "rule": "Layered"},
+        ]));
+        assert_eq!(issue_paths(&by_rule_and_target), ["app/tests/t.py"]);
+    }
+
+    #[test]
+    fn files_in_a_declared_encoding_are_checked() {
+        let project = layered_project();
+        project.write(
+            "app/src/legacy.py",
+            b"# -*- coding: latin-1 -*-\n# r\xe9sum\xe9 of the old API\nimport app.high\n",
+        );
+        let issues = run_check_imports(
+            config(&["**/tests/**"]),
+            RunConfig {
+                no_cache: Some(true),
+                ..RunConfig::default()
+            },
+        )
+        .issues;
+        let legacy: Vec<_> = issues
+            .iter()
+            .filter(|issue| issue.path == "app/src/legacy.py")
+            .collect();
+        assert_eq!(legacy.len(), 1);
+        assert_eq!(legacy[0].line, 3);
+    }
+
+    #[test]
+    fn projects_are_checked_with_their_own_config_and_cache() {
+        use std::path::Path;
+
+        let project = TempProject::new();
+        for package in ["alpha", "beta"] {
+            project.write(&format!("{}/__init__.py", package), "");
+            project.write(&format!("{}/high.py", package), "");
+            project.write(
+                &format!("{}/low.py", package),
+                format!("import {}.high\n", package),
+            );
+        }
+        let project_for = |package: &str, order: &[&str]| -> ProjectConfig {
+            serde_json::from_value(serde_json::json!({
+                "source_modules": [package],
+                "rules": {"linear": [{"order": order, "source_module": package}]},
+            }))
+            .unwrap()
+        };
+        let run = RunConfig {
+            cache_dir: Some(String::from("cache")),
+            ..RunConfig::default()
+        };
+        let result = run_check_projects(
+            vec![
+                project_for("alpha", &["low", "high"]),
+                project_for("beta", &["high", "low"]),
+            ],
+            run,
+        );
+        let paths: Vec<&str> = result.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, ["alpha/low.py"]);
+        assert_eq!(result.summary.total, 1);
+        assert_eq!(result.summary.files_scanned, 6);
+        assert!(Path::new("cache/project-0/alpha/low.imports.json").is_file());
+        assert!(Path::new("cache/project-1/beta/low.imports.json").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_directories_are_walked_once() {
+        let project = layered_project();
+        project.write("shared/__init__.py", "");
+        project.write("shared/s.py", "import app.high\n");
+        let root = std::env::current_dir().unwrap();
+        // A loop back to the source, and a directory outside it linking back to itself
+        std::os::unix::fs::symlink(root.join("app"), root.join("app/src/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("shared"), root.join("shared/again")).unwrap();
+        std::os::unix::fs::symlink(root.join("shared"), root.join("app/src/shared")).unwrap();
+        let run = |follow_symlinks: Option<bool>| {
+            let run = RunConfig {
+                no_cache: Some(true),
+                follow_symlinks,
+                ..RunConfig::default()
+            };
+            let mut paths: Vec<String> = run_check_imports(config(&[]), run)
+                .issues
+                .into_iter()
+                .map(|issue| issue.path)
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(run(None), ["app/src/a.py", "app/tests/t.py"]);
+        assert_eq!(
+            run(Some(true)),
+            ["app/src/a.py", "app/src/shared/s.py", "app/tests/t.py"]
+        );
+    }
+
+    #[test]
+    fn include_patterns_restrict_checked_files() {
+        let _project = layered_project();
+        let include = |patterns: &[&str]| ProjectConfig {
+            include: patterns.iter().map(|p| p.to_string()).collect(),
+            ..config(&[])
+        };
+        assert_eq!(
+            issue_paths(&include(&["**/tests/*.py"])),
+            ["app/tests/t.py"]
+        );
+        // Excludes still win over includes
+        let both = ProjectConfig {
+            exclude: vec![String::from("**/tests/**")],
+            ..include(&["**/tests/*.py", "app/src/a.py"])
+        };
+        assert_eq!(issue_paths(&both), ["app/src/a.py"]);
+    }
+
+    #[test]
+    fn syntax_errors_are_reported_when_asked() {
+        let project = layered_project();
+        project.write(
+            "app/src/broken.py",
+            "import app.high\n\ndef f(:\n    pass\n",
+        );
+        let run = |report: Option<bool>| {
+            let run = RunConfig {
+                no_cache: Some(true),
+                report_syntax_errors: report,
+                ..RunConfig::default()
+            };
+            run_check_imports(config(&["**/tests/**"]), run).issues
+        };
+
+        let issues = run(Some(true));
+        let broken: Vec<_> = issues
+            .iter()
+            .filter(|issue| issue.path == "app/src/broken.py")
+            .collect();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].rule_name, "SyntaxError");
+        assert_eq!(broken[0].line, 3);
+        assert!(!broken[0].message.is_empty());
+
+        // Skipped silently by default
+        assert!(run(None)
+            .iter()
+            .all(|issue| issue.path != "app/src/broken.py"));
+    }
+
+    #[test]
+    fn exclude_nothing_checks_everything() {
+        let _project = layered_project();
+        assert_eq!(
+            issue_paths(&config(&[])),
+            ["app/src/a.py", "app/tests/t.py"]
+        );
+    }
+
+    #[test]
+    fn exclude_skips_matching_directory() {
+        let _project = layered_project();
+        // Relative to the source module root, and as walked from the project root
+        for pattern in ["tests/**", "app/tests/**", "**/tests"] {
+            assert_eq!(
+                issue_paths(&config(&[pattern])),
+                ["app/src/a.py"],
+                "{}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn artifact_roundtrip_evaluates_rules() {
+        use super::{run_collect_imports, run_evaluate_imports};
+        use crate::imports::artifact::{read_artifact, write_artifact, Artifact};
+
+        let _project = layered_project();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let collected = run_collect_imports(&config(&[]), &run);
+        assert_eq!(collected.imports.len(), 2);
+
+        let artifact = std::path::Path::new("imports.json");
+        let written = Artifact {
+            files: collected.files,
+            imports: collected.imports,
+        };
+        write_artifact(artifact, &written).unwrap();
+        // Sources are gone: evaluation must rely on the artifact alone
+        std::fs::remove_dir_all("app").unwrap();
+        let loaded = read_artifact(artifact).unwrap();
+        let result = run_evaluate_imports(&config(&[]), &run, &loaded);
+        let paths: Vec<&str> = result.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, ["app/src/a.py", "app/tests/t.py"]);
+    }
+
+    #[test]
+    fn artifact_evaluation_sees_files_without_imports() {
+        use super::{run_collect_imports, run_evaluate_imports};
+        use crate::imports::artifact::Artifact;
+
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/foo/__init__.py", "");
+        project.write("app/foo/api.py", "");
+        project.write("app/foo/impl/__init__.py", "");
+        project.write("app/foo/impl/good.py", "import app.foo.api\n");
+        project.write("app/foo/impl/empty.py", "");
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"interface_implementation": [{}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let live = run_check_imports(config.clone(), run.clone());
+        assert_eq!(live.issues.len(), 1);
+        assert_eq!(live.issues[0].path, "app/foo/impl/empty.py");
+
+        let collected = run_collect_imports(&config, &run);
+        let artifact = Artifact {
+            files: collected.files,
+            imports: collected.imports,
+        };
+        std::fs::remove_dir_all("app").unwrap();
+        let evaluated = run_evaluate_imports(&config, &run, &artifact);
+        let found = |result: &crate::results::CheckResult| {
+            result
+                .issues
+                .iter()
+                .map(|issue| (issue.path.clone(), issue.message.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found(&evaluated), found(&live));
+        assert_eq!(evaluated.summary, live.summary);
+    }
+
+    #[test]
+    fn artifact_rejects_other_versions() {
+        use crate::imports::artifact::{read_artifact, ArtifactError};
+
+        let _project = TempProject::new();
+        std::fs::write("imports.json", r#"{"version": 999, "imports": []}"#).unwrap();
+        assert!(matches!(
+            read_artifact(std::path::Path::new("imports.json")),
+            Err(ArtifactError::Version(999))
+        ));
+        // Imports a newer version can write are not parsed before the version check
+        std::fs::write(
+            "imports.json",
+            r#"{"version": 999, "imports": [{"kind": "FutureKind"}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            read_artifact(std::path::Path::new("imports.json")),
+            Err(ArtifactError::Version(999))
+        ));
+    }
+
+    #[test]
+    fn check_single_file() {
+        use super::run_check_file;
+        use std::path::Path;
+
+        let project = layered_project();
+        project.write("app/notes.txt", "import app.high\n");
+        project.write("other/x.py", "import app.high\n");
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let check = |path: &Path| run_check_file(config(&[]), run.clone(), path).issues;
+
+        let issues = check(Path::new("app/src/a.py"));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "app/src/a.py");
+        // Absolute paths are mapped back onto the source module
+        let absolute = std::env::current_dir().unwrap().join("app/tests/t.py");
+        assert_eq!(check(&absolute).len(), 1);
+        // Non-Python files and files outside every source module are ignored
+        assert!(check(Path::new("app/notes.txt")).is_empty());
+        assert!(check(Path::new("other/x.py")).is_empty());
+    }
+
+    #[test]
+    fn source_text_is_checked_in_place_of_the_file() {
+        use crate::module_path::ModulePath;
+        use std::path::Path;
+
+        let _project = layered_project();
+        let run = RunConfig {
+            cache_dir: Some(String::from("cache")),
+            ..RunConfig::default()
+        };
+        let check = |module: &str, source: &str| {
+            let module = ModulePath::from_dotted(module);
+            run_check_source(config(&[]), run.clone(), &module, source)
+        };
+
+        // The buffer's imports count, not the saved file's
+        assert!(check("app.src.a", "import os\n").issues.is_empty());
+        let unsaved = check("app.src.new", "\nimport app.high\n");
+        assert_eq!(unsaved.summary.files_scanned, 1);
+        assert_eq!(unsaved.issues.len(), 1);
+        assert_eq!(unsaved.issues[0].path, "app/src/new.py");
+        assert_eq!(unsaved.issues[0].line, 2);
+        assert!(!Path::new("cache").exists());
+        assert!(check("other.x", "import app.high\n").issues.is_empty());
+    }
+
+    #[test]
+    fn rules_only_check_files_matching_applies_to() {
+        let _project = layered_project();
+        let scoped = |applies_to: &[&str]| -> ProjectConfig {
+            serde_json::from_value(serde_json::json!({
+                "source_modules": ["app"],
+                "rules": {"linear": [
+                    {"order": ["src", "tests", "high"], "applies_to": applies_to}
+                ]},
+            }))
+            .unwrap()
+        };
+        assert_eq!(
+            issue_paths(&scoped(&[])),
+            ["app/src/a.py", "app/tests/t.py"]
+        );
+        assert_eq!(issue_paths(&scoped(&["app/tests/**"])), ["app/tests/t.py"]);
+        assert!(issue_paths(&scoped(&["other/**"])).is_empty());
+    }
+
+    #[test]
+    fn strict_config_reports_misconfigured_rules() {
+        let _project = layered_project();
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [
+                {"order": ["src", "tests", "hgih"]},
+                {"order": [], "source_module": "missing"},
+            ]},
+        }))
+        .unwrap();
+        let run = |strict_config| RunConfig {
+            no_cache: Some(true),
+            strict_config,
+            ..RunConfig::default()
+        };
+
+        let config_issues = |result: crate::results::CheckResult| -> Vec<(String, String)> {
+            result
+                .issues
+                .into_iter()
+                .filter(|issue| issue.rule_name == "Config")
+                .map(|issue| (issue.path, issue.message))
+                .collect()
+        };
+        assert_eq!(
+            config_issues(run_check_imports(config.clone(), run(Some(true)))),
+            [
+                (
+                    String::from("app"),
+                    String::from(
+                        "'hgih' not found under 'app' (looked for app/hgih or app/hgih.py)"
+                    )
+                ),
+                (
+                    String::from("missing"),
+                    String::from("linear rule on 'missing' has an empty order")
+                ),
+                (
+                    String::from("missing"),
+                    String::from("source module 'missing' directory not found at missing")
+                ),
+            ]
+        );
+        assert!(config_issues(run_check_imports(config, run(None))).is_empty());
+    }
+
+    #[test]
+    fn missing_source_modules_are_reported() {
+        let _project = layered_project();
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app", "ap"],
+            "rules": {"linear": [{"order": ["src", "tests"]}]},
+        }))
+        .unwrap();
+        let result = run_check_imports(
+            config,
+            RunConfig {
+                no_cache: Some(true),
+                ..RunConfig::default()
+            },
+        );
+
+        let config_issues: Vec<(String, String)> = result
+            .issues
+            .into_iter()
+            .filter(|issue| issue.rule_name == "Config")
+            .map(|issue| (issue.path, issue.message))
+            .collect();
+        assert_eq!(
+            config_issues,
+            [(
+                String::from("ap"),
+                String::from("source module 'ap' not found (looked for ap or ap.py)")
+            )]
+        );
+    }
+
+    #[test]
+    fn streamed_issues_are_written_instead_of_returned() {
+        let _project = layered_project();
+        let run = |ndjson_stream| RunConfig {
+            no_cache: Some(true),
+            ndjson_stream,
+            ..RunConfig::default()
+        };
+        let collected = run_check_imports(config(&[]), run(None));
+
+        let captured = Captured::default();
+        let project = config(&[]);
+        let mut context = RunContext::new(&project, run(Some(true)));
+        context.stream = std::sync::Mutex::new(Box::new(captured.clone()));
+        let streamed = run_check_imports_in(project, &context);
+        assert!(streamed.issues.is_empty());
+        assert_eq!(streamed.error_count, collected.error_count);
+        assert!(!streamed.ok);
+
+        let mut lines: Vec<serde_json::Value> = captured
+            .text()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        // Files are streamed as they are checked, in any order
+        lines.sort_by_key(|line| line["path"].as_str().unwrap().to_string());
+        let expected: Vec<serde_json::Value> = collected
+            .issues
+            .iter()
+            .map(|issue| serde_json::to_value(issue).unwrap())
+            .collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn streamed_projects_keep_their_error_count() {
+        let _project = layered_project();
+        let run = |ndjson_stream| RunConfig {
+            no_cache: Some(true),
+            ndjson_stream,
+            ..RunConfig::default()
+        };
+        let projects = || vec![config(&[]), config(&["src/a.py"])];
+        let collected = run_check_projects(projects(), run(None));
+        assert_eq!(collected.error_count, 3);
+
+        let captured = Captured::default();
+        let streamed = check_projects(projects(), run(Some(true)), &|project, run| {
+            let mut context = RunContext::new(project, run);
+            context.stream = std::sync::Mutex::new(Box::new(captured.clone()));
+            context
+        });
+        assert!(streamed.issues.is_empty());
+        assert_eq!(streamed.error_count, collected.error_count);
+        assert!(!streamed.ok);
+        assert_eq!(captured.text().lines().count(), 3);
+    }
+
+    #[test]
+    fn disabled_rules_are_not_run() {
+        let _project = layered_project();
+        let run = |disabled_rules: &[&str]| RunConfig {
+            no_cache: Some(true),
+            disabled_rules: Some(disabled_rules.iter().map(|s| s.to_string()).collect()),
+            ..RunConfig::default()
+        };
+        assert_eq!(
+            run_check_imports(config(&[]), run(&["Layered"]))
+                .issues
+                .len(),
+            2
+        );
+        assert!(run_check_imports(config(&[]), run(&["linear"]))
+            .issues
+            .is_empty());
+    }
+
+    #[test]
+    fn gitignored_files_are_skipped_when_asked() {
+        let project = layered_project();
+        project.write("app/src/build/__init__.py", "");
+        project.write("app/src/build/gen.py", "import app.high\n");
+        project.write("app/src/.gitignore", "/build/\n");
+        project.write(".gitignore", "t.py\n");
+        project.git(&["init", "-q"]);
+        let run = |respect_gitignore| RunConfig {
+            no_cache: Some(true),
+            respect_gitignore,
+            ..RunConfig::default()
+        };
+        let paths = |run_config| {
+            let mut paths: Vec<String> = run_check_imports(config(&[]), run_config)
+                .issues
+                .into_iter()
+                .map(|issue| issue.path)
+                .collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(
+            paths(run(None)),
+            ["app/src/a.py", "app/src/build/gen.py", "app/tests/t.py"]
+        );
+        assert_eq!(paths(run(Some(true))), ["app/src/a.py"]);
+    }
+
+    #[test]
+    fn bounded_thread_pool_finds_the_same_issues() {
+        let _project = layered_project();
+        let run = |jobs| RunConfig {
+            no_cache: Some(true),
+            jobs,
+            ..RunConfig::default()
+        };
+        let issues = |run_config| {
+            let mut issues: Vec<(String, u32, String)> = run_check_imports(config(&[]), run_config)
+                .issues
+                .into_iter()
+                .map(|issue| (issue.path, issue.line, issue.message))
+                .collect();
+            issues.sort();
+            issues
+        };
+        let default = issues(run(None));
+        assert_eq!(default.len(), 2);
+        assert_eq!(issues(run(Some(1))), default);
+    }
+
+    #[test]
+    fn issue_paths_are_reported_relative_to_the_base_dir() {
+        let _project = layered_project();
+        let cwd = std::env::current_dir().unwrap();
+        let paths = |relative_to: Option<String>| {
+            let run_config = RunConfig {
+                no_cache: Some(true),
+                relative_to,
+                ..RunConfig::default()
+            };
+            run_check_imports(config(&[]), run_config)
+                .issues
+                .into_iter()
+                .map(|issue| issue.path)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(paths(None), ["app/src/a.py", "app/tests/t.py"]);
+        assert_eq!(paths(Some(String::from("app"))), ["src/a.py", "tests/t.py"]);
+        let absolute = cwd.join("app/src").to_string_lossy().to_string();
+        assert_eq!(paths(Some(absolute)), ["a.py", "app/tests/t.py"]);
+    }
+
+    #[test]
+    fn checked_files_are_reported_like_issue_paths() {
+        let _project = layered_project();
+        let run_config = RunConfig {
+            no_cache: Some(true),
+            relative_to: Some(String::from("app")),
+            ..RunConfig::default()
+        };
+        let result = run_check_imports(config(&[]), run_config);
+        assert_eq!(result.files.len(), result.summary.files_scanned);
+        assert!(result.files.contains(&String::from("src/__init__.py")));
+        for issue in &result.issues {
+            assert!(result.files.contains(&issue.path));
+        }
+        // A JUnit report lists each file once, failing or not
+        let tests = format!("tests=\"{}\" failures=\"2\"", result.files.len());
+        assert!(result.to_junit_xml().contains(&tests));
+    }
+
+    #[test]
+    fn fail_fast_stops_after_the_first_file_with_issues() {
+        let _project = layered_project();
+        // On a single thread no other file is in flight when the first issue is found
+        let run = |fail_fast| RunConfig {
+            no_cache: Some(true),
+            jobs: Some(1),
+            fail_fast,
+            ..RunConfig::default()
+        };
+        assert_eq!(run_check_imports(config(&[]), run(None)).issues.len(), 2);
+        assert_eq!(
+            run_check_imports(config(&[]), run(Some(true))).issues.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn message_template_replaces_the_default_message() {
+        let _project = layered_project();
+        let mut project = config(&[]);
+        project.rules.linear[0].options.message_template = Some(String::from(
+            "{rule}: {from} -> {target} (line {line}) {unknown}",
+        ));
+        let run_config = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let messages: Vec<String> = run_check_imports(project, run_config)
+            .issues
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Linear: app.src.a -> app.high (line 1) {unknown}",
+                "Linear: app.tests.t -> app.high (line 1) {unknown}"
+            ]
+        );
+    }
+
+    #[test]
+    fn exclude_skips_matching_file() {
+        let _project = layered_project();
+        assert_eq!(issue_paths(&config(&["src/a.py"])), ["app/tests/t.py"]);
+    }
+
+    #[test]
+    fn unwritable_cache_warns_once() {
+        let project = layered_project();
+        // A path under a regular file can never be created, even as root
+        project.write("blocker", "");
+        let run = RunConfig {
+            cache_dir: Some(String::from("blocker/cache")),
+            warn_cache_write_failures: Some(true),
+            ..RunConfig::default()
+        };
+        let result = run_check_imports(config(&[]), run);
+        assert_eq!(result.issues.len(), 2);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert!(result.diagnostics[0].contains("could not write the import cache"));
+        // Collecting only returns it too
+        let run = RunConfig {
+            cache_dir: Some(String::from("blocker/cache")),
+            warn_cache_write_failures: Some(true),
+            ..RunConfig::default()
+        };
+        let collected = super::run_collect_imports(&config(&[]), &run);
+        assert_eq!(collected.diagnostics, result.diagnostics);
+
+        // Without the flag the run stays silent
+        let run = RunConfig {
+            cache_dir: Some(String::from("blocker/cache")),
+            ..RunConfig::default()
+        };
+        assert!(run_check_imports(config(&[]), run).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn conftest_skips_architecture_rules_but_not_forbidden() {
+        let project = layered_project();
+        project.write("app/src/conftest.py", "import app.high\n");
+        let rules = serde_json::json!({
+            "linear": [{"order": ["src", "tests", "high"]}],
+            "forbidden": [{"modules": ["app.high"]}],
+        });
+        let rule_paths = |project_config: serde_json::Value| {
+            let run = RunConfig {
+                no_cache: Some(true),
+                ..RunConfig::default()
+            };
+            let project_config: ProjectConfig = serde_json::from_value(project_config).unwrap();
+            let mut found: Vec<(String, String)> = run_check_imports(project_config, run)
+                .issues
+                .into_iter()
+                .filter(|issue| issue.path.ends_with("conftest.py"))
+                .map(|issue| (issue.rule_name, issue.path))
+                .collect();
+            found.sort();
+            found
+        };
+
+        let found = rule_paths(serde_json::json!({"source_modules": ["app"], "rules": rules}));
+        assert_eq!(
+            found,
+            [("Forbidden".to_string(), "app/src/conftest.py".to_string())]
+        );
+
+        // Without the exemption conftest.py is an ordinary module
+        let found = rule_paths(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": rules,
+            "test_fixtures": [],
+        }));
+        let names: Vec<&str> = found.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Forbidden", "Linear"]);
+    }
+
+    #[test]
+    fn transitive_leaf_reports_offending_path() {
+        let project = layered_project();
+        project.write("app/util.py", "import app.src.a\n");
+        let project_config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"transitive_leaf": [{"modules": ["app.util"]}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(project_config, run).issues;
+        let found: Vec<(&str, u32, &str)> = issues
+            .iter()
+            .map(|i| (i.path.as_str(), i.line, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [(
+                "app/util.py",
+                1,
+                "imported \"app.src.a\" : leaf 'app.util' depends on 'app.src.a' via app.util -> app.src.a"
+            )]
+        );
+    }
+
+    #[test]
+    fn import_cycles_are_reported_on_the_closing_import() {
+        let project = layered_project();
+        project.write("app/high.py", "import os\n\nimport app.src.a\n");
+        let project_config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"no_cycles": [{}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(project_config, run).issues;
+        let found: Vec<(&str, u32, &str)> = issues
+            .iter()
+            .map(|i| (i.path.as_str(), i.line, i.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [(
+                "app/src/a.py",
+                1,
+                "imported \"app.high\" : import cycle app.src.a (line 1) -> app.high -> app.src.a"
+            )]
+        );
+    }
+
+    #[test]
+    fn project_rules_see_files_no_line_rule_is_concerned_with() {
+        let project = layered_project();
+        project.write("app/high.py", "import app.tests.t\n");
+        let project_config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {
+                "linear": [{"source_module": "app.src", "order": ["a"]}],
+                "no_cycles": [{}],
+            },
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(project_config, run).issues;
+        let found: Vec<(&str, &str)> = issues
+            .iter()
+            .map(|i| (i.rule_name.as_str(), i.path.as_str()))
+            .collect();
+        assert_eq!(found, [("NoCycles", "app/tests/t.py")]);
+    }
+
+    #[test]
+    fn changed_since_checks_only_changed_files() {
+        let project = layered_project();
+        project.git(&["init", "-q"]);
+        project.git(&["add", "."]);
+        project.git(&["commit", "-q", "-m", "init"]);
+        project.write("app/tests/t.py", "import os\nimport app.high\n");
+        // New files are checked before they are added to git
+        project.write("app/src/new.py", "import app.high\n");
+
+        let issues = |changed_since: &str| {
+            let run = RunConfig {
+                no_cache: Some(true),
+                changed_since: Some(changed_since.to_string()),
+                ..RunConfig::default()
+            };
+            let mut paths: Vec<String> = run_check_imports(config(&[]), run)
+                .issues
+                .into_iter()
+                .map(|issue| issue.path)
+                .collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(issues("HEAD"), ["app/src/new.py", "app/tests/t.py"]);
+        // An unknown ref falls back to a full walk
+        assert_eq!(
+            issues("no-such-ref"),
+            ["app/src/a.py", "app/src/new.py", "app/tests/t.py"]
+        );
+    }
+
+    #[test]
+    fn ignore_comment_suppresses_matching_rule() {
+        let project = layered_project();
+        project.write(
+            "app/tests/t.py",
+            "import app.high  # importee: ignore[Linear]\n",
+        );
+        project.write(
+            "app/src/a.py",
+            "import app.high  # importee: ignore[Forbidden]\n",
+        );
+        assert_eq!(issue_paths(&config(&[])), ["app/src/a.py"]);
+    }
+
+    #[test]
+    fn mistyped_submodule_is_reported_as_unresolved() {
+        let project = layered_project();
+        project.write(
+            "app/src/a.py",
+            "import app.hgih\nimport requests\nfrom app import high\n",
+        );
+        let run = RunConfig {
+            no_cache: Some(true),
+            report_unresolved: Some(true),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(config(&["**/tests/**"]), run).issues;
+        let unresolved: Vec<_> = issues
+            .iter()
+            .filter(|issue| issue.rule_name == "Unresolved")
+            .collect();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].path, "app/src/a.py");
+        assert_eq!(unresolved[0].line, 1);
+        assert!(unresolved[0]
+            .message
+            .starts_with("path not found under root"));
+    }
+
+    #[test]
+    fn fan_out_is_reported_on_the_module_file() {
+        let project = layered_project();
+        project.write("app/low.py", "");
+        project.write("app/src/a.py", "import app.high\nimport app.low\n");
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"fan": [{"max_fan_out": 1}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(config, run).issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_name, "Fan");
+        assert_eq!(issues[0].path, "app/src/a.py");
+        assert_eq!(issues[0].line, 0);
+        assert_eq!(issues[0].message, "'app.src.a' has a fan-out of 2 (max 1)");
+    }
+
+    #[test]
+    fn fan_in_of_a_package_is_reported_on_its_init_file() {
+        let project = layered_project();
+        project.write(
+            "app/src/a.py",
+            "import app.tests
+",
+        );
+        project.write(
+            "app/src/b.py",
+            "from app import tests
+",
+        );
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"fan": [{"max_fan_in": 1}]},
+        }))
+        .unwrap();
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let issues = run_check_imports(config, run).issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "app/tests/__init__.py");
+        assert_eq!(issues[0].message, "'app.tests' has a fan-in of 2 (max 1)");
+    }
+
+    #[test]
+    fn stubs_are_checked_when_included() {
+        let project = layered_project();
+        project.write("app/src/__init__.py", "");
+        project.write(
+            "app/src/typed.pyi",
+            "from typing import Any\nimport app.high\n",
+        );
+        // A stub next to its module is not checked twice
+        project.write("app/src/a.pyi", "import app.high\n");
+        let check = |include_stubs| {
+            let run = RunConfig {
+                no_cache: Some(true),
+                include_stubs: Some(include_stubs),
+                ..RunConfig::default()
+            };
+            let mut issues: Vec<(String, u32)> = run_check_imports(config(&["**/tests/**"]), run)
+                .issues
+                .into_iter()
+                .map(|issue| (issue.path, issue.line))
+                .collect();
+            issues.sort();
+            issues
+        };
+        assert_eq!(check(false), [(String::from("app/src/a.py"), 1)]);
+        assert_eq!(
+            check(true),
+            [
+                (String::from("app/src/a.py"), 1),
+                (String::from("app/src/typed.pyi"), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_files_honors_excludes_and_skips_non_modules() {
+        let project = layered_project();
+        project.write("app/__pycache__/high.cpython-312.py", "");
+        project.write("app/notes.txt", "");
+        project.write("app/src/types.pyi", "");
+        assert_eq!(
+            run_list_files(&config(&["**/tests/**"]), &RunConfig::default()),
+            [
+                "app/__init__.py",
+                "app/high.py",
+                "app/src/__init__.py",
+                "app/src/a.py"
+            ]
+        );
+    }
+
+    #[test]
+    fn configured_extensions_are_scanned_but_binaries_never_are() {
+        let project = layered_project();
+        project.write("app/src/fast.pyx", "import app.high\n");
+        project.write("app/src/page.py.jinja", "import app.high\n");
+        project.write("app/src/native.so", "");
+        let run = RunConfig {
+            no_cache: Some(true),
+            extensions: Some(vec![
+                String::from("py"),
+                String::from("pyx"),
+                String::from("py.jinja"),
+                String::from("so"),
+            ]),
+            ..RunConfig::default()
+        };
+        let project_config = config(&["**/tests/**"]);
+        assert_eq!(
+            run_list_files(&project_config, &run),
+            [
+                "app/__init__.py",
+                "app/high.py",
+                "app/src/__init__.py",
+                "app/src/a.py",
+                "app/src/fast.pyx",
+                "app/src/page.py.jinja"
+            ]
+        );
+        let mut paths: Vec<String> = run_check_imports(project_config, run)
+            .issues
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            ["app/src/a.py", "app/src/fast.pyx", "app/src/page.py.jinja"]
+        );
+    }
+
+    #[test]
+    fn progress_is_reported_after_each_file() {
+        let _project = layered_project();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let project = config(&[]);
+        let run = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let run = RunContext::new(&project, run).with_progress(sender);
+        let scanned = run_check_imports_in(project, &run).summary.files_scanned;
+        drop(run);
+        let mut reported: Vec<usize> = receiver.iter().collect();
+        reported.sort();
+        assert_eq!(reported, (1..=scanned).collect::<Vec<usize>>());
+    }
+}
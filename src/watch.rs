@@ -0,0 +1,158 @@
+//! Watch mode: re-check module files as they change, for local development.
+//! Events are debounced, so an editor saving a file in several writes yields one check.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::configs::{ProjectConfig, RunConfig};
+use crate::results::CheckResult;
+use crate::walker::run_check_file;
+
+/// Quiet period after the last event before the changed files are checked.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watch the source directories and call `on_result(path, result)` with the issues of every
+/// changed module file, by its cwd-relative path; deleted files get an empty result.
+/// `keep_going` is polled every `DEBOUNCE` and after each result; the watch ends once it
+/// returns false. Project rules need the whole graph and are not evaluated.
+pub fn watch_sources(
+    project_config: &ProjectConfig,
+    run_config: &RunConfig,
+    mut on_result: impl FnMut(&Path, CheckResult),
+    mut keep_going: impl FnMut() -> bool,
+) -> notify::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let sources: Vec<PathBuf> = if project_config.source_modules.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        project_config
+            .source_modules
+            .iter()
+            .map(|source| source.to_dir_pathbuf())
+            .filter(|dir| dir.is_dir())
+            .collect()
+    };
+    for dir in sources.iter() {
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+    }
+
+    let extensions = run_config.module_extensions();
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let mut pending: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut last_event = Instant::now();
+    while keep_going() {
+        match receiver.recv_timeout(DEBOUNCE) {
+            // Checking a file opens it, so access events would retrigger checks forever
+            Ok(Ok(event)) if event.kind.is_access() || event.kind.is_other() => {}
+            Ok(Ok(event)) => {
+                let changed = event
+                    .paths
+                    .into_iter()
+                    .filter(|path| is_module_file(path, &extensions));
+                pending.extend(changed);
+                last_event = Instant::now();
+            }
+            Ok(Err(e)) => {
+                if run_config.verbose.unwrap_or(false) {
+                    eprintln!("[watch] {}", e);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        if pending.is_empty() || last_event.elapsed() < DEBOUNCE {
+            continue;
+        }
+        for path in std::mem::take(&mut pending) {
+            let rel_path = path.strip_prefix(&cwd).unwrap_or(&path).to_path_buf();
+            let result = if rel_path.is_file() {
+                run_check_file(project_config.clone(), run_config.clone(), &rel_path)
+            } else {
+                CheckResult::new()
+            };
+            on_result(&rel_path, result);
+            if !keep_going() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Files with one of the module extensions, outside `__pycache__`.
+fn is_module_file(path: &Path, extensions: &[String]) -> bool {
+    let in_cache = path
+        .components()
+        .any(|component| component == Component::Normal("__pycache__".as_ref()));
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    !in_cache
+        && extensions.iter().any(|extension| {
+            name.strip_suffix(extension.as_str())
+                .is_some_and(|stem| stem.len() > 1 && stem.ends_with('.'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::watch_sources;
+    use crate::configs::{ProjectConfig, RunConfig};
+    use crate::test_support::TempProject;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn changed_and_deleted_files_are_rechecked() {
+        let project = TempProject::new();
+        project.write("app/__init__.py", "");
+        project.write("app/high.py", "");
+        project.write("app/low.py", "");
+        let config: ProjectConfig = serde_json::from_value(serde_json::json!({
+            "source_modules": ["app"],
+            "rules": {"linear": [{"order": ["low", "high"]}]},
+        }))
+        .unwrap();
+        let run_config = RunConfig {
+            no_cache: Some(true),
+            ..RunConfig::default()
+        };
+        let root = std::env::current_dir().unwrap();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            std::fs::write(root.join("app/low.py"), "import app.high\n").unwrap();
+            std::thread::sleep(Duration::from_millis(600));
+            std::fs::remove_file(root.join("app/low.py")).unwrap();
+        });
+
+        let results: RefCell<Vec<(PathBuf, usize)>> = RefCell::new(Vec::new());
+        let deadline = Instant::now() + Duration::from_secs(10);
+        watch_sources(
+            &config,
+            &run_config,
+            |path, result| {
+                results
+                    .borrow_mut()
+                    .push((path.to_path_buf(), result.issues.len()))
+            },
+            || results.borrow().len() < 2 && Instant::now() < deadline,
+        )
+        .unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(
+            results.into_inner(),
+            [
+                (PathBuf::from("app/low.py"), 1),
+                (PathBuf::from("app/low.py"), 0)
+            ]
+        );
+    }
+}